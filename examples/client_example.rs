@@ -113,7 +113,7 @@ async fn demonstrate_operations(
                 tracing::info!("Calculator result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -138,7 +138,7 @@ async fn demonstrate_operations(
                 tracing::info!("Echo result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
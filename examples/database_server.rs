@@ -71,6 +71,8 @@ impl ToolHandler for StoreHandler {
         Ok(ToolResult {
             content: vec![Content::text(message)],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -102,11 +104,15 @@ impl ToolHandler for RetrieveHandler {
                 Ok(ToolResult {
                     content: vec![Content::text(serde_json::to_string_pretty(&response)?)],
                     is_error: None,
+                    structured_content: None,
+                    meta: None,
                 })
             }
             None => Ok(ToolResult {
                 content: vec![Content::text(format!("No record found with ID: {}", id))],
                 is_error: Some(true),
+                structured_content: None,
+                meta: None,
             }),
         }
     }
@@ -149,6 +155,8 @@ impl ToolHandler for ListHandler {
         Ok(ToolResult {
             content: vec![Content::text(serde_json::to_string_pretty(&response)?)],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -172,10 +180,14 @@ impl ToolHandler for DeleteHandler {
             Some(_) => Ok(ToolResult {
                 content: vec![Content::text(format!("Deleted record with ID: {}", id))],
                 is_error: None,
+                structured_content: None,
+                meta: None,
             }),
             None => Ok(ToolResult {
                 content: vec![Content::text(format!("No record found with ID: {}", id))],
                 is_error: Some(true),
+                structured_content: None,
+                meta: None,
             }),
         }
     }
@@ -113,7 +113,7 @@ async fn demonstrate_http_operations(
                 tracing::info!("HTTP Calculator result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -141,7 +141,7 @@ async fn demonstrate_http_operations(
                 tracing::info!("Power operation result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
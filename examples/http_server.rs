@@ -48,6 +48,8 @@ impl ToolHandler for HttpCalculatorHandler {
                     return Ok(ToolResult {
                         content: vec![Content::text("Error: Division by zero")],
                         is_error: Some(true),
+                        structured_content: None,
+                        meta: None,
                     });
                 }
                 a / b
@@ -61,6 +63,8 @@ impl ToolHandler for HttpCalculatorHandler {
                         operation
                     ))],
                     is_error: Some(true),
+                    structured_content: None,
+                    meta: None,
                 });
             }
         };
@@ -71,6 +75,8 @@ impl ToolHandler for HttpCalculatorHandler {
                 a, operation, b, result
             ))],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
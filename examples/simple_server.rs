@@ -59,6 +59,8 @@ impl ToolHandler for CalculatorHandler {
                     return Ok(ToolResult {
                         content: vec![Content::text("Error: Division by zero")],
                         is_error: Some(true),
+                        structured_content: None,
+                        meta: None,
                     });
                 }
                 a / b
@@ -70,6 +72,8 @@ impl ToolHandler for CalculatorHandler {
                         operation
                     ))],
                     is_error: Some(true),
+                    structured_content: None,
+                    meta: None,
                 });
             }
         };
@@ -80,6 +84,8 @@ impl ToolHandler for CalculatorHandler {
                 a, operation, b, result
             ))],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -114,6 +120,8 @@ impl ToolHandler for EchoHandler {
         Ok(ToolResult {
             content: vec![Content::text(formatted_message)],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -184,7 +192,7 @@ impl ResourceHandler for FileSystemHandler {
         Ok(files
             .keys()
             .map(|uri| {
-                let name = uri.split('/').last().unwrap_or(uri);
+                let name = uri.split('/').next_back().unwrap_or(uri);
                 let mime_type = if uri.ends_with(".json") {
                     Some("application/json".to_string())
                 } else if uri.ends_with(".csv") {
@@ -261,6 +269,7 @@ impl PromptHandler for CodeReviewPromptHandler {
                     content: PromptContent::text(user_prompt),
                 },
             ],
+            meta: None,
         })
     }
 }
@@ -315,6 +324,7 @@ impl PromptHandler for DocumentationPromptHandler {
                     content: PromptContent::text(user_prompt),
                 },
             ],
+            meta: None,
         })
     }
 }
@@ -334,6 +344,7 @@ async fn main() -> McpResult<()> {
         request_timeout_ms: 30000,
         validate_requests: true,
         enable_logging: true,
+        normalize_tool_arguments: true,
     };
 
     let mut server = McpServer::with_config(
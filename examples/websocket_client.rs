@@ -109,7 +109,7 @@ async fn demonstrate_websocket_operations(
                 tracing::info!("WebSocket Echo result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -137,7 +137,7 @@ async fn demonstrate_websocket_operations(
                 tracing::info!("WebSocket Broadcast result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -165,7 +165,7 @@ async fn demonstrate_websocket_operations(
                 tracing::info!("WebSocket Chat result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -196,7 +196,7 @@ async fn demonstrate_websocket_operations(
                 tracing::info!("WebSocket Chat (Bob) result:");
                 for content in &result.content {
                     match content {
-                        Content::Text { text } => {
+                        Content::Text { text, .. } => {
                             tracing::info!("  {}", text);
                         }
                         _ => tracing::info!("  (non-text content)"),
@@ -72,6 +72,8 @@ impl ToolHandler for WebSocketEchoHandler {
         Ok(ToolResult {
             content: vec![Content::text(response)],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -112,6 +114,8 @@ impl ToolHandler for WebSocketChatHandler {
                 room, username, message
             ))],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -0,0 +1,191 @@
+//! Code generation for tools, prompts and resources
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Result;
+
+use crate::handler::{call_site_error, AttrArgs, Handler};
+
+/// Wrap `body` in an async closure taking `input`, cloning `this` for methods
+fn closure(method: bool, input: TokenStream, body: TokenStream) -> TokenStream {
+    if method {
+        quote! {{
+            let this = ::std::sync::Arc::clone(&this);
+            move |#input| {
+                let this = ::std::sync::Arc::clone(&this);
+                async move { #body }
+            }
+        }}
+    } else {
+        quote! {
+            move |#input| async move { #body }
+        }
+    }
+}
+
+/// The expression calling the handler function with the given arguments
+fn call(handler: &Handler, method: bool, args: TokenStream) -> TokenStream {
+    let fn_name = &handler.fn_name;
+    if method {
+        quote! { this.#fn_name(#args).await }
+    } else {
+        quote! { #fn_name(#args).await }
+    }
+}
+
+fn optional_string(value: &Option<String>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value.to_string()) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// Generate an expression evaluating to a `Tool`
+pub fn tool(handler: &Handler, method: bool) -> Result<TokenStream> {
+    handler.require_owned_params()?;
+
+    let name = &handler.name;
+    let description = optional_string(&handler.description);
+    let idents: Vec<_> = handler.params.iter().map(|p| &p.ident).collect();
+    let fields = handler.params.iter().map(|param| {
+        let ident = &param.ident;
+        let ty = &param.ty;
+        let doc = param.description.iter();
+        quote! { #(#[doc = #doc])* #ident: #ty }
+    });
+
+    let body = call(handler, method, quote! { #(__args.#idents),* });
+    let closure = closure(method, quote! { __args: __McpArgs }, body);
+
+    Ok(quote! {{
+        #[derive(
+            ::mcp_protocol_sdk::__private::serde::Deserialize,
+            ::mcp_protocol_sdk::__private::schemars::JsonSchema,
+        )]
+        #[serde(crate = "::mcp_protocol_sdk::__private::serde")]
+        #[schemars(crate = "::mcp_protocol_sdk::__private::schemars")]
+        struct __McpArgs {
+            #(#fields,)*
+        }
+
+        let handler = ::mcp_protocol_sdk::core::typed_tool::TypedToolHandler::new(#closure);
+        let schema = handler.input_schema();
+        ::mcp_protocol_sdk::core::tool::Tool::new(
+            #name.to_string(),
+            #description,
+            schema,
+            handler,
+        )
+    }})
+}
+
+/// Generate an expression evaluating to a `Prompt`
+pub fn prompt(handler: &Handler, method: bool) -> Result<TokenStream> {
+    handler.require_owned_params()?;
+
+    let name = &handler.name;
+    let description = optional_string(&handler.description);
+    let idents: Vec<_> = handler.params.iter().map(|p| &p.ident).collect();
+    let types = handler.params.iter().map(|p| &p.ty);
+    let arguments = if handler.params.is_empty() {
+        quote! { ::std::option::Option::None }
+    } else {
+        let infos = handler.params.iter().map(|param| {
+            let name = param.ident.to_string();
+            let description = optional_string(&param.description);
+            let required = !param.is_optional();
+            quote! {
+                ::mcp_protocol_sdk::protocol::types::PromptArgument {
+                    name: #name.to_string(),
+                    description: #description,
+                    required: #required,
+                }
+            }
+        });
+        quote! { ::std::option::Option::Some(::std::vec![#(#infos),*]) }
+    };
+
+    let call = call(handler, method, quote! { #(__args.#idents),* });
+    let body = quote! {
+        let __args: __McpArgs = ::mcp_protocol_sdk::__private::parse_arguments(arguments)?;
+        #call
+    };
+    let closure = closure(
+        method,
+        quote! {
+            arguments: ::std::collections::HashMap<
+                ::std::string::String,
+                ::mcp_protocol_sdk::__private::serde_json::Value,
+            >,
+            _: ::mcp_protocol_sdk::core::context::RequestContext
+        },
+        body,
+    );
+
+    Ok(quote! {{
+        #[derive(::mcp_protocol_sdk::__private::serde::Deserialize)]
+        #[serde(crate = "::mcp_protocol_sdk::__private::serde")]
+        struct __McpArgs {
+            #(#idents: #types,)*
+        }
+
+        ::mcp_protocol_sdk::core::prompt::Prompt::new(
+            ::mcp_protocol_sdk::protocol::types::PromptInfo {
+                name: #name.to_string(),
+                description: #description,
+                arguments: #arguments,
+            },
+            ::mcp_protocol_sdk::core::prompt::FnPromptHandler::new(#closure),
+        )
+    }})
+}
+
+/// Generate an expression evaluating to a `Resource`
+pub fn resource(handler: &Handler, args: &AttrArgs, method: bool) -> Result<TokenStream> {
+    let uri = args
+        .uri
+        .as_ref()
+        .ok_or_else(|| call_site_error("resources require a `uri = \"...\"` argument"))?;
+    let name = &handler.name;
+    let description = optional_string(&handler.description);
+    let mime_type = optional_string(&args.mime_type);
+    let handler_mime_type = args
+        .mime_type
+        .as_ref()
+        .map(|mime_type| quote! { .mime_type(#mime_type) });
+
+    let uri_arg = match handler.params.as_slice() {
+        [] => quote! {},
+        [param] if matches!(param.ty, syn::Type::Reference(_)) => quote! { &__uri },
+        [_] => quote! { __uri },
+        [_, extra, ..] => {
+            return Err(syn::Error::new_spanned(
+                &extra.ident,
+                "resource handlers take at most one parameter, the requested URI",
+            ))
+        }
+    };
+
+    let body = call(handler, method, uri_arg);
+    let closure = closure(
+        method,
+        quote! {
+            __uri: ::std::string::String,
+            _: ::mcp_protocol_sdk::core::context::RequestContext
+        },
+        body,
+    );
+
+    Ok(quote! {{
+        ::mcp_protocol_sdk::core::resource::Resource::new(
+            ::mcp_protocol_sdk::protocol::types::ResourceInfo {
+                uri: #uri.to_string(),
+                name: #name.to_string(),
+                description: #description,
+                mime_type: #mime_type,
+            },
+            ::mcp_protocol_sdk::core::resource::FnResourceHandler::new(#closure)
+                #handler_mime_type,
+        )
+    }})
+}
@@ -0,0 +1,245 @@
+//! Parsing of handler functions and macro arguments
+
+use proc_macro2::Span;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Error, Expr, ExprLit, FnArg, Ident, Lit, Meta, MetaNameValue, Pat, Result,
+    Signature, Token, Type,
+};
+
+/// Key-value arguments of a macro attribute, e.g. `name = "add", uri = "file:///a"`
+#[derive(Default)]
+pub struct AttrArgs {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub uri: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl AttrArgs {
+    /// Parse attribute arguments, accepting only the given keys
+    pub fn parse(tokens: proc_macro2::TokenStream, allowed: &[&str]) -> Result<Self> {
+        let mut args = AttrArgs::default();
+        let pairs = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(tokens)?;
+
+        for pair in pairs {
+            let key = pair
+                .path
+                .get_ident()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            if !allowed.contains(&key.as_str()) {
+                return Err(Error::new_spanned(
+                    &pair.path,
+                    format!("unknown argument, expected one of: {}", allowed.join(", ")),
+                ));
+            }
+
+            let value = string_value(&pair.value)?;
+            match key.as_str() {
+                "name" => args.name = Some(value),
+                "description" => args.description = Some(value),
+                "uri" => args.uri = Some(value),
+                "mime_type" => args.mime_type = Some(value),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// Parse the arguments of an attribute such as `#[tool(name = "add")]`
+    pub fn from_attribute(attr: &Attribute, allowed: &[&str]) -> Result<Self> {
+        match &attr.meta {
+            Meta::Path(_) => Ok(AttrArgs::default()),
+            Meta::List(list) => Self::parse(list.tokens.clone(), allowed),
+            Meta::NameValue(_) => Err(Error::new_spanned(attr, "expected a list of arguments")),
+        }
+    }
+}
+
+/// A parameter of a handler function
+pub struct Param {
+    pub ident: Ident,
+    pub ty: Type,
+    pub description: Option<String>,
+}
+
+impl Param {
+    /// Whether the parameter may be omitted by the client
+    pub fn is_optional(&self) -> bool {
+        match &self.ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Option"),
+            _ => false,
+        }
+    }
+}
+
+/// An async function exposed as a tool, prompt or resource
+pub struct Handler {
+    pub fn_name: Ident,
+    pub name: String,
+    pub description: Option<String>,
+    pub params: Vec<Param>,
+}
+
+impl Handler {
+    /// Parse a handler from a function signature, removing `#[arg(...)]` attributes
+    ///
+    /// `method` selects whether the function must take `&self` (inside `#[mcp_server]`)
+    /// or must not have a receiver at all.
+    pub fn parse(
+        sig: &mut Signature,
+        attrs: &[Attribute],
+        args: &AttrArgs,
+        method: bool,
+    ) -> Result<Self> {
+        if sig.asyncness.is_none() {
+            return Err(Error::new_spanned(
+                sig.fn_token,
+                "MCP handlers must be async functions",
+            ));
+        }
+        if !sig.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &sig.generics,
+                "MCP handlers cannot be generic",
+            ));
+        }
+
+        let mut params = Vec::new();
+        let mut has_receiver = false;
+        for input in sig.inputs.iter_mut() {
+            match input {
+                FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_none() || receiver.mutability.is_some() {
+                        return Err(Error::new_spanned(
+                            receiver,
+                            "MCP handler methods must take `&self`",
+                        ));
+                    }
+                    has_receiver = true;
+                }
+                FnArg::Typed(typed) => {
+                    let description = take_arg_description(&mut typed.attrs)?;
+                    let ident = match typed.pat.as_ref() {
+                        Pat::Ident(pat) => pat.ident.clone(),
+                        other => {
+                            return Err(Error::new_spanned(
+                                other,
+                                "MCP handler parameters must be plain identifiers",
+                            ))
+                        }
+                    };
+                    params.push(Param {
+                        ident,
+                        ty: (*typed.ty).clone(),
+                        description,
+                    });
+                }
+            }
+        }
+
+        if method && !has_receiver {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "MCP handler methods must take `&self`",
+            ));
+        }
+        if !method && has_receiver {
+            return Err(Error::new_spanned(
+                &sig.ident,
+                "use `#[mcp_server]` on the impl block to expose methods",
+            ));
+        }
+
+        Ok(Self {
+            fn_name: sig.ident.clone(),
+            name: args.name.clone().unwrap_or_else(|| sig.ident.to_string()),
+            description: args.description.clone().or_else(|| doc_comment(attrs)),
+            params,
+        })
+    }
+
+    /// Reject parameters that cannot be deserialized into an owned argument struct
+    pub fn require_owned_params(&self) -> Result<()> {
+        for param in &self.params {
+            if let Type::Reference(reference) = &param.ty {
+                return Err(Error::new_spanned(
+                    reference,
+                    "MCP handler parameters must be owned types",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Extract a string literal from an attribute value
+fn string_value(expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit.value()),
+        other => Err(Error::new_spanned(other, "expected a string literal")),
+    }
+}
+
+/// Remove `#[arg(description = "...")]` from a parameter and return the description
+fn take_arg_description(attrs: &mut Vec<Attribute>) -> Result<Option<String>> {
+    let mut description = None;
+    let mut error = None;
+
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("arg") {
+            return true;
+        }
+        match AttrArgs::from_attribute(attr, &["description"]) {
+            Ok(args) => description = args.description,
+            Err(e) => error = Some(e),
+        }
+        false
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(description),
+    }
+}
+
+/// Join the doc comment lines of an item into a description
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit), ..
+                    }),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    let description = lines.join("\n").trim().to_string();
+    (!description.is_empty()).then_some(description)
+}
+
+/// Build an error pointing at the macro invocation
+pub fn call_site_error(message: &str) -> Error {
+    Error::new(Span::call_site(), message)
+}
@@ -0,0 +1,208 @@
+//! Procedural macros for the MCP Rust SDK
+//!
+//! These macros are re-exported by `mcp-protocol-sdk` when its `macros` feature is
+//! enabled and should be used through that crate rather than depending on this one.
+//!
+//! - `#[mcp_tool]` turns an async function into a tool. Doc comments become the tool
+//!   description and parameters become properties of the input schema.
+//! - `#[mcp_prompt]` turns an async function returning a `PromptResult` into a prompt.
+//! - `#[mcp_resource(uri = "...")]` turns an async function into a resource.
+//! - `#[mcp_server]` on an impl block generates a `register` method adding every
+//!   method marked `#[tool]`, `#[prompt]` or `#[resource(uri = "...")]` to a server.
+//!
+//! Parameters can be described with `#[arg(description = "...")]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, ImplItem, ItemFn, ItemImpl, Result};
+
+mod expand;
+mod handler;
+
+use handler::{AttrArgs, Handler};
+
+const TOOL_ARGS: &[&str] = &["name", "description"];
+const PROMPT_ARGS: &[&str] = &["name", "description"];
+const RESOURCE_ARGS: &[&str] = &["uri", "name", "description", "mime_type"];
+
+/// Declare an async function as an MCP tool
+///
+/// Generates a `<name>_tool()` function returning the `Tool`, which can be added with
+/// `McpServer::register_tool`. Accepts optional `name = "..."` and
+/// `description = "..."` arguments overriding the function name and doc comment.
+#[proc_macro_attribute]
+pub fn mcp_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    expand_fn(attr.into(), function, TOOL_ARGS, "tool", |handler, _| {
+        expand::tool(handler, false)
+    })
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Declare an async function returning a `PromptResult` as an MCP prompt
+///
+/// Generates a `<name>_prompt()` function returning the `Prompt`, which can be added
+/// with `McpServer::register_prompt`. Parameters become prompt arguments, required
+/// unless their type is an `Option`.
+#[proc_macro_attribute]
+pub fn mcp_prompt(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    expand_fn(
+        attr.into(),
+        function,
+        PROMPT_ARGS,
+        "prompt",
+        |handler, _| expand::prompt(handler, false),
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Declare an async function as an MCP resource
+///
+/// Requires a `uri = "..."` argument and accepts optional `name`, `description` and
+/// `mime_type`. The function may take the requested URI as its only parameter and
+/// must return a value implementing `IntoResourceContents`. Generates a
+/// `<name>_resource()` function returning the `Resource`.
+#[proc_macro_attribute]
+pub fn mcp_resource(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    expand_fn(
+        attr.into(),
+        function,
+        RESOURCE_ARGS,
+        "resource",
+        |handler, args| expand::resource(handler, args, false),
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Generate a `register` method for the tools, prompts and resources of an impl block
+///
+/// Methods marked `#[tool]`, `#[prompt]` or `#[resource(uri = "...")]` must be async and
+/// take `&self`; the markers accept the same arguments as the standalone macros. The
+/// generated `register(self, server)` moves the value into an `Arc` shared by all of them.
+#[proc_macro_attribute]
+pub fn mcp_server(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[mcp_server] takes no arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    let item_impl = parse_macro_input!(item as ItemImpl);
+    expand_server(item_impl)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expand a standalone handler function into itself plus a `<name>_<kind>()` constructor
+fn expand_fn(
+    attr: TokenStream2,
+    mut function: ItemFn,
+    allowed: &[&str],
+    kind: &str,
+    generate: impl FnOnce(&Handler, &AttrArgs) -> Result<TokenStream2>,
+) -> Result<TokenStream2> {
+    let args = AttrArgs::parse(attr, allowed)?;
+    let handler = Handler::parse(&mut function.sig, &function.attrs, &args, false)?;
+    let expr = generate(&handler, &args)?;
+
+    let vis = &function.vis;
+    let fn_name = &handler.fn_name;
+    let constructor = format_ident!("{}_{}", fn_name, kind);
+    let return_type = match kind {
+        "tool" => quote! { ::mcp_protocol_sdk::core::tool::Tool },
+        "prompt" => quote! { ::mcp_protocol_sdk::core::prompt::Prompt },
+        _ => quote! { ::mcp_protocol_sdk::core::resource::Resource },
+    };
+    let doc = format!("Build the MCP {} declared by [`{}`]", kind, fn_name);
+
+    Ok(quote! {
+        #function
+
+        #[doc = #doc]
+        #vis fn #constructor() -> #return_type #expr
+    })
+}
+
+/// Expand an impl block marked `#[mcp_server]`
+fn expand_server(mut item_impl: ItemImpl) -> Result<TokenStream2> {
+    let mut registrations = Vec::new();
+
+    for item in item_impl.items.iter_mut() {
+        let ImplItem::Fn(method) = item else {
+            continue;
+        };
+
+        let mut marker = None;
+        method.attrs.retain(|attr| {
+            let kind = ["tool", "prompt", "resource"]
+                .into_iter()
+                .find(|kind| attr.path().is_ident(kind));
+            match kind {
+                Some(kind) if marker.is_none() => {
+                    marker = Some((kind, attr.clone()));
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        let Some((kind, attr)) = marker else {
+            continue;
+        };
+
+        let allowed = match kind {
+            "tool" => TOOL_ARGS,
+            "prompt" => PROMPT_ARGS,
+            _ => RESOURCE_ARGS,
+        };
+        let args = AttrArgs::from_attribute(&attr, allowed)?;
+        let handler = Handler::parse(&mut method.sig, &method.attrs, &args, true)?;
+
+        registrations.push(match kind {
+            "tool" => {
+                let expr = expand::tool(&handler, true)?;
+                quote! { server.register_tool(#expr).await?; }
+            }
+            "prompt" => {
+                let expr = expand::prompt(&handler, true)?;
+                quote! { server.register_prompt(#expr).await?; }
+            }
+            _ => {
+                let expr = expand::resource(&handler, &args, true)?;
+                quote! { server.register_resource(#expr).await?; }
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    let self_ty = &item_impl.self_ty;
+
+    Ok(quote! {
+        #item_impl
+
+        impl #impl_generics #self_ty #where_clause {
+            /// Register the tools, prompts and resources declared in this impl block
+            pub async fn register(
+                self,
+                server: &::mcp_protocol_sdk::server::McpServer,
+            ) -> ::mcp_protocol_sdk::McpResult<()>
+            where
+                Self: ::std::marker::Send + ::std::marker::Sync + 'static,
+            {
+                let this = ::std::sync::Arc::new(self);
+                #(#registrations)*
+                let _ = this;
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
@@ -0,0 +1,20 @@
+//! Support code for the procedural macros
+//!
+//! Everything in this module is an implementation detail of the code generated by
+//! `#[mcp_tool]`, `#[mcp_prompt]`, `#[mcp_resource]` and `#[mcp_server]` and may change
+//! without notice.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::core::error::McpResult;
+
+pub use schemars;
+pub use serde;
+pub use serde_json;
+
+/// Deserialize macro-generated argument structs
+pub fn parse_arguments<T: DeserializeOwned>(arguments: HashMap<String, Value>) -> McpResult<T> {
+    crate::core::typed_tool::parse_arguments(arguments)
+}
@@ -0,0 +1,211 @@
+//! Batched requests
+//!
+//! A [`Batch`] sends several requests to the server as a single JSON-RPC batch
+//! message, saving round-trips on high-latency links. The server processes the
+//! requests concurrently and each call gets its own result.
+//!
+//! # Examples
+//! ```rust,no_run
+//! use mcp_protocol_sdk::client::McpClient;
+//! use mcp_protocol_sdk::protocol::messages::{ListPromptsResult, ListToolsResult};
+//! use mcp_protocol_sdk::McpResult;
+//!
+//! # async fn discover(client: &McpClient) -> McpResult<()> {
+//! let mut results = client.batch().list_tools().list_prompts().send().await?;
+//! let tools: ListToolsResult = results.take(0)?;
+//! let prompts: ListPromptsResult = results.take(1)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::client::McpClient;
+use crate::core::error::{McpError, McpResult};
+use crate::protocol::{messages::*, types::*};
+
+/// Builder collecting the requests of a batch
+///
+/// Created with [`McpClient::batch`].
+pub struct Batch<'a> {
+    client: &'a McpClient,
+    calls: Vec<(String, Option<Value>)>,
+    error: Option<McpError>,
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(client: &'a McpClient) -> Self {
+        Self {
+            client,
+            calls: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Add a request with arbitrary parameters
+    ///
+    /// # Arguments
+    /// * `method` - Method name of the request
+    /// * `params` - Optional request parameters
+    pub fn request<P: Serialize>(mut self, method: &str, params: Option<P>) -> Self {
+        match params.map(serde_json::to_value).transpose() {
+            Ok(params) => self.calls.push((method.to_string(), params)),
+            Err(e) => {
+                self.error.get_or_insert(McpError::Serialization(e));
+            }
+        }
+        self
+    }
+
+    /// Add a `tools/list` request
+    pub fn list_tools(self) -> Self {
+        self.request(methods::TOOLS_LIST, Some(ListToolsParams::default()))
+    }
+
+    /// Add a `tools/call` request
+    pub fn call_tool(self, name: String, arguments: Option<HashMap<String, Value>>) -> Self {
+        self.request(
+            methods::TOOLS_CALL,
+            Some(CallToolParams {
+                name,
+                arguments,
+                meta: None,
+            }),
+        )
+    }
+
+    /// Add a `resources/list` request
+    pub fn list_resources(self) -> Self {
+        self.request(
+            methods::RESOURCES_LIST,
+            Some(ListResourcesParams::default()),
+        )
+    }
+
+    /// Add a `resources/read` request
+    pub fn read_resource(self, uri: String) -> Self {
+        self.request(
+            methods::RESOURCES_READ,
+            Some(ReadResourceParams { uri, meta: None }),
+        )
+    }
+
+    /// Add a `prompts/list` request
+    pub fn list_prompts(self) -> Self {
+        self.request(methods::PROMPTS_LIST, Some(ListPromptsParams::default()))
+    }
+
+    /// Add a `prompts/get` request
+    pub fn get_prompt(self, name: String, arguments: Option<HashMap<String, Value>>) -> Self {
+        self.request(
+            methods::PROMPTS_GET,
+            Some(GetPromptParams::new(name, arguments)),
+        )
+    }
+
+    /// Get the number of requests in the batch
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Check whether the batch has no requests
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Send the batch and wait for every response
+    ///
+    /// # Returns
+    /// Result containing the result of each call, in the order the calls were added,
+    /// or an error if the batch as a whole could not be sent
+    pub async fn send(self) -> McpResult<BatchResults> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.calls.is_empty() {
+            return Ok(BatchResults {
+                results: Vec::new(),
+            });
+        }
+        self.client.ensure_connected().await?;
+
+        let mut requests = Vec::with_capacity(self.calls.len());
+        for (method, params) in self.calls {
+            let id = self.client.next_request_id();
+            requests.push(JsonRpcRequest::new(id, method, params)?);
+        }
+        let ids: Vec<RequestId> = requests.iter().map(|request| request.id.clone()).collect();
+
+        let mut responses: HashMap<RequestId, JsonRpcResponse> = self
+            .client
+            .send_batch(requests)
+            .await?
+            .into_iter()
+            .filter_map(|response| Some((response.id.clone()?, response)))
+            .collect();
+
+        let results = ids
+            .into_iter()
+            .map(|id| {
+                Some(match responses.remove(&id) {
+                    Some(response) => self.client.handle_response(response),
+                    None => Err(McpError::protocol(format!(
+                        "Missing response to batched request {}",
+                        id
+                    ))),
+                })
+            })
+            .collect();
+
+        Ok(BatchResults { results })
+    }
+}
+
+/// Results of the calls in a batch, in the order the calls were added
+#[derive(Debug)]
+pub struct BatchResults {
+    results: Vec<Option<McpResult<Value>>>,
+}
+
+impl BatchResults {
+    /// Get the number of results
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Check whether there are no results
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Take the result of the call at `index` and deserialize it
+    ///
+    /// # Arguments
+    /// * `index` - Position of the call in the batch
+    ///
+    /// # Returns
+    /// Result containing the deserialized result, or the error the call failed with
+    pub fn take<T: DeserializeOwned>(&mut self, index: usize) -> McpResult<T> {
+        let result = self
+            .results
+            .get_mut(index)
+            .ok_or_else(|| McpError::validation(format!("No call at batch index {}", index)))?
+            .take()
+            .ok_or_else(|| {
+                McpError::validation(format!("Result at batch index {} was already taken", index))
+            })?;
+        Ok(serde_json::from_value(result?)?)
+    }
+
+    /// Get the raw results of all calls
+    pub fn into_results(self) -> Vec<McpResult<Value>> {
+        self.results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| Err(McpError::validation("Result was already taken")))
+            })
+            .collect()
+    }
+}
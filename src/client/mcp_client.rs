@@ -4,14 +4,17 @@
 //! initialize connections, and perform operations like calling tools, reading resources,
 //! and executing prompts according to the Model Context Protocol specification.
 
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
+use crate::client::batch::Batch;
 use crate::core::error::{McpError, McpResult};
 use crate::protocol::{messages::*, types::*, validation::*};
-use crate::transport::traits::Transport;
+use crate::transport::traits::{RequestCallback, Transport};
 
 /// Configuration for the MCP client
 #[derive(Debug, Clone)]
@@ -26,6 +29,10 @@ pub struct ClientConfig {
     pub validate_requests: bool,
     /// Whether to validate all incoming responses
     pub validate_responses: bool,
+    /// Prefix for string request IDs; integer IDs are used when unset
+    ///
+    /// Useful behind proxies that rewrite integer IDs as strings.
+    pub request_id_prefix: Option<String>,
 }
 
 impl Default for ClientConfig {
@@ -36,10 +43,16 @@ impl Default for ClientConfig {
             retry_delay_ms: 1000,
             validate_requests: true,
             validate_responses: true,
+            request_id_prefix: None,
         }
     }
 }
 
+/// Handler for the requests a server sends to the client
+type ServerRequestHandlerFn = Arc<
+    dyn Fn(ServerRequest) -> Pin<Box<dyn Future<Output = McpResult<Value>> + Send>> + Send + Sync,
+>;
+
 /// Main MCP client implementation
 pub struct McpClient {
     /// Client information
@@ -54,8 +67,10 @@ pub struct McpClient {
     server_capabilities: Arc<RwLock<Option<ServerCapabilities>>>,
     /// Server information (available after initialization)
     server_info: Arc<RwLock<Option<ServerInfo>>>,
-    /// Request ID counter
-    request_counter: Arc<Mutex<u64>>,
+    /// Request ID generator, kept across reconnects
+    request_ids: Arc<RequestIdGenerator>,
+    /// Handler for requests from the server
+    server_request_handler: Option<ServerRequestHandlerFn>,
     /// Connection state
     connected: Arc<RwLock<bool>>,
 }
@@ -70,7 +85,8 @@ impl McpClient {
             transport: Arc::new(Mutex::new(None)),
             server_capabilities: Arc::new(RwLock::new(None)),
             server_info: Arc::new(RwLock::new(None)),
-            request_counter: Arc::new(Mutex::new(0)),
+            request_ids: Arc::new(RequestIdGenerator::new()),
+            server_request_handler: None,
             connected: Arc::new(RwLock::new(false)),
         }
     }
@@ -78,6 +94,9 @@ impl McpClient {
     /// Create a new MCP client with custom configuration
     pub fn with_config(name: String, version: String, config: ClientConfig) -> Self {
         let mut client = Self::new(name, version);
+        if let Some(prefix) = &config.request_id_prefix {
+            client.request_ids = Arc::new(RequestIdGenerator::with_prefix(prefix.clone()));
+        }
         client.config = config;
         client
     }
@@ -87,6 +106,22 @@ impl McpClient {
         self.capabilities = capabilities;
     }
 
+    /// Handle the requests the server sends, such as `roots/list` or `sampling/createMessage`
+    ///
+    /// The handler applies to transports connected afterwards. `ping` requests are
+    /// answered by the client itself, and other requests arriving while no handler is
+    /// set are answered with a method-not-found error.
+    ///
+    /// # Arguments
+    /// * `handler` - Function returning the result of a request, or the error to answer with
+    pub fn on_server_request<F, Fut>(&mut self, handler: F)
+    where
+        F: Fn(ServerRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<Value>> + Send + 'static,
+    {
+        self.server_request_handler = Some(Arc::new(move |request| Box::pin(handler(request))));
+    }
+
     /// Get client information
     pub fn info(&self) -> &ClientInfo {
         &self.info
@@ -129,6 +164,9 @@ impl McpClient {
     where
         T: Transport + 'static,
     {
+        let mut transport = transport;
+        transport.set_request_handler(self.request_callback());
+
         // Set the transport
         {
             let mut transport_guard = self.transport.lock().await;
@@ -147,6 +185,42 @@ impl McpClient {
         Ok(init_result)
     }
 
+    /// Build the callback a transport answers the server's requests with
+    fn request_callback(&self) -> RequestCallback {
+        let handler = self.server_request_handler.clone();
+        Arc::new(move |request: JsonRpcRequest| {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                let id = request.id.clone();
+                let method = request.method.clone();
+                let result = match ServerRequest::try_from(request) {
+                    Ok(ServerRequest::Ping(_)) => {
+                        serde_json::to_value(PingResult::default()).map_err(McpError::serialization)
+                    }
+                    Ok(request) => match handler {
+                        Some(handler) => handler(request).await,
+                        None => Err(McpError::MethodNotFound(method)),
+                    },
+                    Err(error) => Err(error),
+                };
+
+                let response = result
+                    .and_then(|result| Ok(JsonRpcResponse::success(id.clone(), result)?))
+                    .unwrap_or_else(|error| {
+                        let JsonRpcError {
+                            code,
+                            message,
+                            data,
+                        } = error.to_rpc_error();
+                        JsonRpcResponse::error(id, code, message, data)
+                    });
+                let _ = sender.send(response);
+            });
+            receiver
+        })
+    }
+
     /// Disconnect from the server
     pub async fn disconnect(&self) -> McpResult<()> {
         // Close the transport
@@ -186,7 +260,7 @@ impl McpClient {
         );
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::INITIALIZE.to_string(),
             Some(params),
         )?;
@@ -227,9 +301,9 @@ impl McpClient {
     pub async fn list_tools(&self, cursor: Option<String>) -> McpResult<ListToolsResult> {
         self.ensure_connected().await?;
 
-        let params = ListToolsParams { cursor };
+        let params = ListToolsParams { cursor, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::TOOLS_LIST.to_string(),
             Some(params),
         )?;
@@ -244,16 +318,32 @@ impl McpClient {
         name: String,
         arguments: Option<HashMap<String, Value>>,
     ) -> McpResult<CallToolResult> {
-        self.ensure_connected().await?;
+        self.call_tool_with_params(CallToolParams::new(name, arguments))
+            .await
+    }
 
-        let params = CallToolParams::new(name, arguments);
+    /// Call a tool on the server, sending `meta` as the request's `_meta`
+    pub async fn call_tool_with_meta(
+        &self,
+        name: String,
+        arguments: Option<HashMap<String, Value>>,
+        meta: Map<String, Value>,
+    ) -> McpResult<CallToolResult> {
+        let mut params = CallToolParams::new(name, arguments);
+        params.meta = Some(meta);
+        self.call_tool_with_params(params).await
+    }
+
+    /// Call a tool on the server with fully specified parameters
+    pub async fn call_tool_with_params(&self, params: CallToolParams) -> McpResult<CallToolResult> {
+        self.ensure_connected().await?;
 
         if self.config.validate_requests {
             validate_call_tool_params(&params)?;
         }
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::TOOLS_CALL.to_string(),
             Some(params),
         )?;
@@ -262,6 +352,42 @@ impl McpClient {
         self.handle_response(response)
     }
 
+    /// Call a tool and deserialize its structured content into `T`
+    ///
+    /// # Returns
+    /// Result containing the structured result, or an error if the tool failed or
+    /// returned no structured content matching `T`
+    pub async fn call_tool_structured<T: serde::de::DeserializeOwned>(
+        &self,
+        name: String,
+        arguments: Option<HashMap<String, Value>>,
+    ) -> McpResult<T> {
+        let result = self.call_tool(name.clone(), arguments).await?;
+
+        if result.is_error == Some(true) {
+            let message = result
+                .content
+                .iter()
+                .find_map(|content| match content {
+                    Content::Text { text, .. } => Some(text.as_str()),
+                    _ => None,
+                })
+                .unwrap_or("no details");
+            return Err(McpError::protocol(format!(
+                "Tool '{}' failed: {}",
+                name, message
+            )));
+        }
+
+        match result.structured_content_as::<T>() {
+            Some(value) => Ok(value?),
+            None => Err(McpError::protocol(format!(
+                "Tool '{}' returned no structured content",
+                name
+            ))),
+        }
+    }
+
     // ========================================================================
     // Resource Operations
     // ========================================================================
@@ -270,9 +396,9 @@ impl McpClient {
     pub async fn list_resources(&self, cursor: Option<String>) -> McpResult<ListResourcesResult> {
         self.ensure_connected().await?;
 
-        let params = ListResourcesParams { cursor };
+        let params = ListResourcesParams { cursor, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::RESOURCES_LIST.to_string(),
             Some(params),
         )?;
@@ -292,7 +418,7 @@ impl McpClient {
         }
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::RESOURCES_READ.to_string(),
             Some(params),
         )?;
@@ -305,9 +431,9 @@ impl McpClient {
     pub async fn subscribe_resource(&self, uri: String) -> McpResult<SubscribeResourceResult> {
         self.ensure_connected().await?;
 
-        let params = SubscribeResourceParams { uri };
+        let params = SubscribeResourceParams { uri, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::RESOURCES_SUBSCRIBE.to_string(),
             Some(params),
         )?;
@@ -320,9 +446,9 @@ impl McpClient {
     pub async fn unsubscribe_resource(&self, uri: String) -> McpResult<UnsubscribeResourceResult> {
         self.ensure_connected().await?;
 
-        let params = UnsubscribeResourceParams { uri };
+        let params = UnsubscribeResourceParams { uri, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::RESOURCES_UNSUBSCRIBE.to_string(),
             Some(params),
         )?;
@@ -339,9 +465,9 @@ impl McpClient {
     pub async fn list_prompts(&self, cursor: Option<String>) -> McpResult<ListPromptsResult> {
         self.ensure_connected().await?;
 
-        let params = ListPromptsParams { cursor };
+        let params = ListPromptsParams { cursor, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::PROMPTS_LIST.to_string(),
             Some(params),
         )?;
@@ -365,7 +491,7 @@ impl McpClient {
         }
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::PROMPTS_GET.to_string(),
             Some(params),
         )?;
@@ -404,7 +530,7 @@ impl McpClient {
         }
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::SAMPLING_CREATE_MESSAGE.to_string(),
             Some(params),
         )?;
@@ -422,9 +548,9 @@ impl McpClient {
         self.ensure_connected().await?;
 
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::PING.to_string(),
-            Some(PingParams {}),
+            Some(PingParams::default()),
         )?;
 
         let response = self.send_request(request).await?;
@@ -435,9 +561,9 @@ impl McpClient {
     pub async fn set_logging_level(&self, level: LoggingLevel) -> McpResult<SetLoggingLevelResult> {
         self.ensure_connected().await?;
 
-        let params = SetLoggingLevelParams { level };
+        let params = SetLoggingLevelParams { level, meta: None };
         let request = JsonRpcRequest::new(
-            Value::from(self.next_request_id().await),
+            self.next_request_id(),
             methods::LOGGING_SET_LEVEL.to_string(),
             Some(params),
         )?;
@@ -450,6 +576,32 @@ impl McpClient {
     // Notification Handling
     // ========================================================================
 
+    /// Send a notification to the server
+    pub async fn send_notification(&self, notification: JsonRpcNotification) -> McpResult<()> {
+        self.ensure_connected().await?;
+
+        if self.config.validate_requests {
+            validate_jsonrpc_notification(&notification)?;
+        }
+
+        let mut transport_guard = self.transport.lock().await;
+        if let Some(transport) = transport_guard.as_mut() {
+            transport.send_notification(notification).await
+        } else {
+            Err(McpError::Transport("Not connected".to_string()))
+        }
+    }
+
+    /// Notify the server that the client's list of roots has changed
+    pub async fn notify_roots_list_changed(&self) -> McpResult<()> {
+        let notification = JsonRpcNotification::new(
+            methods::ROOTS_LIST_CHANGED.to_string(),
+            Some(RootsListChangedParams::default()),
+        )?;
+
+        self.send_notification(notification).await
+    }
+
     /// Receive notifications from the server
     pub async fn receive_notification(&self) -> McpResult<Option<JsonRpcNotification>> {
         let mut transport_guard = self.transport.lock().await;
@@ -485,16 +637,65 @@ impl McpClient {
         }
     }
 
+    /// Send several requests to the server as a single batch
+    ///
+    /// Results are returned per call; a failing call does not fail the others.
+    pub fn batch(&self) -> Batch<'_> {
+        Batch::new(self)
+    }
+
+    /// Send a batch of requests and get their responses, in any order
+    pub(crate) async fn send_batch(
+        &self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        if self.config.validate_requests {
+            for request in &requests {
+                validate_jsonrpc_request(request)?;
+                validate_mcp_request(&request.method, request.params.as_ref())?;
+            }
+        }
+
+        let mut transport_guard = self.transport.lock().await;
+        if let Some(transport) = transport_guard.as_mut() {
+            let responses = transport.send_batch(requests).await?;
+
+            if self.config.validate_responses {
+                for response in &responses {
+                    validate_jsonrpc_response(response)?;
+                }
+            }
+
+            Ok(responses)
+        } else {
+            Err(McpError::Transport("Not connected".to_string()))
+        }
+    }
+
+    /// Send a request with arbitrary parameters and deserialize its result
+    ///
+    /// Useful for methods without a dedicated helper, or for attaching `_meta` to
+    /// the parameters of any request.
+    pub async fn request<P, R>(&self, method: &str, params: Option<P>) -> McpResult<R>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        self.ensure_connected().await?;
+
+        let request = JsonRpcRequest::new(self.next_request_id(), method.to_string(), params)?;
+
+        let response = self.send_request(request).await?;
+        self.handle_response(response)
+    }
+
     /// Handle a JSON-RPC response and extract the result
-    fn handle_response<T>(&self, response: JsonRpcResponse) -> McpResult<T>
+    pub(crate) fn handle_response<T>(&self, response: JsonRpcResponse) -> McpResult<T>
     where
         T: serde::de::DeserializeOwned,
     {
         if let Some(error) = response.error {
-            return Err(McpError::Protocol(format!(
-                "Server error: {}",
-                error.message
-            )));
+            return Err(McpError::from_rpc_error(error));
         }
 
         let result = response
@@ -505,7 +706,7 @@ impl McpClient {
     }
 
     /// Ensure the client is connected
-    async fn ensure_connected(&self) -> McpResult<()> {
+    pub(crate) async fn ensure_connected(&self) -> McpResult<()> {
         if !self.is_connected().await {
             return Err(McpError::Connection("Not connected to server".to_string()));
         }
@@ -513,10 +714,8 @@ impl McpClient {
     }
 
     /// Get the next request ID
-    async fn next_request_id(&self) -> u64 {
-        let mut counter = self.request_counter.lock().await;
-        *counter += 1;
-        *counter
+    pub(crate) fn next_request_id(&self) -> RequestId {
+        self.request_ids.next_id()
     }
 }
 
@@ -575,11 +774,16 @@ impl McpClientBuilder {
         self
     }
 
+    /// Use string request IDs with the given prefix
+    pub fn request_id_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.config.request_id_prefix = Some(prefix.into());
+        self
+    }
+
     /// Build the client
     pub fn build(self) -> McpClient {
-        let mut client = McpClient::new(self.name, self.version);
+        let mut client = McpClient::with_config(self.name, self.version, self.config);
         client.set_capabilities(self.capabilities);
-        client.config = self.config;
         client
     }
 }
@@ -661,7 +865,8 @@ mod tests {
             MCP_PROTOCOL_VERSION.to_string(),
         );
 
-        let init_response = JsonRpcResponse::success(Value::from(1), init_result.clone()).unwrap();
+        let init_response =
+            JsonRpcResponse::success(RequestId::from(1), init_result.clone()).unwrap();
 
         let transport = MockTransport::new(vec![init_response]);
 
@@ -683,7 +888,7 @@ mod tests {
             MCP_PROTOCOL_VERSION.to_string(),
         );
 
-        let init_response = JsonRpcResponse::success(Value::from(1), init_result).unwrap();
+        let init_response = JsonRpcResponse::success(RequestId::from(1), init_result).unwrap();
 
         let transport = MockTransport::new(vec![init_response]);
 
@@ -697,4 +902,155 @@ mod tests {
         assert!(client.server_info().await.is_none());
         assert!(client.server_capabilities().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_call_tool_structured() {
+        let init_result = InitializeResult::new(
+            ServerInfo {
+                name: "test-server".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ServerCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let responses = vec![
+            JsonRpcResponse::success(RequestId::from(1), init_result).unwrap(),
+            JsonRpcResponse::success(
+                RequestId::from(2),
+                ToolResult::structured(serde_json::json!({"sum": 3})),
+            )
+            .unwrap(),
+        ];
+
+        #[derive(serde::Deserialize)]
+        struct Sum {
+            sum: i64,
+        }
+
+        let mut client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        client.connect(MockTransport::new(responses)).await.unwrap();
+
+        let result: Sum = client
+            .call_tool_structured("add".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(result.sum, 3);
+    }
+
+    #[tokio::test]
+    async fn test_meta_preserved_in_results() {
+        let init_result = InitializeResult::new(
+            ServerInfo {
+                name: "test-server".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ServerCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let responses = vec![
+            JsonRpcResponse::success(RequestId::from(1), init_result).unwrap(),
+            JsonRpcResponse::success(
+                RequestId::from(2),
+                serde_json::json!({
+                    "content": [],
+                    "_meta": {"vendor/requestId": "abc"}
+                }),
+            )
+            .unwrap(),
+        ];
+
+        let mut client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        client.connect(MockTransport::new(responses)).await.unwrap();
+
+        let mut meta = Map::new();
+        meta.insert("vendor/user".to_string(), Value::from("alice"));
+        let result = client
+            .call_tool_with_meta("echo".to_string(), None, meta)
+            .await
+            .unwrap();
+        assert_eq!(result.meta.unwrap()["vendor/requestId"], Value::from("abc"));
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_typed() {
+        let init_result = InitializeResult::new(
+            ServerInfo {
+                name: "test-server".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ServerCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let tool_missing = McpError::ToolNotFound("search".to_string()).to_rpc_error();
+        let responses = vec![
+            JsonRpcResponse::success(RequestId::from(1), init_result).unwrap(),
+            JsonRpcResponse::error(
+                RequestId::from(2),
+                tool_missing.code,
+                tool_missing.message,
+                tool_missing.data,
+            ),
+        ];
+
+        let mut client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        client.connect(MockTransport::new(responses)).await.unwrap();
+
+        let error = client
+            .call_tool("search".to_string(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, McpError::ToolNotFound(ref name) if name == "search"));
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let init_result = InitializeResult::new(
+            ServerInfo {
+                name: "test-server".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ServerCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let tool_missing = McpError::ToolNotFound("missing".to_string()).to_rpc_error();
+        // Responses to a batch may arrive in any order
+        let responses = vec![
+            JsonRpcResponse::success(RequestId::from(1), init_result).unwrap(),
+            JsonRpcResponse::error(
+                RequestId::from(3),
+                tool_missing.code,
+                tool_missing.message,
+                tool_missing.data,
+            ),
+            JsonRpcResponse::success(
+                RequestId::from(2),
+                ListToolsResult {
+                    tools: Vec::new(),
+                    next_cursor: None,
+                    meta: None,
+                },
+            )
+            .unwrap(),
+        ];
+
+        let mut client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        client.connect(MockTransport::new(responses)).await.unwrap();
+
+        let mut results = client
+            .batch()
+            .list_tools()
+            .call_tool("missing".to_string(), None)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let tools: ListToolsResult = results.take(0).unwrap();
+        assert!(tools.tools.is_empty());
+        assert!(matches!(
+            results.take::<CallToolResult>(1),
+            Err(McpError::ToolNotFound(_))
+        ));
+        assert!(results.take::<ListToolsResult>(0).is_err());
+    }
 }
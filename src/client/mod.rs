@@ -2,9 +2,11 @@
 //!
 //! This module provides the main client implementation for the Model Context Protocol.
 
+pub mod batch;
 pub mod mcp_client;
 pub mod session;
 
 // Re-export the main client type
+pub use batch::{Batch, BatchResults};
 pub use mcp_client::McpClient;
 pub use session::ClientSession;
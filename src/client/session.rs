@@ -3,9 +3,12 @@
 //! This module provides session management for MCP clients, including connection
 //! state tracking, notification handling, and automatic reconnection capabilities.
 
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
+use tokio::sync::{broadcast, watch, Mutex, RwLock};
 use tokio::time::{sleep, timeout};
 
 use crate::client::mcp_client::McpClient;
@@ -28,6 +31,98 @@ pub enum SessionState {
     Failed(String),
 }
 
+/// How often the notification task polls the transport when it is idle
+const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Factory used by a session to create a fresh transport when reconnecting
+pub type TransportFactory = Arc<
+    dyn Fn() -> Pin<Box<dyn Future<Output = McpResult<Box<dyn Transport>>> + Send>> + Send + Sync,
+>;
+
+/// Cause of a session-level failure
+///
+/// `McpError` is not `Clone`, so session events carry this summary instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionErrorCause {
+    /// Error category as reported by [`McpError::category`]
+    pub category: &'static str,
+    /// Whether the underlying error is considered recoverable
+    pub recoverable: bool,
+    /// Human-readable error message
+    pub message: String,
+}
+
+impl From<&McpError> for SessionErrorCause {
+    fn from(error: &McpError) -> Self {
+        Self {
+            category: error.category(),
+            recoverable: error.is_recoverable(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Session-scoped state that is replayed after a reconnect
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionResumeState {
+    /// URIs of active resource subscriptions
+    pub subscriptions: BTreeSet<String>,
+    /// Logging level last set on the server
+    pub logging_level: Option<LoggingLevel>,
+    /// Roots exposed to the server
+    pub roots: Vec<Root>,
+}
+
+/// Outcome of replaying session state after a reconnect
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResyncReport {
+    /// Subscriptions that were restored
+    pub subscriptions: Vec<String>,
+    /// Subscriptions that could not be restored, with the error for each URI
+    pub failed_subscriptions: Vec<(String, String)>,
+    /// Logging level that was restored
+    pub logging_level: Option<LoggingLevel>,
+    /// Whether the server was notified of the client's roots
+    pub roots_notified: bool,
+    /// Other errors encountered while replaying
+    pub errors: Vec<String>,
+}
+
+/// Typed events emitted by a client session
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    /// Session is connected, or has recovered from a degraded state
+    Connected,
+    /// Heartbeats are failing but the miss threshold has not been reached yet
+    Degraded {
+        /// Number of consecutive missed heartbeats
+        missed_heartbeats: u32,
+        /// Error from the most recent missed heartbeat
+        cause: SessionErrorCause,
+    },
+    /// The connection to the server was lost
+    Disconnected {
+        /// Error that caused the disconnect, if any
+        cause: Option<SessionErrorCause>,
+    },
+    /// The session is about to attempt a reconnect
+    Reconnecting {
+        /// Reconnection attempt number, starting at 1
+        attempt: u32,
+        /// Backoff delay before the attempt
+        delay: Duration,
+        /// Error that caused the previous connection or attempt to fail
+        cause: Option<SessionErrorCause>,
+    },
+    /// The session has failed and will not reconnect on its own
+    Failed {
+        /// Error that caused the failure
+        cause: SessionErrorCause,
+    },
+    /// Session state was replayed after a reconnect; cached lists should be refetched
+    Resynced(ResyncReport),
+}
+
 /// Notification handler trait
 pub trait NotificationHandler: Send + Sync {
     /// Handle a notification from the server
@@ -53,6 +148,8 @@ pub struct SessionConfig {
     pub heartbeat_interval_ms: u64,
     /// Heartbeat timeout in milliseconds
     pub heartbeat_timeout_ms: u64,
+    /// Consecutive missed heartbeats before the connection is considered lost
+    pub max_missed_heartbeats: u32,
 }
 
 impl Default for SessionConfig {
@@ -66,11 +163,15 @@ impl Default for SessionConfig {
             connection_timeout_ms: 10000,
             heartbeat_interval_ms: 30000,
             heartbeat_timeout_ms: 5000,
+            max_missed_heartbeats: 3,
         }
     }
 }
 
 /// Client session that manages connection lifecycle and notifications
+///
+/// Cloning a session yields another handle to the same underlying connection.
+#[derive(Clone)]
 pub struct ClientSession {
     /// The underlying MCP client
     client: Arc<Mutex<McpClient>>,
@@ -88,14 +189,23 @@ pub struct ClientSession {
     connected_at: Arc<RwLock<Option<Instant>>>,
     /// Reconnection attempts counter
     reconnect_attempts: Arc<Mutex<u32>>,
-    /// Shutdown signal
-    shutdown_tx: Arc<Mutex<Option<mpsc::Sender<()>>>>,
+    /// Shutdown signal for background tasks
+    shutdown_tx: Arc<Mutex<Option<broadcast::Sender<()>>>>,
+    /// Session state replayed after a reconnect
+    resume_state: Arc<RwLock<SessionResumeState>>,
+    /// Whether the session has completed an initialize before
+    has_connected: Arc<RwLock<bool>>,
+    /// Session event broadcaster
+    events_tx: broadcast::Sender<SessionEvent>,
+    /// Factory used to create transports when reconnecting
+    transport_factory: Arc<RwLock<Option<TransportFactory>>>,
 }
 
 impl ClientSession {
     /// Create a new client session
     pub fn new(client: McpClient) -> Self {
         let (state_tx, state_rx) = watch::channel(SessionState::Disconnected);
+        let (events_tx, _) = broadcast::channel(64);
 
         Self {
             client: Arc::new(Mutex::new(client)),
@@ -107,6 +217,10 @@ impl ClientSession {
             connected_at: Arc::new(RwLock::new(None)),
             reconnect_attempts: Arc::new(Mutex::new(0)),
             shutdown_tx: Arc::new(Mutex::new(None)),
+            resume_state: Arc::new(RwLock::new(SessionResumeState::default())),
+            has_connected: Arc::new(RwLock::new(false)),
+            events_tx,
+            transport_factory: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -128,6 +242,11 @@ impl ClientSession {
         self.state_rx.clone()
     }
 
+    /// Subscribe to session events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events_tx.subscribe()
+    }
+
     /// Check if the session is connected
     pub async fn is_connected(&self) -> bool {
         let state = self.state.read().await;
@@ -149,6 +268,17 @@ impl ClientSession {
         handlers.push(Box::new(handler));
     }
 
+    /// Add a callback receiving server notifications as [`ServerNotification`]s
+    ///
+    /// Notifications with an unknown method or malformed parameters are skipped.
+    pub async fn on_notification<F>(&self, callback: F)
+    where
+        F: Fn(ServerNotification) + Send + Sync + 'static,
+    {
+        self.add_notification_handler(ServerNotificationHandler::new(callback))
+            .await;
+    }
+
     /// Connect to the server with the provided transport
     pub async fn connect<T>(&self, transport: T) -> McpResult<InitializeResult>
     where
@@ -156,47 +286,49 @@ impl ClientSession {
     {
         self.transition_state(SessionState::Connecting).await?;
 
-        let connect_future = async {
-            let mut client = self.client.lock().await;
-            client.connect(transport).await
-        };
-
-        let result = timeout(
-            Duration::from_millis(self.config.connection_timeout_ms),
-            connect_future,
-        )
-        .await;
-
-        match result {
-            Ok(Ok(init_result)) => {
-                self.transition_state(SessionState::Connected).await?;
+        match self.establish(transport).await {
+            Ok(init_result) => Ok(init_result),
+            Err(error) => {
+                self.fail(SessionErrorCause::from(&error)).await;
+                Err(error)
+            }
+        }
+    }
 
-                // Record connection time
-                {
-                    let mut connected_at = self.connected_at.write().await;
-                    *connected_at = Some(Instant::now());
-                }
+    /// Connect to the server using a transport factory
+    ///
+    /// The factory is kept by the session and invoked again to create a fresh
+    /// transport whenever the connection is lost and `auto_reconnect` is enabled.
+    pub async fn connect_with_factory<F, Fut, T>(&self, factory: F) -> McpResult<InitializeResult>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<T>> + Send + 'static,
+        T: Transport + 'static,
+    {
+        let factory: TransportFactory = Arc::new(move || {
+            let transport = factory();
+            Box::pin(async move { Ok(Box::new(transport.await?) as Box<dyn Transport>) })
+        });
 
-                // Reset reconnection attempts
-                {
-                    let mut attempts = self.reconnect_attempts.lock().await;
-                    *attempts = 0;
-                }
+        {
+            let mut transport_factory = self.transport_factory.write().await;
+            *transport_factory = Some(factory.clone());
+        }
 
-                // Start background tasks
-                self.start_background_tasks().await?;
+        self.transition_state(SessionState::Connecting).await?;
 
-                Ok(init_result)
-            }
-            Ok(Err(error)) => {
-                self.transition_state(SessionState::Failed(error.to_string()))
-                    .await?;
-                Err(error)
+        let transport = match factory().await {
+            Ok(transport) => transport,
+            Err(error) => {
+                self.fail(SessionErrorCause::from(&error)).await;
+                return Err(error);
             }
-            Err(_) => {
-                let error = McpError::Connection("Connection timeout".to_string());
-                self.transition_state(SessionState::Failed(error.to_string()))
-                    .await?;
+        };
+
+        match self.establish(transport).await {
+            Ok(init_result) => Ok(init_result),
+            Err(error) => {
+                self.fail(SessionErrorCause::from(&error)).await;
                 Err(error)
             }
         }
@@ -239,44 +371,320 @@ impl ClientSession {
             ));
         }
 
-        let mut attempts = self.reconnect_attempts.lock().await;
-        if *attempts >= self.config.max_reconnect_attempts {
+        let Some(attempt) = self.next_reconnect_attempt().await else {
             let error = McpError::Connection("Max reconnection attempts exceeded".to_string());
-            self.transition_state(SessionState::Failed(error.to_string()))
-                .await?;
+            self.fail(SessionErrorCause::from(&error)).await;
             return Err(error);
+        };
+
+        self.wait_before_reconnect(attempt, None).await?;
+
+        // Attempt to reconnect
+        self.connect(transport_factory()).await
+    }
+
+    /// Get the underlying client (for direct operations)
+    pub fn client(&self) -> Arc<Mutex<McpClient>> {
+        self.client.clone()
+    }
+
+    /// Get session configuration
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    // ========================================================================
+    // Connection Internals
+    // ========================================================================
+
+    /// Initialize the client over `transport` and bring the session up
+    ///
+    /// On failure the session state is left untouched so the caller can decide
+    /// whether to retry or give up.
+    async fn establish<T>(&self, transport: T) -> McpResult<InitializeResult>
+    where
+        T: Transport + 'static,
+    {
+        let connect_future = async {
+            let mut client = self.client.lock().await;
+            client.connect(transport).await
+        };
+
+        let init_result = timeout(
+            Duration::from_millis(self.config.connection_timeout_ms),
+            connect_future,
+        )
+        .await
+        .map_err(|_| McpError::Connection("Connection timeout".to_string()))??;
+
+        let is_reconnect = {
+            let mut has_connected = self.has_connected.write().await;
+            std::mem::replace(&mut *has_connected, true)
+        };
+
+        // Replay session state before handing the connection back
+        let report = if is_reconnect {
+            Some(self.resync().await)
+        } else {
+            None
+        };
+
+        self.transition_state(SessionState::Connected).await?;
+
+        // Record connection time
+        {
+            let mut connected_at = self.connected_at.write().await;
+            *connected_at = Some(Instant::now());
+        }
+
+        // Reset reconnection attempts
+        {
+            let mut attempts = self.reconnect_attempts.lock().await;
+            *attempts = 0;
+        }
+
+        // Start background tasks
+        self.start_background_tasks().await?;
+
+        let _ = self.events_tx.send(SessionEvent::Connected);
+        if let Some(report) = report {
+            let _ = self.events_tx.send(SessionEvent::Resynced(report));
+        }
+
+        Ok(init_result)
+    }
+
+    /// Claim the next reconnection attempt, or `None` if the limit is reached
+    async fn next_reconnect_attempt(&self) -> Option<u32> {
+        let mut attempts = self.reconnect_attempts.lock().await;
+        if *attempts >= self.config.max_reconnect_attempts {
+            return None;
         }
 
         *attempts += 1;
-        let current_attempts = *attempts;
-        drop(attempts);
+        Some(*attempts)
+    }
 
+    /// Enter the reconnecting state and wait out the backoff for `attempt`
+    async fn wait_before_reconnect(
+        &self,
+        attempt: u32,
+        cause: Option<SessionErrorCause>,
+    ) -> McpResult<()> {
         self.transition_state(SessionState::Reconnecting).await?;
 
         // Calculate reconnection delay with exponential backoff
-        let delay = std::cmp::min(
+        let delay = Duration::from_millis(std::cmp::min(
             (self.config.reconnect_delay_ms as f64
-                * self
-                    .config
-                    .reconnect_backoff
-                    .powi(current_attempts as i32 - 1)) as u64,
+                * self.config.reconnect_backoff.powi(attempt as i32 - 1)) as u64,
             self.config.max_reconnect_delay_ms,
-        );
+        ));
 
-        sleep(Duration::from_millis(delay)).await;
+        let _ = self.events_tx.send(SessionEvent::Reconnecting {
+            attempt,
+            delay,
+            cause,
+        });
 
-        // Attempt to reconnect
-        self.connect(transport_factory()).await
+        sleep(delay).await;
+        Ok(())
     }
 
-    /// Get the underlying client (for direct operations)
-    pub fn client(&self) -> Arc<Mutex<McpClient>> {
-        self.client.clone()
+    /// Move the session into the failed state
+    async fn fail(&self, cause: SessionErrorCause) {
+        let _ = self
+            .transition_state(SessionState::Failed(cause.message.clone()))
+            .await;
+        let _ = self.events_tx.send(SessionEvent::Failed { cause });
     }
 
-    /// Get session configuration
-    pub fn config(&self) -> &SessionConfig {
-        &self.config
+    /// Hand a lost connection over to a recovery task
+    fn connection_lost(&self, error: McpError) {
+        let session = self.clone();
+        tokio::spawn(async move {
+            session.recover(error).await;
+        });
+    }
+
+    /// Tear down a lost connection and reconnect if a transport factory is available
+    async fn recover(&self, error: McpError) {
+        // Only the first task to notice the failure drives recovery
+        {
+            let mut state = self.state.write().await;
+            if *state != SessionState::Connected {
+                return;
+            }
+            *state = SessionState::Disconnected;
+        }
+        let _ = self.state_tx.send(SessionState::Disconnected);
+
+        tracing::warn!("Session connection lost: {}", error);
+
+        self.stop_background_tasks().await;
+        {
+            let client = self.client.lock().await;
+            let _ = client.disconnect().await;
+        }
+        {
+            let mut connected_at = self.connected_at.write().await;
+            *connected_at = None;
+        }
+
+        let mut last_cause = SessionErrorCause::from(&error);
+        let _ = self.events_tx.send(SessionEvent::Disconnected {
+            cause: Some(last_cause.clone()),
+        });
+
+        let factory = {
+            let transport_factory = self.transport_factory.read().await;
+            transport_factory.clone()
+        };
+        let Some(factory) = factory.filter(|_| self.config.auto_reconnect) else {
+            return;
+        };
+
+        loop {
+            let Some(attempt) = self.next_reconnect_attempt().await else {
+                self.fail(last_cause).await;
+                return;
+            };
+
+            if self
+                .wait_before_reconnect(attempt, Some(last_cause.clone()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let result = match factory().await {
+                Ok(transport) => self.establish(transport).await,
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Ok(_) => {
+                    tracing::info!("Session reconnected after {} attempt(s)", attempt);
+                    return;
+                }
+                Err(error) => {
+                    tracing::warn!("Reconnection attempt {} failed: {}", attempt, error);
+                    last_cause = SessionErrorCause::from(&error);
+                }
+            }
+        }
+    }
+
+    // ========================================================================
+    // Session-Scoped Operations
+    // ========================================================================
+
+    /// Get the session state that will be replayed after a reconnect
+    pub async fn resume_state(&self) -> SessionResumeState {
+        let resume_state = self.resume_state.read().await;
+        resume_state.clone()
+    }
+
+    /// Subscribe to resource updates, restoring the subscription after reconnects
+    pub async fn subscribe_resource(&self, uri: String) -> McpResult<SubscribeResourceResult> {
+        let result = {
+            let client = self.client.lock().await;
+            client.subscribe_resource(uri.clone()).await?
+        };
+
+        let mut resume_state = self.resume_state.write().await;
+        resume_state.subscriptions.insert(uri);
+
+        Ok(result)
+    }
+
+    /// Unsubscribe from resource updates
+    ///
+    /// The subscription is forgotten even if the server request fails, so it
+    /// will not be restored on the next reconnect.
+    pub async fn unsubscribe_resource(&self, uri: String) -> McpResult<UnsubscribeResourceResult> {
+        {
+            let mut resume_state = self.resume_state.write().await;
+            resume_state.subscriptions.remove(&uri);
+        }
+
+        let client = self.client.lock().await;
+        client.unsubscribe_resource(uri).await
+    }
+
+    /// Set the logging level on the server, restoring it after reconnects
+    pub async fn set_logging_level(&self, level: LoggingLevel) -> McpResult<SetLoggingLevelResult> {
+        let result = {
+            let client = self.client.lock().await;
+            client.set_logging_level(level.clone()).await?
+        };
+
+        let mut resume_state = self.resume_state.write().await;
+        resume_state.logging_level = Some(level);
+
+        Ok(result)
+    }
+
+    /// Get the roots exposed to the server
+    pub async fn roots(&self) -> Vec<Root> {
+        let resume_state = self.resume_state.read().await;
+        resume_state.roots.clone()
+    }
+
+    /// Set the roots exposed to the server
+    ///
+    /// If the session is connected the server is notified that the root list changed.
+    pub async fn set_roots(&self, roots: Vec<Root>) -> McpResult<()> {
+        {
+            let mut resume_state = self.resume_state.write().await;
+            resume_state.roots = roots;
+        }
+
+        if self.is_connected().await {
+            let client = self.client.lock().await;
+            client.notify_roots_list_changed().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replay tracked session state against a freshly initialized connection
+    async fn resync(&self) -> ResyncReport {
+        let snapshot = self.resume_state().await;
+        let mut report = ResyncReport::default();
+        let client = self.client.lock().await;
+
+        for uri in snapshot.subscriptions {
+            match client.subscribe_resource(uri.clone()).await {
+                Ok(_) => report.subscriptions.push(uri),
+                Err(error) => {
+                    tracing::warn!("Failed to restore subscription to {}: {}", uri, error);
+                    report.failed_subscriptions.push((uri, error.to_string()));
+                }
+            }
+        }
+
+        if let Some(level) = snapshot.logging_level {
+            match client.set_logging_level(level.clone()).await {
+                Ok(_) => report.logging_level = Some(level),
+                Err(error) => {
+                    tracing::warn!("Failed to restore logging level: {}", error);
+                    report.errors.push(error.to_string());
+                }
+            }
+        }
+
+        if !snapshot.roots.is_empty() {
+            match client.notify_roots_list_changed().await {
+                Ok(()) => report.roots_notified = true,
+                Err(error) => {
+                    tracing::warn!("Failed to notify server of roots: {}", error);
+                    report.errors.push(error.to_string());
+                }
+            }
+        }
+
+        report
     }
 
     // ========================================================================
@@ -285,39 +693,47 @@ impl ClientSession {
 
     /// Start background tasks (notification handling, heartbeat)
     async fn start_background_tasks(&self) -> McpResult<()> {
-        let (_shutdown_tx, shutdown_rx): (broadcast::Sender<()>, broadcast::Receiver<()>) =
-            broadcast::channel(16);
+        // Tasks from a previous connection must not outlive it
+        self.stop_background_tasks().await;
+
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
         {
             let mut shutdown_guard = self.shutdown_tx.lock().await;
-            *shutdown_guard = Some(mpsc::channel(1).0); // Store a dummy for interface compatibility
+            *shutdown_guard = Some(shutdown_tx);
         }
 
         // Start notification handler task
         {
-            let client = self.client.clone();
-            let handlers = self.notification_handlers.clone();
+            let session = self.clone();
             let mut shutdown_rx_clone = shutdown_rx.resubscribe();
 
             tokio::spawn(async move {
                 loop {
                     tokio::select! {
+                        biased;
                         _ = shutdown_rx_clone.recv() => break,
                         notification_result = async {
-                            let client_guard = client.lock().await;
+                            let client_guard = session.client.lock().await;
                             client_guard.receive_notification().await
                         } => {
                             match notification_result {
                                 Ok(Some(notification)) => {
-                                    let handlers_guard = handlers.read().await;
+                                    let handlers_guard = session.notification_handlers.read().await;
                                     for handler in handlers_guard.iter() {
                                         handler.handle_notification(notification.clone());
                                     }
                                 }
                                 Ok(None) => {
-                                    // No notification available, continue
+                                    // No notification available, poll again shortly
+                                    sleep(NOTIFICATION_POLL_INTERVAL).await;
                                 }
-                                Err(_) => {
-                                    // Error receiving notification, might be disconnected
+                                Err(error) => {
+                                    // Ignore errors caused by an intentional shutdown
+                                    if shutdown_rx_clone.try_recv().is_err_and(|e| {
+                                        matches!(e, broadcast::error::TryRecvError::Empty)
+                                    }) {
+                                        session.connection_lost(error);
+                                    }
                                     break;
                                 }
                             }
@@ -329,38 +745,63 @@ impl ClientSession {
 
         // Start heartbeat task if enabled
         if self.config.heartbeat_interval_ms > 0 {
-            let client = self.client.clone();
+            let session = self.clone();
             let heartbeat_interval = Duration::from_millis(self.config.heartbeat_interval_ms);
             let heartbeat_timeout = Duration::from_millis(self.config.heartbeat_timeout_ms);
-            let state = self.state.clone();
-            let state_tx = self.state_tx.clone();
+            let max_missed = self.config.max_missed_heartbeats.max(1);
             let mut shutdown_rx_clone = shutdown_rx.resubscribe();
 
             tokio::spawn(async move {
                 let mut interval = tokio::time::interval(heartbeat_interval);
+                // The first tick completes immediately; the connection was just verified
+                interval.tick().await;
+                let mut missed = 0;
 
                 loop {
                     tokio::select! {
+                        biased;
                         _ = shutdown_rx_clone.recv() => break,
                         _ = interval.tick() => {
-                            // Check if we're still connected
-                            {
-                                let current_state = state.read().await;
-                                if !matches!(*current_state, SessionState::Connected) {
-                                    break;
-                                }
-                            }
-
                             // Send ping
                             let ping_result = timeout(heartbeat_timeout, async {
-                                let client_guard = client.lock().await;
+                                let client_guard = session.client.lock().await;
                                 client_guard.ping().await
-                            }).await;
-
-                            if ping_result.is_err() {
-                                // Heartbeat failed, mark as disconnected
-                                let _ = state_tx.send(SessionState::Disconnected);
-                                break;
+                            })
+                            .await
+                            .unwrap_or_else(|_| Err(McpError::timeout("Heartbeat timed out")));
+
+                            match ping_result {
+                                Ok(_) => {
+                                    if missed > 0 {
+                                        tracing::info!("Heartbeat recovered after {} miss(es)", missed);
+                                        missed = 0;
+                                        let _ = session.events_tx.send(SessionEvent::Connected);
+                                    }
+                                }
+                                Err(error) => {
+                                    missed += 1;
+                                    tracing::warn!(
+                                        "Heartbeat missed ({}/{}): {}",
+                                        missed,
+                                        max_missed,
+                                        error
+                                    );
+
+                                    if missed < max_missed {
+                                        let _ = session.events_tx.send(SessionEvent::Degraded {
+                                            missed_heartbeats: missed,
+                                            cause: SessionErrorCause::from(&error),
+                                        });
+                                    } else {
+                                        // Ignore errors caused by an intentional shutdown
+                                        if shutdown_rx_clone.try_recv().is_err_and(|e| {
+                                            matches!(e, broadcast::error::TryRecvError::Empty)
+                                        }) {
+                                            session.connection_lost(error);
+                                        }
+                                        break;
+                                    }
+                                }
                             }
                         }
                     }
@@ -379,10 +820,9 @@ impl ClientSession {
         };
 
         if let Some(tx) = shutdown_tx {
-            let _ = tx.send(()).await; // Ignore error if receiver is dropped
+            let _ = tx.send(()); // Ignore error if the tasks already exited
         }
     }
-
     /// Transition to a new state
     async fn transition_state(&self, new_state: SessionState) -> McpResult<()> {
         {
@@ -412,6 +852,32 @@ impl NotificationHandler for LoggingNotificationHandler {
     }
 }
 
+/// Notification handler passing typed server notifications to a callback
+pub struct ServerNotificationHandler {
+    callback: Box<dyn Fn(ServerNotification) + Send + Sync>,
+}
+
+impl ServerNotificationHandler {
+    /// Create a new typed notification handler
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(ServerNotification) + Send + Sync + 'static,
+    {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl NotificationHandler for ServerNotificationHandler {
+    fn handle_notification(&self, notification: JsonRpcNotification) {
+        match ServerNotification::try_from(notification) {
+            Ok(notification) => (self.callback)(notification),
+            Err(e) => tracing::debug!("Skipping notification: {}", e),
+        }
+    }
+}
+
 /// Resource update notification handler
 pub struct ResourceUpdateHandler {
     callback: Box<dyn Fn(String) + Send + Sync>,
@@ -431,12 +897,10 @@ impl ResourceUpdateHandler {
 
 impl NotificationHandler for ResourceUpdateHandler {
     fn handle_notification(&self, notification: JsonRpcNotification) {
-        if notification.method == methods::RESOURCES_UPDATED {
-            if let Some(params) = notification.params {
-                if let Ok(update_params) = serde_json::from_value::<ResourceUpdatedParams>(params) {
-                    (self.callback)(update_params.uri);
-                }
-            }
+        if let Ok(ServerNotification::ResourceUpdated(params)) =
+            ServerNotification::try_from(notification)
+        {
+            (self.callback)(params.uri);
         }
     }
 }
@@ -460,7 +924,9 @@ impl ToolListChangedHandler {
 
 impl NotificationHandler for ToolListChangedHandler {
     fn handle_notification(&self, notification: JsonRpcNotification) {
-        if notification.method == methods::TOOLS_LIST_CHANGED {
+        if let Ok(ServerNotification::ToolListChanged(_)) =
+            ServerNotification::try_from(notification)
+        {
             (self.callback)();
         }
     }
@@ -485,16 +951,9 @@ impl ProgressHandler {
 
 impl NotificationHandler for ProgressHandler {
     fn handle_notification(&self, notification: JsonRpcNotification) {
-        if notification.method == methods::PROGRESS {
-            if let Some(params) = notification.params {
-                if let Ok(progress_params) = serde_json::from_value::<ProgressParams>(params) {
-                    (self.callback)(
-                        progress_params.progress_token,
-                        progress_params.progress,
-                        progress_params.total,
-                    );
-                }
-            }
+        if let Ok(ServerNotification::Progress(params)) = ServerNotification::try_from(notification)
+        {
+            (self.callback)(params.progress_token, params.progress, params.total);
         }
     }
 }
@@ -556,7 +1015,7 @@ mod tests {
                 ServerCapabilities::default(),
                 MCP_PROTOCOL_VERSION.to_string(),
             );
-            JsonRpcResponse::success(serde_json::Value::from(1), init_result)
+            JsonRpcResponse::success(RequestId::from(1), init_result)
                 .map_err(McpError::Serialization)
         }
 
@@ -573,6 +1032,40 @@ mod tests {
         }
     }
 
+    // Mock transport that records every method it is asked to send
+    struct RecordingTransport {
+        methods: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+            self.methods.lock().unwrap().push(request.method.clone());
+            let init_result = InitializeResult::new(
+                ServerInfo {
+                    name: "test-server".to_string(),
+                    version: "1.0.0".to_string(),
+                },
+                ServerCapabilities::default(),
+                MCP_PROTOCOL_VERSION.to_string(),
+            );
+            JsonRpcResponse::success(request.id, init_result).map_err(McpError::Serialization)
+        }
+
+        async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
+            self.methods.lock().unwrap().push(notification.method);
+            Ok(())
+        }
+
+        async fn receive_notification(&mut self) -> McpResult<Option<JsonRpcNotification>> {
+            Ok(None)
+        }
+
+        async fn close(&mut self) -> McpResult<()> {
+            Ok(())
+        }
+    }
+
     #[tokio::test]
     async fn test_session_creation() {
         let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
@@ -649,6 +1142,52 @@ mod tests {
         assert_eq!(handlers.len(), 4);
     }
 
+    #[tokio::test]
+    async fn test_typed_notifications() {
+        let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let session = ClientSession::new(client);
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        session
+            .on_notification(move |notification| sink.lock().unwrap().push(notification))
+            .await;
+
+        let notifications = [
+            JsonRpcNotification::new(
+                methods::RESOURCES_UPDATED.to_string(),
+                Some(serde_json::json!({"uri": "file:///a.txt"})),
+            )
+            .unwrap(),
+            // Unknown methods and malformed parameters are skipped
+            JsonRpcNotification::new("vendor/event".to_string(), None::<serde_json::Value>)
+                .unwrap(),
+            JsonRpcNotification::new(methods::PROGRESS.to_string(), None::<serde_json::Value>)
+                .unwrap(),
+            JsonRpcNotification::new(
+                methods::TOOLS_LIST_CHANGED.to_string(),
+                None::<serde_json::Value>,
+            )
+            .unwrap(),
+        ];
+        for notification in notifications {
+            let handlers = session.notification_handlers.read().await;
+            for handler in handlers.iter() {
+                handler.handle_notification(notification.clone());
+            }
+        }
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(
+            matches!(&received[0], ServerNotification::ResourceUpdated(params) if params.uri == "file:///a.txt")
+        );
+        assert!(matches!(
+            received[1],
+            ServerNotification::ToolListChanged(_)
+        ));
+    }
+
     #[tokio::test]
     async fn test_session_stats() {
         let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
@@ -677,6 +1216,188 @@ mod tests {
         assert_eq!(session.config().reconnect_delay_ms, 2000);
     }
 
+    #[tokio::test]
+    async fn test_resync_after_reconnect() {
+        let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let config = SessionConfig {
+            heartbeat_interval_ms: 0,
+            ..Default::default()
+        };
+        let session = ClientSession::with_config(client, config);
+        let methods = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        session
+            .connect(RecordingTransport {
+                methods: methods.clone(),
+            })
+            .await
+            .unwrap();
+        session
+            .subscribe_resource("file:///dashboard.json".to_string())
+            .await
+            .unwrap();
+        session
+            .set_logging_level(LoggingLevel::Warning)
+            .await
+            .unwrap();
+        session
+            .set_roots(vec![Root {
+                uri: "file:///workspace".to_string(),
+                name: Some("workspace".to_string()),
+            }])
+            .await
+            .unwrap();
+
+        let resume_state = session.resume_state().await;
+        assert!(resume_state
+            .subscriptions
+            .contains("file:///dashboard.json"));
+        assert_eq!(resume_state.logging_level, Some(LoggingLevel::Warning));
+        assert_eq!(resume_state.roots.len(), 1);
+
+        // Simulate a server restart
+        session.disconnect().await.unwrap();
+        let mut events = session.subscribe_events();
+        let replayed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        session
+            .connect(RecordingTransport {
+                methods: replayed.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *replayed.lock().unwrap(),
+            vec![
+                methods::INITIALIZE.to_string(),
+                methods::RESOURCES_SUBSCRIBE.to_string(),
+                methods::LOGGING_SET_LEVEL.to_string(),
+                methods::ROOTS_LIST_CHANGED.to_string(),
+            ]
+        );
+
+        assert_eq!(events.recv().await.unwrap(), SessionEvent::Connected);
+        match events.recv().await.unwrap() {
+            SessionEvent::Resynced(report) => {
+                assert_eq!(report.subscriptions, vec!["file:///dashboard.json"]);
+                assert_eq!(report.logging_level, Some(LoggingLevel::Warning));
+                assert!(report.roots_notified);
+                assert!(report.failed_subscriptions.is_empty());
+                assert!(report.errors.is_empty());
+            }
+            other => panic!("Expected resynced event, got {:?}", other),
+        }
+    }
+
+    // Mock transport whose pings always fail
+    struct DeadPeerTransport;
+
+    #[async_trait]
+    impl Transport for DeadPeerTransport {
+        async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+            if request.method == methods::PING {
+                return Err(McpError::transport("Broken pipe"));
+            }
+            MockTransport.send_request(request).await
+        }
+
+        async fn send_notification(&mut self, _notification: JsonRpcNotification) -> McpResult<()> {
+            Ok(())
+        }
+
+        async fn receive_notification(&mut self) -> McpResult<Option<JsonRpcNotification>> {
+            Ok(None)
+        }
+
+        async fn close(&mut self) -> McpResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_failure_triggers_reconnect() {
+        let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let config = SessionConfig {
+            heartbeat_interval_ms: 20,
+            heartbeat_timeout_ms: 50,
+            max_missed_heartbeats: 2,
+            reconnect_delay_ms: 10,
+            ..Default::default()
+        };
+        let session = ClientSession::with_config(client, config);
+        let mut events = session.subscribe_events();
+
+        let connections = Arc::new(std::sync::Mutex::new(0));
+        let factory_connections = connections.clone();
+        session
+            .connect_with_factory(move || {
+                let connections = factory_connections.clone();
+                async move {
+                    let mut count = connections.lock().unwrap();
+                    *count += 1;
+                    if *count == 1 {
+                        Ok(Box::new(DeadPeerTransport) as Box<dyn Transport>)
+                    } else {
+                        Ok(Box::new(MockTransport) as Box<dyn Transport>)
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        while !matches!(received.last(), Some(SessionEvent::Resynced(_))) {
+            let event = timeout(Duration::from_secs(5), events.recv())
+                .await
+                .expect("session event")
+                .unwrap();
+            received.push(event);
+        }
+
+        assert_eq!(received[0], SessionEvent::Connected);
+        assert!(matches!(
+            received[1],
+            SessionEvent::Degraded {
+                missed_heartbeats: 1,
+                ..
+            }
+        ));
+        match &received[2] {
+            SessionEvent::Disconnected { cause: Some(cause) } => {
+                assert_eq!(cause.category, "transport");
+                assert!(cause.message.contains("Broken pipe"));
+            }
+            other => panic!("Expected disconnected event, got {:?}", other),
+        }
+        assert!(matches!(
+            received[3],
+            SessionEvent::Reconnecting { attempt: 1, .. }
+        ));
+        assert_eq!(received[4], SessionEvent::Connected);
+        assert_eq!(*connections.lock().unwrap(), 2);
+        assert!(session.is_connected().await);
+
+        session.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_forgets_subscription() {
+        let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let session = ClientSession::new(client);
+
+        session.connect(MockTransport).await.unwrap();
+        session
+            .subscribe_resource("file:///a.txt".to_string())
+            .await
+            .unwrap();
+        session
+            .unsubscribe_resource("file:///a.txt".to_string())
+            .await
+            .unwrap();
+
+        assert!(session.resume_state().await.subscriptions.is_empty());
+    }
+
     #[tokio::test]
     async fn test_state_subscription() {
         let client = McpClient::new("test-client".to_string(), "1.0.0".to_string());
@@ -0,0 +1,276 @@
+//! Request context passed to handlers
+//!
+//! This module provides the context a tool, prompt or resource handler receives
+//! alongside its arguments: the request being served, the client that sent it,
+//! server-wide application state and a handle for talking back to the client.
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::error::{McpError, McpResult};
+use crate::protocol::messages::LoggingLevel;
+use crate::protocol::types::{ClientCapabilities, ClientInfo, RequestId};
+
+/// Context of the request a handler is serving
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// ID of the JSON-RPC request, if the handler was invoked through one
+    pub request_id: Option<RequestId>,
+    /// The `_meta` object sent with the request parameters
+    pub meta: Map<String, Value>,
+    /// The client session, once the client has initialized
+    ///
+    /// Requests outside of a session, such as HTTP requests without an
+    /// `Mcp-Session-Id` header, have none.
+    pub session: Option<Session>,
+    /// Application state registered on the server
+    pub state: Extensions,
+    /// Handle for sending messages back to the client
+    pub peer: Option<Peer>,
+}
+
+impl RequestContext {
+    /// Create an empty context for a handler invoked outside of a request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a context for the JSON-RPC request with the given ID
+    pub fn for_request(request_id: RequestId) -> Self {
+        Self {
+            request_id: Some(request_id),
+            ..Self::default()
+        }
+    }
+
+    /// Get application state of type `T`, if it has been registered
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.state.get::<T>()
+    }
+}
+
+/// Type map holding application state shared with handlers
+///
+/// Cloning is cheap; values are stored behind `Arc`s.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl Extensions {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, replacing any previous value of the same type
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: T) {
+        Arc::make_mut(&mut self.map).insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Get the value of type `T`, if present
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+
+    /// Check whether the map is empty
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
+
+/// Information about the client negotiated during initialization
+///
+/// Transports serving several clients, such as WebSocket and HTTP, keep a separate
+/// session for every connection, so handlers can treat each client independently.
+/// A transport serving a single client, such as STDIO, has one session without an ID.
+///
+/// Over HTTP, `initialize` always starts a new session, so requests sent without
+/// the `Mcp-Session-Id` header belong to no session and are never attributed to
+/// another client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// ID of the connection the session belongs to, if the transport serves several
+    pub id: Option<String>,
+    /// Name and version of the client
+    pub client_info: ClientInfo,
+    /// Capabilities the client declared
+    pub capabilities: ClientCapabilities,
+    /// Protocol version the client requested
+    pub protocol_version: String,
+    /// Logging level the client set with `logging/setLevel`, if any
+    pub log_level: Option<LoggingLevel>,
+    /// Data scoped to this session
+    pub data: SessionData,
+}
+
+/// Type map holding data scoped to a client session
+///
+/// Unlike [`Extensions`], values can be inserted while the session is in use; all
+/// clones of a session share the same data, which is dropped when the session ends.
+#[derive(Clone, Default)]
+pub struct SessionData {
+    map: Arc<std::sync::RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl SessionData {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a value, replacing any previous value of the same type
+    pub fn insert<T: Send + Sync + 'static>(&self, value: T) {
+        self.map
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    /// Get the value of type `T`, if present
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.map
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+
+    /// Remove the value of type `T`, returning it if it was present
+    pub fn remove<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.map
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+}
+
+impl PartialEq for SessionData {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.map, &other.map)
+    }
+}
+
+impl std::fmt::Debug for SessionData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.map.read().map(|map| map.len()).unwrap_or_default();
+        f.debug_struct("SessionData").field("len", &len).finish()
+    }
+}
+
+/// Sends messages to the client on whose behalf a handler runs
+#[async_trait]
+pub trait PeerSender: Send + Sync {
+    /// Send a request to the client and wait for its result
+    ///
+    /// # Arguments
+    /// * `method` - Method name of the request
+    /// * `params` - Optional request parameters
+    ///
+    /// # Returns
+    /// Result containing the response result or an error
+    async fn send_request(&self, method: &str, params: Option<Value>) -> McpResult<Value>;
+
+    /// Send a notification to the client
+    ///
+    /// # Arguments
+    /// * `method` - Method name of the notification
+    /// * `params` - Optional notification parameters
+    ///
+    /// # Returns
+    /// Result indicating success or an error
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> McpResult<()>;
+}
+
+/// Handle for sending requests and notifications back to the client
+#[derive(Clone)]
+pub struct Peer {
+    sender: Arc<dyn PeerSender>,
+}
+
+impl Peer {
+    /// Create a peer handle sending through the given sender
+    pub fn new(sender: Arc<dyn PeerSender>) -> Self {
+        Self { sender }
+    }
+
+    /// Send a request to the client and deserialize its result
+    pub async fn request<P, R>(&self, method: &str, params: Option<P>) -> McpResult<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let params = params.map(serde_json::to_value).transpose()?;
+        let result = self.sender.send_request(method, params).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Send a notification to the client
+    pub async fn notify<P: Serialize>(&self, method: &str, params: Option<P>) -> McpResult<()> {
+        let params = params.map(serde_json::to_value).transpose()?;
+        self.sender.send_notification(method, params).await
+    }
+}
+
+impl std::fmt::Debug for Peer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Peer").finish_non_exhaustive()
+    }
+}
+
+/// Error returned when a request needs an initialized session but has none
+pub(crate) fn no_session_error() -> McpError {
+    McpError::protocol("Client session has not been initialized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions() {
+        let mut extensions = Extensions::new();
+        assert!(extensions.get::<u32>().is_none());
+
+        extensions.insert(5u32);
+        extensions.insert(String::from("pool"));
+        let snapshot = extensions.clone();
+        extensions.insert(6u32);
+
+        assert_eq!(*snapshot.get::<u32>().unwrap(), 5);
+        assert_eq!(*extensions.get::<u32>().unwrap(), 6);
+        assert_eq!(extensions.get::<String>().unwrap().as_str(), "pool");
+    }
+
+    #[test]
+    fn test_session_data() {
+        let data = SessionData::new();
+        let shared = data.clone();
+        assert!(data.get::<u32>().is_none());
+
+        shared.insert(5u32);
+        assert_eq!(*data.get::<u32>().unwrap(), 5);
+        assert_eq!(data, shared);
+        assert_ne!(data, SessionData::new());
+
+        assert_eq!(*data.remove::<u32>().unwrap(), 5);
+        assert!(shared.get::<u32>().is_none());
+    }
+}
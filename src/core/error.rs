@@ -3,8 +3,15 @@
 //! This module defines all error types that can occur within the MCP SDK,
 //! providing structured error handling with detailed context.
 
+use serde_json::{json, Value};
 use thiserror::Error;
 
+use crate::protocol::types::{
+    JsonRpcError, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PROMPT_NOT_FOUND, RATE_LIMITED, REQUEST_CANCELLED, REQUEST_TIMEOUT, RESOURCE_NOT_FOUND,
+    TOOL_NOT_FOUND, UNAUTHORIZED,
+};
+
 /// The main error type for the MCP SDK
 #[derive(Error, Debug)]
 pub enum McpError {
@@ -36,6 +43,10 @@ pub enum McpError {
     #[error("Prompt not found: {0}")]
     PromptNotFound(String),
 
+    /// Requested method is not supported by the peer
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+
     /// Connection-related errors
     #[error("Connection error: {0}")]
     Connection(String),
@@ -68,13 +79,27 @@ pub enum McpError {
 
     /// JSON Schema validation errors
     #[cfg(feature = "validation")]
-    #[error("Schema validation error: {0}")]
-    SchemaValidation(String),
+    #[error("Schema validation error: {message}")]
+    SchemaValidation {
+        /// Summary of all violations
+        message: String,
+        /// Individual violations with the paths at which they occurred
+        violations: Vec<SchemaViolation>,
+    },
 
     /// Timeout errors
     #[error("Timeout error: {0}")]
     Timeout(String),
 
+    /// The peer is rejecting requests because too many were sent
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        /// Description of the limit that was hit
+        message: String,
+        /// How long to wait before retrying, if the peer said
+        retry_after_ms: Option<u64>,
+    },
+
     /// Cancellation errors
     #[error("Operation cancelled: {0}")]
     Cancelled(String),
@@ -82,11 +107,46 @@ pub enum McpError {
     /// Internal errors that shouldn't normally occur
     #[error("Internal error: {0}")]
     Internal(String),
+
+    /// Error returned by the peer whose code has no dedicated variant
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc {
+        /// JSON-RPC error code
+        code: i32,
+        /// Error message sent by the peer
+        message: String,
+        /// Additional error data sent by the peer
+        data: Option<Value>,
+    },
 }
 
 /// Result type alias for MCP operations
 pub type McpResult<T> = Result<T, McpError>;
 
+/// A single JSON Schema violation found while validating a value
+#[cfg(feature = "validation")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value (empty for the value itself)
+    pub path: String,
+    /// JSON pointer to the schema keyword that failed
+    pub schema_path: String,
+    /// Human-readable description of the violation
+    pub message: String,
+}
+
+#[cfg(feature = "validation")]
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
 impl McpError {
     /// Create a new transport error
     pub fn transport<S: Into<String>>(message: S) -> Self {
@@ -128,6 +188,20 @@ impl McpError {
         Self::Timeout(message.into())
     }
 
+    /// Create a new schema validation error from the violations found
+    #[cfg(feature = "validation")]
+    pub fn schema_validation(violations: Vec<SchemaViolation>) -> Self {
+        let message = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self::SchemaValidation {
+            message,
+            violations,
+        }
+    }
+
     /// Check if this error is recoverable
     pub fn is_recoverable(&self) -> bool {
         match self {
@@ -139,6 +213,8 @@ impl McpError {
             McpError::ToolNotFound(_) => false,
             McpError::ResourceNotFound(_) => false,
             McpError::PromptNotFound(_) => false,
+            McpError::MethodNotFound(_) => false,
+            McpError::RateLimited { .. } => true,
             McpError::Authentication(_) => false,
             McpError::Serialization(_) => false,
             McpError::InvalidUri(_) => false,
@@ -149,9 +225,10 @@ impl McpError {
             #[cfg(feature = "websocket")]
             McpError::WebSocket(_) => true,
             #[cfg(feature = "validation")]
-            McpError::SchemaValidation(_) => false,
+            McpError::SchemaValidation { .. } => false,
             McpError::Cancelled(_) => false,
             McpError::Internal(_) => false,
+            McpError::Rpc { .. } => false,
         }
     }
 
@@ -166,6 +243,8 @@ impl McpError {
             McpError::ToolNotFound(_) => "not_found",
             McpError::ResourceNotFound(_) => "not_found",
             McpError::PromptNotFound(_) => "not_found",
+            McpError::MethodNotFound(_) => "not_found",
+            McpError::RateLimited { .. } => "rate_limit",
             McpError::Authentication(_) => "auth",
             McpError::Serialization(_) => "serialization",
             McpError::InvalidUri(_) => "validation",
@@ -176,9 +255,128 @@ impl McpError {
             #[cfg(feature = "websocket")]
             McpError::WebSocket(_) => "websocket",
             #[cfg(feature = "validation")]
-            McpError::SchemaValidation(_) => "validation",
+            McpError::SchemaValidation { .. } => "validation",
             McpError::Cancelled(_) => "cancelled",
             McpError::Internal(_) => "internal",
+            McpError::Rpc { .. } => "rpc",
+        }
+    }
+
+    /// Get the JSON-RPC error code this error is reported with
+    pub fn code(&self) -> i32 {
+        match self {
+            McpError::ToolNotFound(_) => TOOL_NOT_FOUND,
+            McpError::ResourceNotFound(_) => RESOURCE_NOT_FOUND,
+            McpError::PromptNotFound(_) => PROMPT_NOT_FOUND,
+            McpError::MethodNotFound(_) => METHOD_NOT_FOUND,
+            McpError::Validation(_) | McpError::InvalidUri(_) | McpError::Url(_) => INVALID_PARAMS,
+            #[cfg(feature = "validation")]
+            McpError::SchemaValidation { .. } => INVALID_PARAMS,
+            McpError::Protocol(_) => INVALID_REQUEST,
+            McpError::Authentication(_) => UNAUTHORIZED,
+            McpError::Timeout(_) => REQUEST_TIMEOUT,
+            McpError::Cancelled(_) => REQUEST_CANCELLED,
+            McpError::RateLimited { .. } => RATE_LIMITED,
+            McpError::Rpc { code, .. } => *code,
+            _ => INTERNAL_ERROR,
+        }
+    }
+
+    /// Convert this error into a JSON-RPC error object
+    ///
+    /// Errors that carry details a caller may act on also fill `data`: the missing
+    /// tool, prompt, resource or method, schema violations and retry hints.
+    pub fn to_rpc_error(&self) -> JsonRpcError {
+        let data = match self {
+            McpError::ToolNotFound(name) | McpError::PromptNotFound(name) => {
+                Some(json!({ "name": name }))
+            }
+            McpError::ResourceNotFound(uri) => Some(json!({ "uri": uri })),
+            McpError::MethodNotFound(method) => Some(json!({ "method": method })),
+            #[cfg(feature = "validation")]
+            McpError::SchemaValidation { violations, .. } => {
+                Some(json!({ "violations": violations }))
+            }
+            McpError::RateLimited {
+                retry_after_ms: Some(retry_after_ms),
+                ..
+            } => Some(json!({ "retryAfterMs": retry_after_ms })),
+            McpError::Rpc { data, .. } => data.clone(),
+            _ => None,
+        };
+        let message = match self {
+            McpError::Rpc { message, .. } => message.clone(),
+            _ => self.to_string(),
+        };
+
+        JsonRpcError {
+            code: self.code(),
+            message,
+            data,
+        }
+    }
+
+    /// Rebuild an error from a JSON-RPC error object sent by the peer
+    ///
+    /// This is the inverse of [`McpError::to_rpc_error`]; codes without a dedicated
+    /// variant become [`McpError::Rpc`] so the code and data are never lost.
+    pub fn from_rpc_error(error: JsonRpcError) -> Self {
+        let JsonRpcError {
+            code,
+            message,
+            data,
+        } = error;
+        let field = |name: &str| {
+            data.as_ref()
+                .and_then(|data| data.get(name))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        let detail = |prefix: &str| message.strip_prefix(prefix).unwrap_or(&message).to_string();
+
+        match code {
+            TOOL_NOT_FOUND => {
+                McpError::ToolNotFound(field("name").unwrap_or_else(|| detail("Tool not found: ")))
+            }
+            PROMPT_NOT_FOUND => McpError::PromptNotFound(
+                field("name").unwrap_or_else(|| detail("Prompt not found: ")),
+            ),
+            RESOURCE_NOT_FOUND => McpError::ResourceNotFound(
+                field("uri").unwrap_or_else(|| detail("Resource not found: ")),
+            ),
+            METHOD_NOT_FOUND => McpError::MethodNotFound(
+                field("method").unwrap_or_else(|| detail("Method not found: ")),
+            ),
+            INVALID_PARAMS => {
+                #[cfg(feature = "validation")]
+                if let Some(violations) = data
+                    .as_ref()
+                    .and_then(|data| data.get("violations"))
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                {
+                    return McpError::SchemaValidation {
+                        message: detail("Schema validation error: "),
+                        violations,
+                    };
+                }
+                McpError::Validation(detail("Validation error: "))
+            }
+            INVALID_REQUEST => McpError::Protocol(detail("Protocol error: ")),
+            UNAUTHORIZED => McpError::Authentication(detail("Authentication error: ")),
+            REQUEST_TIMEOUT => McpError::Timeout(detail("Timeout error: ")),
+            REQUEST_CANCELLED => McpError::Cancelled(detail("Operation cancelled: ")),
+            RATE_LIMITED => McpError::RateLimited {
+                retry_after_ms: data
+                    .as_ref()
+                    .and_then(|data| data.get("retryAfterMs"))
+                    .and_then(Value::as_u64),
+                message: detail("Rate limited: "),
+            },
+            _ => McpError::Rpc {
+                code,
+                message,
+                data,
+            },
         }
     }
 }
@@ -230,4 +428,87 @@ mod tests {
             "auth"
         );
     }
+
+    #[test]
+    fn test_rpc_error_round_trip() {
+        let errors = vec![
+            McpError::ToolNotFound("search".to_string()),
+            McpError::PromptNotFound("greet".to_string()),
+            McpError::ResourceNotFound("file:///missing".to_string()),
+            McpError::MethodNotFound("tools/frobnicate".to_string()),
+            McpError::validation("name is required"),
+            McpError::protocol("bad message"),
+            McpError::timeout("took too long"),
+            McpError::RateLimited {
+                message: "slow down".to_string(),
+                retry_after_ms: Some(1500),
+            },
+        ];
+
+        for error in errors {
+            let rpc_error = error.to_rpc_error();
+            let wire: JsonRpcError =
+                serde_json::from_value(serde_json::to_value(&rpc_error).unwrap()).unwrap();
+            let rebuilt = McpError::from_rpc_error(wire);
+            assert_eq!(rebuilt.code(), error.code());
+            assert_eq!(rebuilt.to_string(), error.to_string());
+        }
+
+        let tool_error = McpError::ToolNotFound("search".to_string()).to_rpc_error();
+        assert_eq!(tool_error.code, TOOL_NOT_FOUND);
+        assert_eq!(tool_error.data, Some(json!({"name": "search"})));
+
+        let rate_limited = McpError::from_rpc_error(JsonRpcError {
+            code: RATE_LIMITED,
+            message: "Rate limited: slow down".to_string(),
+            data: Some(json!({"retryAfterMs": 1500})),
+        });
+        assert!(rate_limited.is_recoverable());
+        assert!(matches!(
+            rate_limited,
+            McpError::RateLimited {
+                retry_after_ms: Some(1500),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_unknown_rpc_error_keeps_code_and_data() {
+        let error = McpError::from_rpc_error(JsonRpcError {
+            code: INTERNAL_ERROR,
+            message: "database unavailable".to_string(),
+            data: Some(json!({"backend": "postgres"})),
+        });
+        assert!(matches!(
+            error,
+            McpError::Rpc {
+                code: INTERNAL_ERROR,
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_rpc_error().data,
+            Some(json!({"backend": "postgres"}))
+        );
+        assert!(!error.is_recoverable());
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn test_schema_violations_round_trip() {
+        let error = McpError::schema_validation(vec![SchemaViolation {
+            path: "/count".to_string(),
+            schema_path: "/properties/count/type".to_string(),
+            message: "\"5\" is not of type \"integer\"".to_string(),
+        }]);
+        let rebuilt = McpError::from_rpc_error(error.to_rpc_error());
+        match rebuilt {
+            McpError::SchemaValidation { violations, .. } => {
+                assert_eq!(violations.len(), 1);
+                assert_eq!(violations[0].path, "/count");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
 }
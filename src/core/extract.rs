@@ -0,0 +1,332 @@
+//! Extractor-style handler parameters
+//!
+//! Handlers registered with `McpServer::add_tool_handler` or
+//! `McpServer::add_prompt_handler` are async functions whose parameters are
+//! extractors: each parameter type pulls what it needs out of the incoming request.
+//!
+//! # Examples
+//! ```rust
+//! use mcp_protocol_sdk::core::extract::{Args, Session, State};
+//! use mcp_protocol_sdk::prelude::*;
+//! use serde::Deserialize;
+//!
+//! struct Pool;
+//!
+//! #[derive(Deserialize)]
+//! struct Query {
+//!     sql: String,
+//! }
+//!
+//! async fn query(
+//!     State(_pool): State<Pool>,
+//!     Args(query): Args<Query>,
+//!     session: Option<Session>,
+//! ) -> McpResult<ToolResult> {
+//!     let caller = session.map(|s| s.client_info.name).unwrap_or_default();
+//!     Ok(ToolResult {
+//!         content: vec![Content::text(format!("{} ran {}", caller, query.sql))],
+//!         is_error: None,
+//!         structured_content: None,
+//!         meta: None,
+//!     })
+//! }
+//!
+//! # async fn register() -> McpResult<()> {
+//! let mut server = McpServer::new("db".to_string(), "1.0.0".to_string());
+//! server.add_state(Pool);
+//! server
+//!     .add_tool_handler("query".to_string(), None, json!({"type": "object"}), query)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::core::context::no_session_error;
+use crate::core::error::{McpError, McpResult};
+use crate::core::prompt::PromptHandler;
+use crate::core::tool::ToolHandler;
+use crate::protocol::types::{PromptResult, ToolResult};
+
+pub use crate::core::context::{Peer, RequestContext, Session};
+
+/// The arguments and context of a request, as seen by extractors
+#[derive(Debug, Clone, Default)]
+pub struct HandlerRequest {
+    /// Arguments sent by the client
+    pub arguments: HashMap<String, Value>,
+    /// Context of the request
+    pub context: RequestContext,
+}
+
+/// Types that can be extracted from a handler request
+pub trait FromRequest: Sized {
+    /// Extract the value from the request
+    ///
+    /// # Arguments
+    /// * `request` - The request being handled
+    ///
+    /// # Returns
+    /// Result containing the extracted value or an error that fails the request
+    fn from_request(request: &HandlerRequest) -> McpResult<Self>;
+}
+
+/// Application state registered with `McpServer::add_state`
+#[derive(Debug)]
+pub struct State<T>(pub Arc<T>);
+
+impl<T> Clone for State<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Send + Sync + 'static> FromRequest for State<T> {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        request.context.state::<T>().map(State).ok_or_else(|| {
+            McpError::internal(format!(
+                "State of type '{}' has not been registered",
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+/// Request arguments deserialized into `T`
+#[derive(Debug, Clone)]
+pub struct Args<T>(pub T);
+
+impl<T> Deref for Args<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for Args<T> {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        let arguments = Value::Object(
+            request
+                .arguments
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        );
+        serde_json::from_value(arguments)
+            .map(Args)
+            .map_err(|e| McpError::validation(format!("Invalid arguments: {}", e)))
+    }
+}
+
+/// The `_meta` object sent with the request
+#[derive(Debug, Clone, Default)]
+pub struct Meta(pub Map<String, Value>);
+
+impl FromRequest for Meta {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        Ok(Meta(request.context.meta.clone()))
+    }
+}
+
+/// Fails the request if it does not belong to an initialized session
+///
+/// Use `Option<Session>` in handlers that also serve requests outside of a session.
+impl FromRequest for Session {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        request.context.session.clone().ok_or_else(no_session_error)
+    }
+}
+
+impl FromRequest for Peer {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        request
+            .context
+            .peer
+            .clone()
+            .ok_or_else(|| McpError::internal("No peer is available for this request"))
+    }
+}
+
+impl FromRequest for RequestContext {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        Ok(request.context.clone())
+    }
+}
+
+impl<T: FromRequest> FromRequest for Option<T> {
+    fn from_request(request: &HandlerRequest) -> McpResult<Self> {
+        Ok(T::from_request(request).ok())
+    }
+}
+
+/// Future returned by handlers
+pub type HandlerFuture<R> = Pin<Box<dyn Future<Output = McpResult<R>> + Send>>;
+
+/// Async functions whose parameters are all extractors
+///
+/// Implemented for functions of up to eight parameters; `T` is the tuple of parameter
+/// types and `R` the type of the successful result.
+pub trait Handler<T, R>: Send + Sync + 'static {
+    /// Extract the parameters from the request and call the function
+    fn call(&self, request: HandlerRequest) -> HandlerFuture<R>;
+}
+
+macro_rules! impl_handler {
+    ($($ty:ident),*) => {
+        #[allow(non_snake_case, unused_variables)]
+        impl<F, Fut, R, $($ty,)*> Handler<($($ty,)*), R> for F
+        where
+            F: Fn($($ty),*) -> Fut + Send + Sync + 'static,
+            Fut: Future<Output = McpResult<R>> + Send + 'static,
+            $($ty: FromRequest,)*
+        {
+            fn call(&self, request: HandlerRequest) -> HandlerFuture<R> {
+                $(
+                    let $ty = match $ty::from_request(&request) {
+                        Ok(value) => value,
+                        Err(e) => return Box::pin(async move { Err(e) }),
+                    };
+                )*
+                Box::pin(self($($ty),*))
+            }
+        }
+    };
+}
+
+impl_handler!();
+impl_handler!(T1);
+impl_handler!(T1, T2);
+impl_handler!(T1, T2, T3);
+impl_handler!(T1, T2, T3, T4);
+impl_handler!(T1, T2, T3, T4, T5);
+impl_handler!(T1, T2, T3, T4, T5, T6);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7);
+impl_handler!(T1, T2, T3, T4, T5, T6, T7, T8);
+
+/// Adapter exposing an extractor-based [`Handler`] as a tool or prompt handler
+pub struct ExtractHandler<H, T> {
+    handler: H,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<H, T> ExtractHandler<H, T> {
+    /// Wrap the given handler function
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<H, T> ToolHandler for ExtractHandler<H, T>
+where
+    H: Handler<T, ToolResult>,
+{
+    async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<ToolResult> {
+        self.call_with_context(arguments, RequestContext::new())
+            .await
+    }
+
+    async fn call_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        context: RequestContext,
+    ) -> McpResult<ToolResult> {
+        self.handler
+            .call(HandlerRequest { arguments, context })
+            .await
+    }
+}
+
+#[async_trait]
+impl<H, T> PromptHandler for ExtractHandler<H, T>
+where
+    H: Handler<T, PromptResult>,
+{
+    async fn get(&self, arguments: HashMap<String, Value>) -> McpResult<PromptResult> {
+        self.get_with_context(arguments, RequestContext::new())
+            .await
+    }
+
+    async fn get_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        context: RequestContext,
+    ) -> McpResult<PromptResult> {
+        self.handler
+            .call(HandlerRequest { arguments, context })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::types::{Content, RequestId};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    async fn greet(
+        State(prefix): State<String>,
+        Args(args): Args<Greeting>,
+        Meta(meta): Meta,
+        session: Option<Session>,
+    ) -> McpResult<ToolResult> {
+        assert!(session.is_none());
+        let suffix = meta.get("suffix").and_then(Value::as_str).unwrap_or("");
+        Ok(ToolResult {
+            content: vec![Content::text(format!("{}{}{}", prefix, args.name, suffix))],
+            is_error: None,
+            structured_content: None,
+            meta: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_extractors() {
+        let mut context = RequestContext::for_request(RequestId::from(1));
+        context.state.insert("Hello, ".to_string());
+        context.meta.insert("suffix".to_string(), json!("!"));
+
+        let handler = ExtractHandler::new(greet);
+        let arguments = HashMap::from([("name".to_string(), json!("Ada"))]);
+        let result = handler
+            .call_with_context(arguments, context.clone())
+            .await
+            .unwrap();
+        assert_eq!(result.content, vec![Content::text("Hello, Ada!")]);
+
+        let missing_args = handler.call_with_context(HashMap::new(), context).await;
+        assert!(matches!(missing_args, Err(McpError::Validation(_))));
+
+        let missing_state = handler
+            .call(HashMap::from([("name".to_string(), json!("Ada"))]))
+            .await;
+        assert!(matches!(missing_state, Err(McpError::Internal(_))));
+    }
+}
@@ -3,16 +3,32 @@
 //! This module contains the fundamental building blocks for MCP implementations,
 //! including error handling, resource management, tool execution, and prompt handling.
 
+pub mod context;
 pub mod error;
+pub mod extract;
+pub mod normalize;
 pub mod prompt;
 pub mod resource;
+#[cfg(feature = "validation")]
+pub mod schema;
+pub mod schema_builder;
 pub mod tool;
+#[cfg(feature = "typed")]
+pub mod typed_tool;
 
 // Re-export commonly used items
+pub use context::{Extensions, Peer, PeerSender, RequestContext, Session, SessionData};
 pub use error::{McpError, McpResult};
-pub use prompt::{Prompt, PromptHandler};
-pub use resource::{Resource, ResourceHandler, ResourceTemplate};
-pub use tool::{Tool, ToolHandler};
+pub use prompt::{FnPromptHandler, Prompt, PromptHandler};
+pub use resource::{
+    FnResourceHandler, IntoResourceContents, Resource, ResourceHandler, ResourceTemplate,
+};
+#[cfg(feature = "validation")]
+pub use schema::SchemaValidator;
+pub use schema_builder::Schema;
+pub use tool::{FnToolHandler, Tool, ToolHandler};
+#[cfg(feature = "typed")]
+pub use typed_tool::TypedToolHandler;
 
 // Re-export protocol types through core for convenience
 pub use crate::protocol::types::{
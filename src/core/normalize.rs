@@ -0,0 +1,325 @@
+//! Schema-guided normalization of tool arguments
+//!
+//! Clients, LLM-driven ones in particular, often send `"5"` where the input schema
+//! asks for an integer, or leave out optional arguments whose schema declares a
+//! `default`. When a tool opts in, its arguments are normalized before they are
+//! validated and passed to the handler:
+//!
+//! - missing properties that have a `default` are filled in
+//! - strings are parsed into numbers, integers or booleans when the schema expects one
+//! - a single value is wrapped in an array when the schema expects an array
+//!
+//! Coercions only happen when the value does not already match the schema and the
+//! conversion is lossless, so well-formed arguments are never changed.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Limit on nested `$ref` resolution, guarding against cyclic definitions
+const MAX_REF_DEPTH: usize = 32;
+
+/// A change made to the arguments during normalization
+#[derive(Debug, Clone, PartialEq)]
+pub enum Normalization {
+    /// A missing property was set to its schema default
+    DefaultApplied {
+        /// JSON pointer to the property
+        path: String,
+    },
+    /// A value was converted to the type the schema expects
+    Coerced {
+        /// JSON pointer to the value
+        path: String,
+        /// JSON type of the original value
+        from: &'static str,
+        /// JSON type of the converted value
+        to: &'static str,
+    },
+    /// A single value was wrapped in an array
+    WrappedInArray {
+        /// JSON pointer to the value
+        path: String,
+    },
+}
+
+impl std::fmt::Display for Normalization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Normalization::DefaultApplied { path } => write!(f, "applied default at '{}'", path),
+            Normalization::Coerced { path, from, to } => {
+                write!(f, "coerced {} to {} at '{}'", from, to, path)
+            }
+            Normalization::WrappedInArray { path } => {
+                write!(f, "wrapped value in an array at '{}'", path)
+            }
+        }
+    }
+}
+
+/// Normalize tool arguments in place according to an input schema
+///
+/// # Arguments
+/// * `schema` - The tool's input schema
+/// * `arguments` - Tool arguments to normalize
+///
+/// # Returns
+/// The changes that were made, in the order they were applied
+pub fn normalize_arguments(
+    schema: &Value,
+    arguments: &mut HashMap<String, Value>,
+) -> Vec<Normalization> {
+    let mut object: Map<String, Value> = arguments.drain().collect();
+    let mut changes = Vec::new();
+    Normalizer { root: schema }.object(schema, &mut object, "", 0, &mut changes);
+    arguments.extend(object);
+    changes
+}
+
+struct Normalizer<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Normalizer<'a> {
+    /// Follow a local `$ref`, returning the schema itself if it has none
+    fn resolve(&self, schema: &'a Value, depth: usize) -> Option<(&'a Value, usize)> {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(_) if depth >= MAX_REF_DEPTH => None,
+            Some(reference) => {
+                let target = self.root.pointer(reference.strip_prefix('#')?)?;
+                self.resolve(target, depth + 1)
+            }
+            None => Some((schema, depth)),
+        }
+    }
+
+    fn object(
+        &self,
+        schema: &'a Value,
+        object: &mut Map<String, Value>,
+        path: &str,
+        depth: usize,
+        changes: &mut Vec<Normalization>,
+    ) {
+        let Some((schema, depth)) = self.resolve(schema, depth) else {
+            return;
+        };
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return;
+        };
+
+        for (name, property) in properties {
+            let property_path = format!("{}/{}", path, escape(name));
+            match object.get_mut(name) {
+                Some(value) => self.value(property, value, &property_path, depth, changes),
+                None => {
+                    let default = self
+                        .resolve(property, depth)
+                        .and_then(|(property, _)| property.get("default"));
+                    if let Some(default) = default {
+                        object.insert(name.clone(), default.clone());
+                        changes.push(Normalization::DefaultApplied {
+                            path: property_path,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn value(
+        &self,
+        schema: &'a Value,
+        value: &mut Value,
+        path: &str,
+        depth: usize,
+        changes: &mut Vec<Normalization>,
+    ) {
+        let Some((schema, depth)) = self.resolve(schema, depth) else {
+            return;
+        };
+        let types = schema_types(schema);
+
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            if let Some((coerced, to)) = coerce(value, &types) {
+                changes.push(Normalization::Coerced {
+                    path: path.to_string(),
+                    from: type_name(value),
+                    to,
+                });
+                *value = coerced;
+            } else if types.contains(&"array") && !value.is_null() {
+                *value = Value::Array(vec![value.take()]);
+                changes.push(Normalization::WrappedInArray {
+                    path: path.to_string(),
+                });
+            }
+        }
+
+        match value {
+            Value::Object(object) => self.object(schema, object, path, depth, changes),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items").filter(|s| s.is_object()) {
+                    for (index, item) in items.iter_mut().enumerate() {
+                        let item_path = format!("{}/{}", path, index);
+                        self.value(item_schema, item, &item_path, depth, changes);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The types listed in a schema's `type` keyword
+fn schema_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn matches_type(value: &Value, schema_type: &str) -> bool {
+    match schema_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(is_whole),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Convert a string to the first expected scalar type it parses as without loss
+fn coerce(value: &Value, types: &[&str]) -> Option<(Value, &'static str)> {
+    let text = value.as_str()?.trim();
+    types.iter().find_map(|schema_type| match *schema_type {
+        "integer" => text
+            .parse::<i64>()
+            .ok()
+            .map(Value::from)
+            .or_else(|| text.parse::<u64>().ok().map(Value::from))
+            .map(|v| (v, "integer")),
+        "number" => text
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(|n| match text.parse::<i64>() {
+                Ok(i) => Value::from(i),
+                Err(_) => Value::from(n),
+            })
+            .map(|v| (v, "number")),
+        "boolean" => match text.to_ascii_lowercase().as_str() {
+            "true" => Some((Value::Bool(true), "boolean")),
+            "false" => Some((Value::Bool(false), "boolean")),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn is_whole(n: f64) -> bool {
+    n.is_finite() && n.fract() == 0.0
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a property name for use in a JSON pointer
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_and_coercions() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer"},
+                "ratio": {"type": "number"},
+                "force": {"type": "boolean"},
+                "tags": {"type": "array", "items": {"type": "integer"}},
+                "limit": {"type": "integer", "default": 10},
+                "name": {"type": "string"},
+                "options": {"$ref": "#/$defs/options"}
+            },
+            "$defs": {
+                "options": {
+                    "type": "object",
+                    "properties": {"depth": {"type": "integer", "default": 1}}
+                }
+            }
+        });
+
+        let mut arguments = args(json!({
+            "count": "5",
+            "ratio": " 0.5 ",
+            "force": "TRUE",
+            "tags": "7",
+            "name": "42",
+            "options": {}
+        }));
+        let changes = normalize_arguments(&schema, &mut arguments);
+
+        assert_eq!(
+            arguments,
+            args(json!({
+                "count": 5,
+                "ratio": 0.5,
+                "force": true,
+                "tags": [7],
+                "limit": 10,
+                "name": "42",
+                "options": {"depth": 1}
+            }))
+        );
+        assert!(changes.contains(&Normalization::WrappedInArray {
+            path: "/tags".to_string()
+        }));
+        assert!(changes.contains(&Normalization::Coerced {
+            path: "/tags/0".to_string(),
+            from: "string",
+            to: "integer"
+        }));
+        assert!(changes.contains(&Normalization::DefaultApplied {
+            path: "/options/depth".to_string()
+        }));
+        assert_eq!(changes.len(), 7);
+    }
+
+    #[test]
+    fn test_unconvertible_values_are_left_alone() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "count": {"type": "integer"},
+                "ratio": {"type": "number"}
+            }
+        });
+
+        let mut arguments = args(json!({"count": "5.5", "ratio": "NaN"}));
+        let changes = normalize_arguments(&schema, &mut arguments);
+
+        assert!(changes.is_empty());
+        assert_eq!(arguments, args(json!({"count": "5.5", "ratio": "NaN"})));
+    }
+}
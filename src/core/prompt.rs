@@ -6,7 +6,9 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
 
+use crate::core::context::RequestContext;
 use crate::core::error::{McpError, McpResult};
 use crate::protocol::types::{
     PromptArgument, PromptContent, PromptInfo, PromptMessage, PromptResult,
@@ -23,6 +25,62 @@ pub trait PromptHandler: Send + Sync {
     /// # Returns
     /// Result containing the generated prompt messages or an error
     async fn get(&self, arguments: HashMap<String, Value>) -> McpResult<PromptResult>;
+
+    /// Generate prompt messages with the given arguments and the context of the request
+    ///
+    /// The default implementation ignores the context and delegates to [`PromptHandler::get`].
+    ///
+    /// # Arguments
+    /// * `arguments` - Prompt arguments as key-value pairs
+    /// * `context` - Context of the request for the prompt
+    ///
+    /// # Returns
+    /// Result containing the generated prompt messages or an error
+    async fn get_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        _context: RequestContext,
+    ) -> McpResult<PromptResult> {
+        self.get(arguments).await
+    }
+}
+
+/// Prompt handler wrapping an async closure
+///
+/// The closure receives the prompt arguments and the request context, and can capture
+/// shared state such as an `Arc`.
+pub struct FnPromptHandler<F> {
+    handler: F,
+}
+
+impl<F, Fut> FnPromptHandler<F>
+where
+    F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<PromptResult>> + Send,
+{
+    /// Create a new handler from the given closure
+    pub fn new(handler: F) -> Self {
+        Self { handler }
+    }
+}
+
+#[async_trait]
+impl<F, Fut> PromptHandler for FnPromptHandler<F>
+where
+    F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<PromptResult>> + Send,
+{
+    async fn get(&self, arguments: HashMap<String, Value>) -> McpResult<PromptResult> {
+        (self.handler)(arguments, RequestContext::new()).await
+    }
+
+    async fn get_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        context: RequestContext,
+    ) -> McpResult<PromptResult> {
+        (self.handler)(arguments, context).await
+    }
 }
 
 /// A registered prompt with its handler
@@ -112,10 +170,7 @@ impl PromptMessage {
     pub fn system<S: Into<String>>(content: S) -> Self {
         Self {
             role: "system".to_string(),
-            content: PromptContent::Text {
-                content_type: "text".to_string(),
-                text: content.into(),
-            },
+            content: PromptContent::text(content),
         }
     }
 
@@ -123,10 +178,7 @@ impl PromptMessage {
     pub fn user<S: Into<String>>(content: S) -> Self {
         Self {
             role: "user".to_string(),
-            content: PromptContent::Text {
-                content_type: "text".to_string(),
-                text: content.into(),
-            },
+            content: PromptContent::text(content),
         }
     }
 
@@ -134,10 +186,7 @@ impl PromptMessage {
     pub fn assistant<S: Into<String>>(content: S) -> Self {
         Self {
             role: "assistant".to_string(),
-            content: PromptContent::Text {
-                content_type: "text".to_string(),
-                text: content.into(),
-            },
+            content: PromptContent::text(content),
         }
     }
 
@@ -169,6 +218,7 @@ impl PromptHandler for GreetingPrompt {
                 PromptMessage::system("You are a friendly assistant."),
                 PromptMessage::user(format!("Hello, {}!", name)),
             ],
+            meta: None,
         })
     }
 }
@@ -211,6 +261,7 @@ impl PromptHandler for CodeReviewPrompt {
                 PromptMessage::system(system_prompt),
                 PromptMessage::user(user_prompt),
             ],
+            meta: None,
         })
     }
 }
@@ -253,6 +304,7 @@ impl PromptHandler for SqlQueryPrompt {
                 PromptMessage::system(system_prompt),
                 PromptMessage::user(user_prompt),
             ],
+            meta: None,
         })
     }
 }
@@ -456,22 +508,16 @@ mod tests {
     #[test]
     fn test_prompt_content_creation() {
         let text_content = PromptContent::text("Hello, world!");
-        match text_content {
-            PromptContent::Text { content_type, text } => {
-                assert_eq!(content_type, "text");
-                assert_eq!(text, "Hello, world!");
-            }
-            _ => panic!("Expected text content"),
-        }
+        assert_eq!(
+            serde_json::to_value(&text_content).unwrap(),
+            json!({"type": "text", "text": "Hello, world!"})
+        );
 
         let image_content = PromptContent::image("base64data", "image/png");
         match image_content {
             PromptContent::Image {
-                content_type,
-                data,
-                mime_type,
+                data, mime_type, ..
             } => {
-                assert_eq!(content_type, "image");
                 assert_eq!(data, "base64data");
                 assert_eq!(mime_type, "image/png");
             }
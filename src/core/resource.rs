@@ -5,7 +5,9 @@
 
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::future::Future;
 
+use crate::core::context::RequestContext;
 use crate::core::error::{McpError, McpResult};
 use crate::protocol::types::{ResourceContent, ResourceInfo};
 
@@ -39,6 +41,26 @@ pub trait ResourceHandler: Send + Sync {
         params: &HashMap<String, String>,
     ) -> McpResult<Vec<ResourceContent>>;
 
+    /// Read the content of a resource with the context of the request
+    ///
+    /// The default implementation ignores the context and delegates to [`ResourceHandler::read`].
+    ///
+    /// # Arguments
+    /// * `uri` - URI of the resource to read
+    /// * `params` - Additional parameters for the resource
+    /// * `context` - Context of the request reading the resource
+    ///
+    /// # Returns
+    /// Result containing the resource content or an error
+    async fn read_with_context(
+        &self,
+        uri: &str,
+        params: &HashMap<String, String>,
+        _context: RequestContext,
+    ) -> McpResult<Vec<ResourceContent>> {
+        self.read(uri, params).await
+    }
+
     /// List all available resources
     ///
     /// # Returns
@@ -223,6 +245,106 @@ impl std::fmt::Debug for Resource {
     }
 }
 
+/// Conversion of a resource handler's output into resource contents
+pub trait IntoResourceContents {
+    /// Convert the value into contents of the resource at `uri`
+    ///
+    /// # Arguments
+    /// * `uri` - URI of the resource that was read
+    /// * `mime_type` - MIME type declared for the resource, if any
+    fn into_contents(self, uri: &str, mime_type: Option<&str>) -> Vec<ResourceContent>;
+}
+
+impl IntoResourceContents for String {
+    fn into_contents(self, uri: &str, mime_type: Option<&str>) -> Vec<ResourceContent> {
+        vec![ResourceContent {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.unwrap_or("text/plain").to_string()),
+            text: Some(self),
+            blob: None,
+        }]
+    }
+}
+
+impl IntoResourceContents for &str {
+    fn into_contents(self, uri: &str, mime_type: Option<&str>) -> Vec<ResourceContent> {
+        self.to_string().into_contents(uri, mime_type)
+    }
+}
+
+impl IntoResourceContents for ResourceContent {
+    fn into_contents(self, _uri: &str, _mime_type: Option<&str>) -> Vec<ResourceContent> {
+        vec![self]
+    }
+}
+
+impl IntoResourceContents for Vec<ResourceContent> {
+    fn into_contents(self, _uri: &str, _mime_type: Option<&str>) -> Vec<ResourceContent> {
+        self
+    }
+}
+
+/// Resource handler wrapping an async closure
+///
+/// The closure receives the requested URI and the request context, and returns any
+/// value implementing [`IntoResourceContents`]. It can capture shared state such as an `Arc`.
+pub struct FnResourceHandler<F> {
+    handler: F,
+    mime_type: Option<String>,
+}
+
+impl<F, Fut, O> FnResourceHandler<F>
+where
+    F: Fn(String, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<O>> + Send,
+    O: IntoResourceContents,
+{
+    /// Create a new handler from the given closure
+    pub fn new(handler: F) -> Self {
+        Self {
+            handler,
+            mime_type: None,
+        }
+    }
+
+    /// Set the MIME type reported for text returned by the closure
+    pub fn mime_type<S: Into<String>>(mut self, mime_type: S) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+}
+
+#[async_trait]
+impl<F, Fut, O> ResourceHandler for FnResourceHandler<F>
+where
+    F: Fn(String, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<O>> + Send,
+    O: IntoResourceContents,
+{
+    async fn read(
+        &self,
+        uri: &str,
+        params: &HashMap<String, String>,
+    ) -> McpResult<Vec<ResourceContent>> {
+        self.read_with_context(uri, params, RequestContext::new())
+            .await
+    }
+
+    async fn read_with_context(
+        &self,
+        uri: &str,
+        _params: &HashMap<String, String>,
+        context: RequestContext,
+    ) -> McpResult<Vec<ResourceContent>> {
+        let output = (self.handler)(uri.to_string(), context).await?;
+        Ok(output.into_contents(uri, self.mime_type.as_deref()))
+    }
+
+    async fn list(&self) -> McpResult<Vec<ResourceInfo>> {
+        Ok(vec![])
+    }
+}
+
 // Common resource implementations
 
 /// Simple text resource
@@ -0,0 +1,95 @@
+//! JSON Schema validation for tool arguments
+//!
+//! Schemas are compiled once when a tool is registered and reused for every
+//! call, so invalid schemas are reported up front and argument checks are cheap.
+
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+use crate::core::error::{McpError, McpResult, SchemaViolation};
+
+/// A compiled JSON Schema
+pub struct SchemaValidator {
+    schema: JSONSchema,
+}
+
+impl SchemaValidator {
+    /// Compile a JSON Schema
+    ///
+    /// # Arguments
+    /// * `schema` - The JSON Schema to compile
+    ///
+    /// # Returns
+    /// Result containing the compiled validator or a validation error if the schema is invalid
+    pub fn compile(schema: &Value) -> McpResult<Self> {
+        let schema = JSONSchema::compile(schema)
+            .map_err(|e| McpError::validation(format!("Invalid JSON schema: {}", e)))?;
+        Ok(Self { schema })
+    }
+
+    /// Validate a value against the schema
+    ///
+    /// # Arguments
+    /// * `instance` - The value to validate
+    ///
+    /// # Returns
+    /// Result that is an `McpError::SchemaValidation` listing every violation if the value is invalid
+    pub fn validate(&self, instance: &Value) -> McpResult<()> {
+        if let Err(errors) = self.schema.validate(instance) {
+            let violations = errors
+                .map(|error| SchemaViolation {
+                    path: error.instance_path.to_string(),
+                    schema_path: error.schema_path.to_string(),
+                    message: error.to_string(),
+                })
+                .collect();
+            return Err(McpError::schema_validation(violations));
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SchemaValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchemaValidator").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_invalid_schema_rejected() {
+        let result = SchemaValidator::compile(&json!({"type": "not-a-type"}));
+        assert!(matches!(result, Err(McpError::Validation(_))));
+    }
+
+    #[test]
+    fn test_validation_reports_paths() {
+        let validator = SchemaValidator::compile(&json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["name"]
+        }))
+        .unwrap();
+
+        assert!(validator
+            .validate(&json!({"name": "x", "tags": ["a"]}))
+            .is_ok());
+
+        match validator.validate(&json!({"tags": ["a", 1]})) {
+            Err(McpError::SchemaValidation { violations, .. }) => {
+                let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+                assert!(paths.contains(&""));
+                assert!(paths.contains(&"/tags/1"));
+            }
+            other => panic!("Expected schema validation error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,485 @@
+//! Fluent builder for JSON Schemas
+//!
+//! [`Schema`] builds tool input schemas without writing raw JSON. Mistakes that
+//! a raw `serde_json::Value` would let through — a misspelled type, a required
+//! property that is never declared, bounds that cannot be satisfied, a `$ref` to
+//! a missing definition — are reported by [`Schema::build`]. With the
+//! `validation` feature enabled the result is also checked against the JSON
+//! Schema meta-schema.
+//!
+//! # Examples
+//! ```rust
+//! use mcp_protocol_sdk::core::schema_builder::Schema;
+//!
+//! let schema = Schema::object()
+//!     .prop("path", Schema::string().description("File to read"))
+//!     .prop("limit", Schema::integer().minimum(1).default_value(100))
+//!     .required(["path"])
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(schema["properties"]["path"]["type"], "string");
+//! ```
+
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::error::{McpError, McpResult};
+
+/// The JSON types a schema can be restricted to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaType {
+    /// A JSON string
+    String,
+    /// Any JSON number
+    Number,
+    /// A JSON number without a fractional part
+    Integer,
+    /// `true` or `false`
+    Boolean,
+    /// A JSON array
+    Array,
+    /// A JSON object
+    Object,
+    /// `null`
+    Null,
+}
+
+impl SchemaType {
+    /// The name of the type as used in the `"type"` keyword
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaType::String => "string",
+            SchemaType::Number => "number",
+            SchemaType::Integer => "integer",
+            SchemaType::Boolean => "boolean",
+            SchemaType::Array => "array",
+            SchemaType::Object => "object",
+            SchemaType::Null => "null",
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, SchemaType::Number | SchemaType::Integer)
+    }
+}
+
+/// Keywords that only apply to a single kind of type
+const STRING_KEYWORDS: &[&str] = &["minLength", "maxLength", "pattern"];
+const NUMERIC_KEYWORDS: &[&str] = &[
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+];
+const ARRAY_KEYWORDS: &[&str] = &["minItems", "maxItems", "uniqueItems"];
+
+/// A JSON Schema under construction
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    schema_type: Option<SchemaType>,
+    keywords: Map<String, Value>,
+    properties: BTreeMap<String, Schema>,
+    required: Vec<String>,
+    items: Option<Box<Schema>>,
+    defs: BTreeMap<String, Schema>,
+}
+
+impl Schema {
+    fn typed(schema_type: SchemaType) -> Self {
+        Self {
+            schema_type: Some(schema_type),
+            ..Self::default()
+        }
+    }
+
+    /// A schema accepting any value
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// A schema accepting objects
+    pub fn object() -> Self {
+        Self::typed(SchemaType::Object)
+    }
+
+    /// A schema accepting strings
+    pub fn string() -> Self {
+        Self::typed(SchemaType::String)
+    }
+
+    /// A schema accepting any number
+    pub fn number() -> Self {
+        Self::typed(SchemaType::Number)
+    }
+
+    /// A schema accepting integers
+    pub fn integer() -> Self {
+        Self::typed(SchemaType::Integer)
+    }
+
+    /// A schema accepting `true` and `false`
+    pub fn boolean() -> Self {
+        Self::typed(SchemaType::Boolean)
+    }
+
+    /// A schema accepting `null`
+    pub fn null() -> Self {
+        Self::typed(SchemaType::Null)
+    }
+
+    /// A schema accepting arrays whose items match `items`
+    pub fn array(items: Schema) -> Self {
+        Self {
+            items: Some(Box::new(items)),
+            ..Self::typed(SchemaType::Array)
+        }
+    }
+
+    /// A schema referring to a definition added to the root with [`Schema::def`]
+    pub fn reference<S: Into<String>>(name: S) -> Self {
+        Self::any().keyword("$ref", format!("#/$defs/{}", name.into()))
+    }
+
+    /// The type this schema is restricted to, if any
+    pub fn schema_type(&self) -> Option<SchemaType> {
+        self.schema_type
+    }
+
+    fn keyword<V: Into<Value>>(mut self, name: &str, value: V) -> Self {
+        self.keywords.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Set the title
+    pub fn title<S: Into<String>>(self, title: S) -> Self {
+        self.keyword("title", title.into())
+    }
+
+    /// Set the description
+    pub fn description<S: Into<String>>(self, description: S) -> Self {
+        self.keyword("description", description.into())
+    }
+
+    /// Set the default value
+    pub fn default_value<V: Into<Value>>(self, value: V) -> Self {
+        self.keyword("default", value)
+    }
+
+    /// Restrict the value to one of the given values
+    pub fn enum_values<I, V>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+        self.keyword("enum", values)
+    }
+
+    /// Set the format, e.g. `"uri"` or `"date-time"`
+    pub fn format<S: Into<String>>(self, format: S) -> Self {
+        self.keyword("format", format.into())
+    }
+
+    /// Set the minimum length of a string
+    pub fn min_length(self, length: u64) -> Self {
+        self.keyword("minLength", length)
+    }
+
+    /// Set the maximum length of a string
+    pub fn max_length(self, length: u64) -> Self {
+        self.keyword("maxLength", length)
+    }
+
+    /// Set a regular expression strings must match
+    pub fn pattern<S: Into<String>>(self, pattern: S) -> Self {
+        self.keyword("pattern", pattern.into())
+    }
+
+    /// Set the inclusive lower bound of a number
+    pub fn minimum<N: Into<Value>>(self, minimum: N) -> Self {
+        self.keyword("minimum", minimum)
+    }
+
+    /// Set the inclusive upper bound of a number
+    pub fn maximum<N: Into<Value>>(self, maximum: N) -> Self {
+        self.keyword("maximum", maximum)
+    }
+
+    /// Set the exclusive lower bound of a number
+    pub fn exclusive_minimum<N: Into<Value>>(self, minimum: N) -> Self {
+        self.keyword("exclusiveMinimum", minimum)
+    }
+
+    /// Set the exclusive upper bound of a number
+    pub fn exclusive_maximum<N: Into<Value>>(self, maximum: N) -> Self {
+        self.keyword("exclusiveMaximum", maximum)
+    }
+
+    /// Require numbers to be a multiple of the given value
+    pub fn multiple_of<N: Into<Value>>(self, factor: N) -> Self {
+        self.keyword("multipleOf", factor)
+    }
+
+    /// Set the minimum number of array items
+    pub fn min_items(self, count: u64) -> Self {
+        self.keyword("minItems", count)
+    }
+
+    /// Set the maximum number of array items
+    pub fn max_items(self, count: u64) -> Self {
+        self.keyword("maxItems", count)
+    }
+
+    /// Require array items to be unique
+    pub fn unique_items(self, unique: bool) -> Self {
+        self.keyword("uniqueItems", unique)
+    }
+
+    /// Add a property to an object schema
+    pub fn prop<S: Into<String>>(mut self, name: S, schema: Schema) -> Self {
+        self.properties.insert(name.into(), schema);
+        self
+    }
+
+    /// Mark properties as required
+    pub fn required<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        for name in names {
+            let name = name.into();
+            if !self.required.contains(&name) {
+                self.required.push(name);
+            }
+        }
+        self
+    }
+
+    /// Allow or forbid properties that are not declared
+    pub fn additional_properties(self, allowed: bool) -> Self {
+        self.keyword("additionalProperties", allowed)
+    }
+
+    /// Add a definition under `$defs`, referenced with [`Schema::reference`]
+    pub fn def<S: Into<String>>(mut self, name: S, schema: Schema) -> Self {
+        self.defs.insert(name.into(), schema);
+        self
+    }
+
+    /// Check the schema and convert it to JSON
+    ///
+    /// # Returns
+    /// Result containing the JSON Schema or a validation error naming the offending location
+    pub fn build(self) -> McpResult<Value> {
+        let mut refs = BTreeSet::new();
+        self.check("", &mut refs)?;
+        for reference in refs {
+            let name = reference.strip_prefix("#/$defs/").unwrap_or(&reference);
+            if !self.defs.contains_key(name) {
+                return Err(invalid(
+                    "",
+                    format!("'$ref' to undefined definition '{}'", name),
+                ));
+            }
+        }
+
+        let value = self.into_value();
+
+        #[cfg(feature = "validation")]
+        crate::core::schema::SchemaValidator::compile(&value)?;
+
+        Ok(value)
+    }
+
+    /// Check this schema and its subschemas, collecting every `$ref`
+    fn check(&self, path: &str, refs: &mut BTreeSet<String>) -> McpResult<()> {
+        let kind = self.schema_type;
+        let applies = |keywords: &[&str], ok: bool| -> McpResult<()> {
+            match keywords.iter().find(|k| self.keywords.contains_key(**k)) {
+                Some(keyword) if !ok => Err(invalid(
+                    path,
+                    format!(
+                        "'{}' does not apply to {}",
+                        keyword,
+                        kind.map_or("untyped schemas", |t| t.as_str())
+                    ),
+                )),
+                _ => Ok(()),
+            }
+        };
+        applies(STRING_KEYWORDS, kind == Some(SchemaType::String))?;
+        applies(NUMERIC_KEYWORDS, kind.is_some_and(|t| t.is_numeric()))?;
+        applies(ARRAY_KEYWORDS, kind == Some(SchemaType::Array))?;
+
+        let is_object = kind == Some(SchemaType::Object);
+        if !is_object && (!self.properties.is_empty() || !self.required.is_empty()) {
+            return Err(invalid(path, "properties require an object schema"));
+        }
+        if let Some(name) = self
+            .required
+            .iter()
+            .find(|n| !self.properties.contains_key(*n))
+        {
+            return Err(invalid(
+                path,
+                format!("required property '{}' is not declared", name),
+            ));
+        }
+
+        check_bounds(path, &self.keywords, "minimum", "maximum")?;
+        check_bounds(path, &self.keywords, "minLength", "maxLength")?;
+        check_bounds(path, &self.keywords, "minItems", "maxItems")?;
+
+        if let Some(Value::Array(values)) = self.keywords.get("enum") {
+            if values.is_empty() {
+                return Err(invalid(path, "'enum' must list at least one value"));
+            }
+        }
+        if let Some(Value::String(reference)) = self.keywords.get("$ref") {
+            refs.insert(reference.clone());
+        }
+
+        for (name, property) in &self.properties {
+            property.check(&format!("{}/properties/{}", path, name), refs)?;
+        }
+        if let Some(items) = &self.items {
+            items.check(&format!("{}/items", path), refs)?;
+        }
+        for (name, def) in &self.defs {
+            def.check(&format!("{}/$defs/{}", path, name), refs)?;
+        }
+
+        Ok(())
+    }
+
+    fn into_value(self) -> Value {
+        let mut map = Map::new();
+        if let Some(schema_type) = self.schema_type {
+            map.insert("type".to_string(), Value::from(schema_type.as_str()));
+        }
+        if !self.properties.is_empty() || self.schema_type == Some(SchemaType::Object) {
+            let properties = self
+                .properties
+                .into_iter()
+                .map(|(name, schema)| (name, schema.into_value()))
+                .collect();
+            map.insert("properties".to_string(), Value::Object(properties));
+        }
+        if !self.required.is_empty() {
+            map.insert("required".to_string(), Value::from(self.required));
+        }
+        if let Some(items) = self.items {
+            map.insert("items".to_string(), items.into_value());
+        }
+        if !self.defs.is_empty() {
+            let defs = self
+                .defs
+                .into_iter()
+                .map(|(name, schema)| (name, schema.into_value()))
+                .collect();
+            map.insert("$defs".to_string(), Value::Object(defs));
+        }
+        map.extend(self.keywords);
+        Value::Object(map)
+    }
+}
+
+fn invalid<S: Into<String>>(path: &str, message: S) -> McpError {
+    let path = if path.is_empty() { "/" } else { path };
+    McpError::validation(format!("Invalid schema at '{}': {}", path, message.into()))
+}
+
+/// Reject a lower bound that is greater than its upper bound
+fn check_bounds(path: &str, keywords: &Map<String, Value>, min: &str, max: &str) -> McpResult<()> {
+    let lower = keywords.get(min).and_then(Value::as_f64);
+    let upper = keywords.get(max).and_then(Value::as_f64);
+    match (lower, upper) {
+        (Some(lower), Some(upper)) if lower > upper => Err(invalid(
+            path,
+            format!(
+                "'{}' ({}) is greater than '{}' ({})",
+                min, lower, max, upper
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_schema() {
+        let schema = Schema::object()
+            .prop(
+                "path",
+                Schema::string().description("File path").format("uri"),
+            )
+            .prop(
+                "mode",
+                Schema::string()
+                    .enum_values(["read", "write"])
+                    .default_value("read"),
+            )
+            .prop("depth", Schema::integer().minimum(0).maximum(10))
+            .prop("tags", Schema::array(Schema::string()).unique_items(true))
+            .prop("owner", Schema::reference("user"))
+            .required(["path"])
+            .additional_properties(false)
+            .def("user", Schema::object().prop("id", Schema::integer()))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File path", "format": "uri"},
+                    "mode": {"type": "string", "enum": ["read", "write"], "default": "read"},
+                    "depth": {"type": "integer", "minimum": 0, "maximum": 10},
+                    "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true},
+                    "owner": {"$ref": "#/$defs/user"}
+                },
+                "required": ["path"],
+                "additionalProperties": false,
+                "$defs": {
+                    "user": {"type": "object", "properties": {"id": {"type": "integer"}}}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_inconsistent_schemas() {
+        let cases = [
+            Schema::object().required(["missing"]),
+            Schema::string().minimum(1),
+            Schema::integer().minimum(5).maximum(1),
+            Schema::object().prop("a", Schema::string().enum_values(Vec::<Value>::new())),
+            Schema::object().prop("a", Schema::reference("nowhere")),
+            Schema::string().prop("a", Schema::string()),
+        ];
+
+        for schema in cases {
+            let result = schema.clone().build();
+            assert!(
+                matches!(result, Err(McpError::Validation(_))),
+                "{:?} should be rejected",
+                schema
+            );
+        }
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn test_build_checks_meta_schema() {
+        let result = Schema::integer().multiple_of(0).build();
+        assert!(matches!(result, Err(McpError::Validation(_))));
+    }
+}
@@ -6,9 +6,15 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
 
+use crate::core::context::RequestContext;
 use crate::core::error::{McpError, McpResult};
-use crate::protocol::types::{Content, ToolInfo, ToolResult};
+use crate::core::normalize::normalize_arguments;
+#[cfg(feature = "validation")]
+use crate::core::schema::SchemaValidator;
+use crate::core::schema_builder::Schema;
+use crate::protocol::types::{Content, ToolAnnotations, ToolInfo, ToolResult};
 
 /// Trait for implementing tool handlers
 #[async_trait]
@@ -21,6 +27,62 @@ pub trait ToolHandler: Send + Sync {
     /// # Returns
     /// Result containing the tool execution result or an error
     async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<ToolResult>;
+
+    /// Execute the tool with the given arguments and the context of the request
+    ///
+    /// The default implementation ignores the context and delegates to [`ToolHandler::call`].
+    ///
+    /// # Arguments
+    /// * `arguments` - Tool arguments as key-value pairs
+    /// * `context` - Context of the request invoking the tool
+    ///
+    /// # Returns
+    /// Result containing the tool execution result or an error
+    async fn call_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        _context: RequestContext,
+    ) -> McpResult<ToolResult> {
+        self.call(arguments).await
+    }
+}
+
+/// Tool handler wrapping an async closure
+///
+/// The closure receives the tool arguments and the request context, and can capture
+/// shared state such as an `Arc`.
+pub struct FnToolHandler<F> {
+    handler: F,
+}
+
+impl<F, Fut> FnToolHandler<F>
+where
+    F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<ToolResult>> + Send,
+{
+    /// Create a new handler from the given closure
+    pub fn new(handler: F) -> Self {
+        Self { handler }
+    }
+}
+
+#[async_trait]
+impl<F, Fut> ToolHandler for FnToolHandler<F>
+where
+    F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync,
+    Fut: Future<Output = McpResult<ToolResult>> + Send,
+{
+    async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<ToolResult> {
+        (self.handler)(arguments, RequestContext::new()).await
+    }
+
+    async fn call_with_context(
+        &self,
+        arguments: HashMap<String, Value>,
+        context: RequestContext,
+    ) -> McpResult<ToolResult> {
+        (self.handler)(arguments, context).await
+    }
 }
 
 /// A registered tool with its handler
@@ -31,6 +93,14 @@ pub struct Tool {
     pub handler: Box<dyn ToolHandler>,
     /// Whether the tool is currently enabled
     pub enabled: bool,
+    /// Whether arguments are normalized against the input schema before validation
+    normalize_arguments: bool,
+    /// Compiled input schema used to validate arguments before dispatch
+    #[cfg(feature = "validation")]
+    validator: Option<SchemaValidator>,
+    /// Compiled output schema used to validate structured results
+    #[cfg(feature = "validation")]
+    output_validator: Option<SchemaValidator>,
 }
 
 impl Tool {
@@ -53,12 +123,150 @@ impl Tool {
         Self {
             info: ToolInfo {
                 name,
+                title: None,
                 description,
                 input_schema,
+                output_schema: None,
+                annotations: None,
             },
             handler: Box::new(handler),
             enabled: true,
+            normalize_arguments: false,
+            #[cfg(feature = "validation")]
+            validator: None,
+            #[cfg(feature = "validation")]
+            output_validator: None,
+        }
+    }
+
+    /// Compile the tool's input schema so that arguments are validated before each call
+    ///
+    /// Without the `validation` feature this is a no-op.
+    ///
+    /// # Returns
+    /// Result that is a validation error if the input schema is not a valid JSON Schema
+    pub fn compile_schema(&mut self) -> McpResult<()> {
+        #[cfg(feature = "validation")]
+        {
+            let validator = SchemaValidator::compile(&self.info.input_schema).map_err(|e| {
+                McpError::validation(format!(
+                    "Tool '{}' has an invalid input schema: {}",
+                    self.info.name, e
+                ))
+            })?;
+            self.validator = Some(validator);
+
+            self.output_validator = match &self.info.output_schema {
+                Some(schema) => Some(SchemaValidator::compile(schema).map_err(|e| {
+                    McpError::validation(format!(
+                        "Tool '{}' has an invalid output schema: {}",
+                        self.info.name, e
+                    ))
+                })?),
+                None => None,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Validate arguments against the compiled input schema
+    ///
+    /// Arguments are accepted as-is if the schema has not been compiled.
+    ///
+    /// # Arguments
+    /// * `arguments` - Tool arguments as key-value pairs
+    ///
+    /// # Returns
+    /// Result that is an `McpError::SchemaValidation` if the arguments do not match the schema
+    pub fn validate_arguments(&self, arguments: &HashMap<String, Value>) -> McpResult<()> {
+        #[cfg(feature = "validation")]
+        if let Some(validator) = &self.validator {
+            let instance = Value::Object(
+                arguments
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            );
+            validator.validate(&instance)?;
+        }
+        #[cfg(not(feature = "validation"))]
+        let _ = arguments;
+
+        Ok(())
+    }
+
+    /// Check a successful result against the tool's output schema
+    ///
+    /// Tools that declare an output schema must return structured content, which is
+    /// validated against the compiled schema when the `validation` feature is enabled.
+    /// Error results are not checked.
+    ///
+    /// # Arguments
+    /// * `result` - The result returned by the handler
+    ///
+    /// # Returns
+    /// Result that is an internal error if the structured content is missing or invalid
+    pub fn validate_output(&self, result: &ToolResult) -> McpResult<()> {
+        if self.info.output_schema.is_none() || result.is_error == Some(true) {
+            return Ok(());
+        }
+
+        let Some(structured) = &result.structured_content else {
+            return Err(McpError::internal(format!(
+                "Tool '{}' declares an output schema but returned no structured content",
+                self.info.name
+            )));
+        };
+
+        #[cfg(feature = "validation")]
+        if let Some(validator) = &self.output_validator {
+            validator.validate(structured).map_err(|e| {
+                McpError::internal(format!(
+                    "Tool '{}' returned structured content that does not match its output schema: {}",
+                    self.info.name, e
+                ))
+            })?;
+        }
+        #[cfg(not(feature = "validation"))]
+        let _ = structured;
+
+        Ok(())
+    }
+
+    /// Enable or disable argument normalization
+    ///
+    /// When enabled, missing arguments are filled from schema defaults and values are
+    /// coerced to the types the input schema expects before validation. See
+    /// [`crate::core::normalize`].
+    pub fn set_normalize_arguments(&mut self, enabled: bool) {
+        self.normalize_arguments = enabled;
+    }
+
+    /// Check if argument normalization is enabled
+    pub fn normalizes_arguments(&self) -> bool {
+        self.normalize_arguments
+    }
+
+    /// Normalize arguments if enabled and validate them against the input schema
+    ///
+    /// # Arguments
+    /// * `arguments` - Tool arguments as key-value pairs
+    ///
+    /// # Returns
+    /// Result containing the arguments to pass to the handler or a validation error
+    pub fn prepare_arguments(
+        &self,
+        mut arguments: HashMap<String, Value>,
+    ) -> McpResult<HashMap<String, Value>> {
+        if self.normalize_arguments {
+            for change in normalize_arguments(&self.info.input_schema, &mut arguments) {
+                tracing::debug!("Tool '{}': {}", self.info.name, change);
+            }
         }
+
+        self.validate_arguments(&arguments)?;
+        Ok(arguments)
     }
 
     /// Enable the tool
@@ -76,7 +284,7 @@ impl Tool {
         self.enabled
     }
 
-    /// Execute the tool if it's enabled
+    /// Execute the tool if it's enabled and the arguments match its input schema
     ///
     /// # Arguments
     /// * `arguments` - Tool arguments as key-value pairs
@@ -91,7 +299,10 @@ impl Tool {
             )));
         }
 
-        self.handler.call(arguments).await
+        let arguments = self.prepare_arguments(arguments)?;
+        let result = self.handler.call(arguments).await?;
+        self.validate_output(&result)?;
+        Ok(result)
     }
 }
 
@@ -161,10 +372,10 @@ impl ToolHandler for EchoTool {
             .unwrap_or("Hello, World!");
 
         Ok(ToolResult {
-            content: vec![Content::Text {
-                text: message.to_string(),
-            }],
+            content: vec![Content::text(message.to_string())],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -188,10 +399,10 @@ impl ToolHandler for AdditionTool {
         let result = a + b;
 
         Ok(ToolResult {
-            content: vec![Content::Text {
-                text: result.to_string(),
-            }],
+            content: vec![Content::text(result.to_string())],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -210,10 +421,10 @@ impl ToolHandler for TimestampTool {
             .as_secs();
 
         Ok(ToolResult {
-            content: vec![Content::Text {
-                text: timestamp.to_string(),
-            }],
+            content: vec![Content::text(timestamp.to_string())],
             is_error: None,
+            structured_content: None,
+            meta: None,
         })
     }
 }
@@ -221,8 +432,13 @@ impl ToolHandler for TimestampTool {
 /// Builder for creating tools with fluent API
 pub struct ToolBuilder {
     name: String,
+    title: Option<String>,
     description: Option<String>,
+    output_schema: Option<Value>,
+    annotations: Option<ToolAnnotations>,
     input_schema: Option<Value>,
+    schema_builder: Option<Schema>,
+    normalize_arguments: bool,
 }
 
 impl ToolBuilder {
@@ -230,8 +446,13 @@ impl ToolBuilder {
     pub fn new<S: Into<String>>(name: S) -> Self {
         Self {
             name: name.into(),
+            title: None,
             description: None,
+            output_schema: None,
+            annotations: None,
             input_schema: None,
+            schema_builder: None,
+            normalize_arguments: false,
         }
     }
 
@@ -241,17 +462,59 @@ impl ToolBuilder {
         self
     }
 
+    /// Set the human-readable title
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Set the behavior hints
+    pub fn annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Set the output schema
+    ///
+    /// Results of the tool must then carry structured content matching the schema.
+    pub fn output_schema(mut self, schema: Value) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
     /// Set the input schema
     pub fn schema(mut self, schema: Value) -> Self {
         self.input_schema = Some(schema);
+        self.schema_builder = None;
+        self
+    }
+
+    /// Set the input schema from a [`Schema`] builder
+    ///
+    /// The schema is checked when the tool is built.
+    pub fn input_schema(mut self, schema: Schema) -> Self {
+        self.schema_builder = Some(schema);
+        self.input_schema = None;
+        self
+    }
+
+    /// Enable argument normalization for the tool
+    ///
+    /// See [`Tool::set_normalize_arguments`].
+    pub fn normalize_arguments(mut self, enabled: bool) -> Self {
+        self.normalize_arguments = enabled;
         self
     }
 
     /// Build the tool with the given handler
-    pub fn build<H>(self, handler: H) -> McpResult<Tool>
+    pub fn build<H>(mut self, handler: H) -> McpResult<Tool>
     where
         H: ToolHandler + 'static,
     {
+        if let Some(schema) = self.schema_builder {
+            self.input_schema = Some(schema.build()?);
+        }
+
         let schema = self.input_schema.unwrap_or_else(|| {
             serde_json::json!({
                 "type": "object",
@@ -260,7 +523,30 @@ impl ToolBuilder {
             })
         });
 
-        Ok(Tool::new(self.name, self.description, schema, handler))
+        let mut tool = Tool::new(self.name, self.description, schema, handler);
+        tool.info.title = self.title;
+        tool.info.output_schema = self.output_schema;
+        tool.info.annotations = self.annotations;
+        tool.set_normalize_arguments(self.normalize_arguments);
+        tool.compile_schema()?;
+        Ok(tool)
+    }
+
+    /// Build the tool from an async function taking typed arguments
+    ///
+    /// The input schema is derived from `Args` and replaces any schema set with
+    /// [`ToolBuilder::schema`].
+    #[cfg(feature = "typed")]
+    pub fn build_typed<Args, Out, F, Fut>(self, handler: F) -> McpResult<Tool>
+    where
+        Args: serde::de::DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        Out: serde::Serialize + Send + 'static,
+        F: Fn(Args) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = McpResult<Out>> + Send + 'static,
+    {
+        let handler = crate::core::typed_tool::TypedToolHandler::new(handler);
+        let schema = handler.input_schema();
+        self.schema(schema).build(handler)
     }
 }
 
@@ -277,7 +563,7 @@ mod tests {
 
         let result = tool.call(args).await.unwrap();
         match &result.content[0] {
-            Content::Text { text } => assert_eq!(text, "test message"),
+            Content::Text { text, .. } => assert_eq!(text, "test message"),
             _ => panic!("Expected text content"),
         }
     }
@@ -291,7 +577,7 @@ mod tests {
 
         let result = tool.call(args).await.unwrap();
         match &result.content[0] {
-            Content::Text { text } => assert_eq!(text, "8"),
+            Content::Text { text, .. } => assert_eq!(text, "8"),
             _ => panic!("Expected text content"),
         }
     }
@@ -358,4 +644,118 @@ mod tests {
         assert_eq!(tool.info.name, "test");
         assert_eq!(tool.info.description, Some("A test tool".to_string()));
     }
+
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_tool_builder_validates_arguments() {
+        let tool = ToolBuilder::new("add")
+            .schema(json!({
+                "type": "object",
+                "properties": {"a": {"type": "number"}, "b": {"type": "number"}},
+                "required": ["a", "b"]
+            }))
+            .build(AdditionTool)
+            .unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("a".to_string(), json!("five"));
+        match tool.call(args).await.unwrap_err() {
+            McpError::SchemaValidation { violations, .. } => {
+                let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+                assert!(paths.contains(&"/a"));
+                assert!(paths.contains(&""));
+            }
+            other => panic!("Expected schema validation error, got {:?}", other),
+        }
+
+        let invalid = ToolBuilder::new("broken")
+            .schema(json!({"type": "object", "required": "a"}))
+            .build(EchoTool);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_tool_builder_input_schema() {
+        let tool = ToolBuilder::new("read")
+            .input_schema(
+                Schema::object()
+                    .prop("path", Schema::string())
+                    .required(["path"]),
+            )
+            .build(EchoTool)
+            .unwrap();
+        assert_eq!(tool.info.input_schema["required"], json!(["path"]));
+
+        let invalid = ToolBuilder::new("read")
+            .input_schema(Schema::object().required(["path"]))
+            .build(EchoTool);
+        assert!(matches!(invalid, Err(McpError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_tool_builder_normalizes_arguments() {
+        let tool = ToolBuilder::new("add")
+            .schema(json!({
+                "type": "object",
+                "properties": {
+                    "a": {"type": "number"},
+                    "b": {"type": "number", "default": 1}
+                },
+                "required": ["a"]
+            }))
+            .normalize_arguments(true)
+            .build(AdditionTool)
+            .unwrap();
+
+        let mut args = HashMap::new();
+        args.insert("a".to_string(), json!("5"));
+        let result = tool.call(args).await.unwrap();
+        assert_eq!(result.content, vec![Content::text("6")]);
+    }
+
+    #[tokio::test]
+    async fn test_tool_output_schema() {
+        let structured = |value: Value| {
+            FnToolHandler::new(move |_args, _context| {
+                let value = value.clone();
+                async move { Ok(ToolResult::structured(value)) }
+            })
+        };
+        let builder = || {
+            ToolBuilder::new("sum")
+                .title("Sum")
+                .annotations(ToolAnnotations {
+                    read_only_hint: Some(true),
+                    ..Default::default()
+                })
+                .output_schema(json!({
+                    "type": "object",
+                    "properties": {"sum": {"type": "integer"}},
+                    "required": ["sum"]
+                }))
+        };
+
+        let tool = builder().build(structured(json!({"sum": 3}))).unwrap();
+        assert_eq!(tool.info.title.as_deref(), Some("Sum"));
+        assert!(!tool.info.annotations.as_ref().unwrap().is_destructive());
+        let result = tool.call(HashMap::new()).await.unwrap();
+        assert_eq!(result.structured_content, Some(json!({"sum": 3})));
+
+        let unstructured = builder().build(EchoTool).unwrap();
+        let mut args = HashMap::new();
+        args.insert("message".to_string(), json!("hi"));
+        assert!(matches!(
+            unstructured.call(args).await,
+            Err(McpError::Internal(_))
+        ));
+
+        #[cfg(feature = "validation")]
+        {
+            let mismatched = builder().build(structured(json!({"sum": "3"}))).unwrap();
+            assert!(matches!(
+                mismatched.call(HashMap::new()).await,
+                Err(McpError::Internal(_))
+            ));
+        }
+    }
 }
@@ -0,0 +1,209 @@
+//! Typed tool handlers
+//!
+//! This module provides an adapter that lets tools be written as async functions over
+//! strongly typed argument structs. The tool's input schema is derived from the argument
+//! type, so the schema advertised to clients always matches the code that parses it.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+
+use crate::core::error::{McpError, McpResult};
+use crate::core::tool::ToolHandler;
+use crate::protocol::types::{Content, ToolResult};
+
+/// Generate a tool input schema from a Rust type
+///
+/// # Returns
+/// The JSON Schema for `T` as a JSON value
+pub fn schema_for<T: JsonSchema>() -> Value {
+    let schema = schemars::gen::SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>();
+    serde_json::to_value(schema).unwrap_or_else(|_| serde_json::json!({ "type": "object" }))
+}
+
+/// Tool handler adapter for async functions taking typed arguments
+///
+/// Arguments are deserialized into `Args` before the function is called, and the
+/// function's `Out` value is converted into tool result content: strings become text
+/// content as-is, any other value is serialized as JSON text.
+///
+/// # Examples
+/// ```rust
+/// use mcp_protocol_sdk::core::typed_tool::TypedToolHandler;
+/// use mcp_protocol_sdk::McpResult;
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct AddArgs {
+///     a: f64,
+///     b: f64,
+/// }
+///
+/// let handler = TypedToolHandler::new(|args: AddArgs| async move {
+///     McpResult::Ok(args.a + args.b)
+/// });
+/// let schema = handler.input_schema();
+/// assert_eq!(schema["required"], serde_json::json!(["a", "b"]));
+/// ```
+pub struct TypedToolHandler<Args, Out, F> {
+    handler: F,
+    _marker: PhantomData<fn(Args) -> Out>,
+}
+
+impl<Args, Out, F, Fut> TypedToolHandler<Args, Out, F>
+where
+    Args: DeserializeOwned + JsonSchema + Send + 'static,
+    Out: Serialize + Send + 'static,
+    F: Fn(Args) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = McpResult<Out>> + Send + 'static,
+{
+    /// Create a new typed handler wrapping the given async function
+    pub fn new(handler: F) -> Self {
+        Self {
+            handler,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the input schema derived from the argument type
+    pub fn input_schema(&self) -> Value {
+        schema_for::<Args>()
+    }
+}
+
+#[async_trait]
+impl<Args, Out, F, Fut> ToolHandler for TypedToolHandler<Args, Out, F>
+where
+    Args: DeserializeOwned + JsonSchema + Send + 'static,
+    Out: Serialize + Send + 'static,
+    F: Fn(Args) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = McpResult<Out>> + Send + 'static,
+{
+    async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<ToolResult> {
+        let args = parse_arguments::<Args>(arguments)?;
+        let output = (self.handler)(args).await?;
+        output_to_result(output)
+    }
+}
+
+/// Deserialize handler arguments, reporting the path of the offending field on failure
+pub(crate) fn parse_arguments<T: DeserializeOwned>(
+    arguments: HashMap<String, Value>,
+) -> McpResult<T> {
+    let value = Value::Object(arguments.into_iter().collect());
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let path = e.path().to_string();
+        if path == "." {
+            McpError::validation(format!("Invalid arguments: {}", e.inner()))
+        } else {
+            McpError::validation(format!("Invalid argument '{}': {}", path, e.inner()))
+        }
+    })
+}
+
+/// Convert a handler's output into a tool result
+fn output_to_result<T: Serialize>(output: T) -> McpResult<ToolResult> {
+    let text = match serde_json::to_value(output)? {
+        Value::String(text) => text,
+        value => serde_json::to_string_pretty(&value)?,
+    };
+
+    Ok(ToolResult {
+        content: vec![Content::text(text)],
+        is_error: None,
+        structured_content: None,
+        meta: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, JsonSchema)]
+    struct SearchArgs {
+        /// Text to search for
+        query: String,
+        limit: Option<u32>,
+        filters: Filters,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct Filters {
+        tags: Vec<String>,
+    }
+
+    #[derive(Serialize)]
+    struct SearchOutput {
+        matches: u32,
+    }
+
+    fn search_handler() -> impl ToolHandler {
+        TypedToolHandler::new(|args: SearchArgs| async move {
+            Ok(SearchOutput {
+                matches: args.query.len() as u32
+                    + args.limit.unwrap_or(10)
+                    + args.filters.tags.len() as u32,
+            })
+        })
+    }
+
+    #[test]
+    fn test_schema_derived_from_args() {
+        let schema = schema_for::<SearchArgs>();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], json!(["filters", "query"]));
+        assert_eq!(
+            schema["properties"]["query"]["description"],
+            "Text to search for"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_typed_handler_call() {
+        let args = HashMap::from([
+            ("query".to_string(), json!("rust")),
+            ("filters".to_string(), json!({"tags": ["a", "b"]})),
+        ]);
+
+        let result = search_handler().call(args).await.unwrap();
+        match &result.content[0] {
+            Content::Text { text, .. } => {
+                assert_eq!(serde_json::from_str::<Value>(text).unwrap()["matches"], 16)
+            }
+            _ => panic!("Expected text content"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_handler_reports_argument_path() {
+        let args = HashMap::from([
+            ("query".to_string(), json!("rust")),
+            ("filters".to_string(), json!({"tags": ["a", 5]})),
+        ]);
+
+        match search_handler().call(args).await.unwrap_err() {
+            McpError::Validation(message) => assert!(message.contains("filters.tags[1]")),
+            other => panic!("Expected validation error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_string_output_is_plain_text() {
+        #[derive(Deserialize, JsonSchema)]
+        struct Empty {}
+
+        let handler = TypedToolHandler::new(|_: Empty| async { Ok("done") });
+        let result = handler.call(HashMap::new()).await.unwrap();
+        assert_eq!(result.content, vec![Content::text("done")]);
+    }
+}
@@ -35,8 +35,10 @@
 //!             .unwrap_or("Hello, World!");
 //!         
 //!         Ok(ToolResult {
-//!             content: vec![Content::Text { text: message.to_string() }],
+//!             content: vec![Content::text(message.to_string())],
 //!             is_error: None,
+//!             structured_content: None,
+//!             meta: None,
 //!         })
 //!     }
 //! }
@@ -81,6 +83,22 @@ pub mod utils;
 pub use core::error::{McpError, McpResult};
 pub use protocol::types::*;
 
+/// Re-export of `schemars` for deriving argument schemas of typed tools
+#[cfg(feature = "typed")]
+pub use schemars;
+
+/// Attribute macros for declaring tools, prompts, resources and servers
+#[cfg(feature = "macros")]
+pub use mcp_protocol_sdk_macros::{mcp_prompt, mcp_resource, mcp_server, mcp_tool};
+
+// Lets macro-generated `::mcp_protocol_sdk` paths resolve inside this crate
+#[cfg(feature = "macros")]
+extern crate self as mcp_protocol_sdk;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private;
+
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use crate::client::McpClient;
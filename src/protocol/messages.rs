@@ -4,10 +4,13 @@
 //! logic. These messages follow the JSON-RPC 2.0 specification and represent the various operations
 //! supported by the Model Context Protocol.
 
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::de::{self, DeserializeOwned};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
+use crate::core::error::{McpError, McpResult};
 use crate::protocol::types::*;
 
 // ============================================================================
@@ -25,6 +28,9 @@ pub struct InitializeParams {
     /// Protocol version being used
     #[serde(rename = "protocolVersion")]
     pub protocol_version: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the initialize request
@@ -38,6 +44,17 @@ pub struct InitializeResult {
     /// Protocol version being used
     #[serde(rename = "protocolVersion")]
     pub protocol_version: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+/// Parameters for the notifications/initialized notification
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct InitializedParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 // ============================================================================
@@ -50,6 +67,9 @@ pub struct ListToolsParams {
     /// Optional cursor for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the tools/list request
@@ -60,6 +80,9 @@ pub struct ListToolsResult {
     /// Cursor for pagination (if more tools are available)
     #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the tools/call request
@@ -70,6 +93,9 @@ pub struct CallToolParams {
     /// Arguments to pass to the tool
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<HashMap<String, Value>>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the tools/call request
@@ -80,6 +106,12 @@ pub struct CallToolResult {
     /// Whether this result represents an error
     #[serde(rename = "isError", skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// Structured result matching the tool's output schema
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 // ============================================================================
@@ -92,6 +124,9 @@ pub struct ListResourcesParams {
     /// Optional cursor for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the resources/list request
@@ -102,6 +137,9 @@ pub struct ListResourcesResult {
     /// Cursor for pagination (if more resources are available)
     #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the resources/read request
@@ -109,6 +147,9 @@ pub struct ListResourcesResult {
 pub struct ReadResourceParams {
     /// URI of the resource to read
     pub uri: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the resources/read request
@@ -116,6 +157,9 @@ pub struct ReadResourceParams {
 pub struct ReadResourceResult {
     /// Contents of the resource
     pub contents: Vec<ResourceContent>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the resources/subscribe request
@@ -123,33 +167,54 @@ pub struct ReadResourceResult {
 pub struct SubscribeResourceParams {
     /// URI of the resource to subscribe to
     pub uri: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the resources/subscribe request
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct SubscribeResourceResult {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SubscribeResourceResult {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 /// Parameters for the resources/unsubscribe request
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UnsubscribeResourceParams {
     /// URI of the resource to unsubscribe from
     pub uri: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the resources/unsubscribe request
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct UnsubscribeResourceResult {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct UnsubscribeResourceResult {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 /// Parameters for the resources/updated notification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ResourceUpdatedParams {
     /// URI of the resource that was updated
     pub uri: String,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the resources/list_changed notification
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ResourceListChangedParams {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResourceListChangedParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 // ============================================================================
 // Prompt Messages
@@ -161,6 +226,9 @@ pub struct ListPromptsParams {
     /// Optional cursor for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the prompts/list request
@@ -171,6 +239,9 @@ pub struct ListPromptsResult {
     /// Cursor for pagination (if more prompts are available)
     #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
     pub next_cursor: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the prompts/get request
@@ -181,6 +252,9 @@ pub struct GetPromptParams {
     /// Arguments to pass to the prompt
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arguments: Option<HashMap<String, Value>>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the prompts/get request
@@ -191,11 +265,18 @@ pub struct GetPromptResult {
     pub description: Option<String>,
     /// Messages generated by the prompt
     pub messages: Vec<PromptMessage>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Parameters for the prompts/list_changed notification
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PromptListChangedParams {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PromptListChangedParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 // ============================================================================
 // Sampling Messages
@@ -230,6 +311,9 @@ pub struct CreateMessageParams {
     /// Metadata to include with the request
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, Value>>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the sampling/createMessage request
@@ -244,6 +328,9 @@ pub struct CreateMessageResult {
     /// Stop reason
     #[serde(rename = "stopReason", skip_serializing_if = "Option::is_none")]
     pub stop_reason: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// A message in a sampling conversation
@@ -284,20 +371,50 @@ pub struct ModelPreferences {
 // ============================================================================
 
 /// Parameters for the tools/list_changed notification
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ToolListChangedParams {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolListChangedParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 // ============================================================================
 // Ping Messages
 // ============================================================================
 
 /// Parameters for the ping request (no parameters)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PingParams {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PingParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 /// Result of the ping request (no result)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PingResult {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+// ============================================================================
+// Cancellation Messages
+// ============================================================================
+
+/// Parameters for the notifications/cancelled notification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct PingResult {}
+pub struct CancelledParams {
+    /// ID of the request to cancel
+    #[serde(rename = "requestId")]
+    pub request_id: RequestId,
+    /// Optional reason for the cancellation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 // ============================================================================
 // Logging Messages
@@ -308,11 +425,18 @@ pub struct PingResult {}
 pub struct SetLoggingLevelParams {
     /// The logging level to set
     pub level: LoggingLevel,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 /// Result of the logging/setLevel request
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct SetLoggingLevelResult {}
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SetLoggingLevelResult {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
 
 /// Logging level enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -338,6 +462,9 @@ pub struct LoggingMessageParams {
     pub logger: Option<String>,
     /// The log message data
     pub data: Value,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
 }
 
 // ============================================================================
@@ -355,6 +482,270 @@ pub struct ProgressParams {
     /// Optional total count
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total: Option<u32>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+// ============================================================================
+// Roots Messages
+// ============================================================================
+
+/// Parameters for the roots/list request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ListRootsParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+/// Result of the roots/list request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListRootsResult {
+    /// Roots exposed by the client
+    pub roots: Vec<Root>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+/// Parameters for the roots/list_changed notification
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RootsListChangedParams {
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Map<String, Value>>,
+}
+
+// ============================================================================
+// Typed Messages
+// ============================================================================
+
+/// Wire form of a typed message, used to deserialize it
+#[derive(Deserialize)]
+struct RawMessage {
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+/// Deserialize message parameters, reporting malformed ones as invalid params
+fn parse_message_params<T: DeserializeOwned>(params: Value) -> McpResult<T> {
+    serde_json::from_value(params)
+        .map_err(|e| McpError::validation(format!("Invalid parameters: {}", e)))
+}
+
+/// Define an enum with one variant per method, serialized as `{"method", "params"}`
+macro_rules! typed_messages {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($params:ty) = $method:path,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant($params),
+            )*
+        }
+
+        impl $name {
+            /// Get the JSON-RPC method name of the message
+            pub fn method(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => $method,)*
+                }
+            }
+
+            /// Parse a message from its method name and parameters
+            ///
+            /// Missing parameters are parsed as an empty object.
+            ///
+            /// # Arguments
+            /// * `method` - JSON-RPC method name
+            /// * `params` - Optional message parameters
+            ///
+            /// # Returns
+            /// Result containing the message, or an error if the method is unknown or
+            /// the parameters do not match it
+            pub fn from_parts(method: &str, params: Option<Value>) -> McpResult<Self> {
+                let params = params.unwrap_or_else(|| Value::Object(Map::new()));
+                match method {
+                    $($method => Ok(Self::$variant(parse_message_params(params)?)),)*
+                    _ => Err(McpError::MethodNotFound(method.to_string())),
+                }
+            }
+
+            /// Split the message into its method name and serialized parameters
+            pub fn into_parts(self) -> McpResult<(&'static str, Value)> {
+                let method = self.method();
+                let params = match self {
+                    $(Self::$variant(params) => serde_json::to_value(params)?,)*
+                };
+                Ok((method, params))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut message = serializer.serialize_struct(stringify!($name), 2)?;
+                message.serialize_field("method", self.method())?;
+                match self {
+                    $(Self::$variant(params) => message.serialize_field("params", params)?,)*
+                }
+                message.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let raw = RawMessage::deserialize(deserializer)?;
+                Self::from_parts(&raw.method, raw.params).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+typed_messages! {
+    /// Requests sent from the client to the server
+    pub enum ClientRequest {
+        /// `initialize`
+        Initialize(InitializeParams) = methods::INITIALIZE,
+        /// `ping`
+        Ping(PingParams) = methods::PING,
+        /// `tools/list`
+        ListTools(ListToolsParams) = methods::TOOLS_LIST,
+        /// `tools/call`
+        CallTool(CallToolParams) = methods::TOOLS_CALL,
+        /// `resources/list`
+        ListResources(ListResourcesParams) = methods::RESOURCES_LIST,
+        /// `resources/read`
+        ReadResource(ReadResourceParams) = methods::RESOURCES_READ,
+        /// `resources/subscribe`
+        Subscribe(SubscribeResourceParams) = methods::RESOURCES_SUBSCRIBE,
+        /// `resources/unsubscribe`
+        Unsubscribe(UnsubscribeResourceParams) = methods::RESOURCES_UNSUBSCRIBE,
+        /// `prompts/list`
+        ListPrompts(ListPromptsParams) = methods::PROMPTS_LIST,
+        /// `prompts/get`
+        GetPrompt(GetPromptParams) = methods::PROMPTS_GET,
+        /// `logging/setLevel`
+        SetLoggingLevel(SetLoggingLevelParams) = methods::LOGGING_SET_LEVEL,
+    }
+}
+
+typed_messages! {
+    /// Requests sent from the server to the client
+    pub enum ServerRequest {
+        /// `ping`
+        Ping(PingParams) = methods::PING,
+        /// `sampling/createMessage`
+        CreateMessage(CreateMessageParams) = methods::SAMPLING_CREATE_MESSAGE,
+        /// `roots/list`
+        ListRoots(ListRootsParams) = methods::ROOTS_LIST,
+    }
+}
+
+typed_messages! {
+    /// Notifications sent from the client to the server
+    pub enum ClientNotification {
+        /// `notifications/initialized`
+        Initialized(InitializedParams) = methods::INITIALIZED,
+        /// `notifications/cancelled`
+        Cancelled(CancelledParams) = methods::CANCELLED,
+        /// `progress`
+        Progress(ProgressParams) = methods::PROGRESS,
+        /// `notifications/roots/list_changed`
+        RootsListChanged(RootsListChangedParams) = methods::ROOTS_LIST_CHANGED,
+    }
+}
+
+typed_messages! {
+    /// Notifications sent from the server to the client
+    pub enum ServerNotification {
+        /// `tools/list_changed`
+        ToolListChanged(ToolListChangedParams) = methods::TOOLS_LIST_CHANGED,
+        /// `resources/updated`
+        ResourceUpdated(ResourceUpdatedParams) = methods::RESOURCES_UPDATED,
+        /// `resources/list_changed`
+        ResourceListChanged(ResourceListChangedParams) = methods::RESOURCES_LIST_CHANGED,
+        /// `prompts/list_changed`
+        PromptListChanged(PromptListChangedParams) = methods::PROMPTS_LIST_CHANGED,
+        /// `logging/message`
+        LoggingMessage(LoggingMessageParams) = methods::LOGGING_MESSAGE,
+        /// `progress`
+        Progress(ProgressParams) = methods::PROGRESS,
+    }
+}
+
+impl ClientRequest {
+    /// Build the JSON-RPC request carrying this message
+    pub fn into_request(self, id: RequestId) -> McpResult<JsonRpcRequest> {
+        let (method, params) = self.into_parts()?;
+        Ok(JsonRpcRequest::new(id, method.to_string(), Some(params))?)
+    }
+}
+
+impl TryFrom<JsonRpcRequest> for ClientRequest {
+    type Error = McpError;
+
+    fn try_from(request: JsonRpcRequest) -> McpResult<Self> {
+        Self::from_parts(&request.method, request.params)
+    }
+}
+
+impl ServerRequest {
+    /// Build the JSON-RPC request carrying this message
+    pub fn into_request(self, id: RequestId) -> McpResult<JsonRpcRequest> {
+        let (method, params) = self.into_parts()?;
+        Ok(JsonRpcRequest::new(id, method.to_string(), Some(params))?)
+    }
+}
+
+impl TryFrom<JsonRpcRequest> for ServerRequest {
+    type Error = McpError;
+
+    fn try_from(request: JsonRpcRequest) -> McpResult<Self> {
+        Self::from_parts(&request.method, request.params)
+    }
+}
+
+impl ClientNotification {
+    /// Build the JSON-RPC notification carrying this message
+    pub fn into_notification(self) -> McpResult<JsonRpcNotification> {
+        let (method, params) = self.into_parts()?;
+        Ok(JsonRpcNotification::new(method.to_string(), Some(params))?)
+    }
+}
+
+impl TryFrom<JsonRpcNotification> for ClientNotification {
+    type Error = McpError;
+
+    fn try_from(notification: JsonRpcNotification) -> McpResult<Self> {
+        Self::from_parts(&notification.method, notification.params)
+    }
+}
+
+impl ServerNotification {
+    /// Build the JSON-RPC notification carrying this message
+    pub fn into_notification(self) -> McpResult<JsonRpcNotification> {
+        let (method, params) = self.into_parts()?;
+        Ok(JsonRpcNotification::new(method.to_string(), Some(params))?)
+    }
+}
+
+impl TryFrom<JsonRpcNotification> for ServerNotification {
+    type Error = McpError;
+
+    fn try_from(notification: JsonRpcNotification) -> McpResult<Self> {
+        Self::from_parts(&notification.method, notification.params)
+    }
 }
 
 // ============================================================================
@@ -372,6 +763,7 @@ impl InitializeParams {
             client_info,
             capabilities,
             protocol_version,
+            meta: None,
         }
     }
 }
@@ -387,6 +779,7 @@ impl InitializeResult {
             server_info,
             capabilities,
             protocol_version,
+            meta: None,
         }
     }
 }
@@ -394,21 +787,41 @@ impl InitializeResult {
 impl CallToolParams {
     /// Create new call tool parameters
     pub fn new(name: String, arguments: Option<HashMap<String, Value>>) -> Self {
-        Self { name, arguments }
+        Self {
+            name,
+            arguments,
+            meta: None,
+        }
+    }
+}
+
+impl CallToolResult {
+    /// Deserialize the structured content into `T`
+    ///
+    /// # Returns
+    /// `None` if the result has no structured content, otherwise the deserialization result
+    pub fn structured_content_as<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> Option<Result<T, serde_json::Error>> {
+        self.structured_content.clone().map(serde_json::from_value)
     }
 }
 
 impl ReadResourceParams {
     /// Create new read resource parameters
     pub fn new(uri: String) -> Self {
-        Self { uri }
+        Self { uri, meta: None }
     }
 }
 
 impl GetPromptParams {
     /// Create new get prompt parameters
     pub fn new(name: String, arguments: Option<HashMap<String, Value>>) -> Self {
-        Self { name, arguments }
+        Self {
+            name,
+            arguments,
+            meta: None,
+        }
     }
 }
 
@@ -492,6 +905,11 @@ pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
 pub mod methods {
     /// Initialize the connection
     pub const INITIALIZE: &str = "initialize";
+    /// Notification that the client finished initialization
+    pub const INITIALIZED: &str = "notifications/initialized";
+
+    /// Notification that a request was cancelled
+    pub const CANCELLED: &str = "notifications/cancelled";
 
     /// Ping to check connection
     pub const PING: &str = "ping";
@@ -533,6 +951,11 @@ pub mod methods {
 
     /// Progress notification
     pub const PROGRESS: &str = "progress";
+
+    /// List the client's roots
+    pub const ROOTS_LIST: &str = "roots/list";
+    /// Notification when the client's root list changes
+    pub const ROOTS_LIST_CHANGED: &str = "notifications/roots/list_changed";
 }
 
 #[cfg(test)]
@@ -623,4 +1046,65 @@ mod tests {
         assert_eq!(methods::PROMPTS_GET, "prompts/get");
         assert_eq!(methods::SAMPLING_CREATE_MESSAGE, "sampling/createMessage");
     }
+
+    #[test]
+    fn test_meta_round_trip() {
+        let params: CallToolParams = serde_json::from_value(serde_json::json!({
+            "name": "query",
+            "_meta": {"progressToken": 7, "vendor/trace": "abc"}
+        }))
+        .unwrap();
+        let meta = params.meta.as_ref().unwrap();
+        assert_eq!(meta["progressToken"], 7);
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["_meta"]["vendor/trace"], "abc");
+
+        let ping = serde_json::to_value(PingResult::default()).unwrap();
+        assert_eq!(ping, serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_typed_messages() {
+        let request: ClientRequest = serde_json::from_value(json!({
+            "method": "tools/call",
+            "params": {"name": "search", "arguments": {"q": "rust"}}
+        }))
+        .unwrap();
+        let ClientRequest::CallTool(params) = &request else {
+            panic!("Expected a tools/call request");
+        };
+        assert_eq!(params.name, "search");
+        assert_eq!(request.method(), methods::TOOLS_CALL);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["method"], "tools/call");
+        assert_eq!(json["params"]["arguments"]["q"], "rust");
+
+        // Missing parameters are parsed as an empty object
+        let ping = ClientRequest::from_parts(methods::PING, None).unwrap();
+        assert_eq!(ping, ClientRequest::Ping(PingParams::default()));
+
+        let unknown = ClientRequest::from_parts("tools/frobnicate", None);
+        assert!(matches!(unknown, Err(McpError::MethodNotFound(_))));
+        let malformed = ClientRequest::from_parts(methods::TOOLS_CALL, Some(json!({})));
+        assert!(matches!(malformed, Err(McpError::Validation(_))));
+
+        let notification = ServerNotification::ResourceUpdated(ResourceUpdatedParams {
+            uri: "file:///a.txt".to_string(),
+            meta: None,
+        })
+        .into_notification()
+        .unwrap();
+        assert_eq!(notification.method, methods::RESOURCES_UPDATED);
+        let parsed = ServerNotification::try_from(notification).unwrap();
+        assert!(
+            matches!(parsed, ServerNotification::ResourceUpdated(p) if p.uri == "file:///a.txt")
+        );
+
+        let request = ServerRequest::ListRoots(ListRootsParams::default())
+            .into_request(RequestId::from(1))
+            .unwrap();
+        assert_eq!(request.method, methods::ROOTS_LIST);
+    }
 }
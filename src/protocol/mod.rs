@@ -8,6 +8,9 @@ pub mod types;
 pub mod validation;
 
 // Re-export commonly used types
-pub use messages::{methods, MCP_PROTOCOL_VERSION};
+pub use messages::{
+    methods, ClientNotification, ClientRequest, ServerNotification, ServerRequest,
+    MCP_PROTOCOL_VERSION,
+};
 pub use types::*;
 pub use validation::*;
@@ -3,7 +3,8 @@
 //! This module contains all the core types defined by the Model Context Protocol
 //! specification, including messages, capabilities, and content types.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Information about an MCP server
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -46,6 +47,9 @@ pub struct ClientCapabilities {
     /// Sampling-related capabilities
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sampling: Option<SamplingCapability>,
+    /// Roots-related capabilities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
 }
 
 /// Prompt-related server capabilities
@@ -79,6 +83,24 @@ pub struct ToolsCapability {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SamplingCapability {}
 
+/// Roots-related client capabilities
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RootsCapability {
+    /// Whether the client sends notifications when its roots change
+    #[serde(rename = "listChanged", skip_serializing_if = "Option::is_none")]
+    pub list_changed: Option<bool>,
+}
+
+/// A root directory or file that the client exposes to the server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Root {
+    /// URI of the root (typically a `file://` URI)
+    pub uri: String,
+    /// Human-readable name of the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
 /// Content that can be returned by tools, resources, or prompts
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
@@ -88,6 +110,9 @@ pub enum Content {
     Text {
         /// The text content
         text: String,
+        /// Hints about how the content should be used
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
     },
     /// Image content
     #[serde(rename = "image")]
@@ -97,7 +122,73 @@ pub enum Content {
         /// MIME type of the image
         #[serde(rename = "mimeType")]
         mime_type: String,
+        /// Hints about how the content should be used
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
     },
+    /// Audio content
+    #[serde(rename = "audio")]
+    Audio {
+        /// Base64-encoded audio data
+        data: String,
+        /// MIME type of the audio
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+        /// Hints about how the content should be used
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    /// Resource contents embedded in the message
+    #[serde(rename = "resource")]
+    Resource {
+        /// The embedded resource contents
+        resource: ResourceContent,
+        /// Hints about how the content should be used
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+    /// Link to a resource the client can read or subscribe to
+    #[serde(rename = "resource_link")]
+    ResourceLink {
+        /// URI of the resource
+        uri: String,
+        /// Name of the resource
+        name: String,
+        /// Description of the resource
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// MIME type of the resource
+        #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+        mime_type: Option<String>,
+        /// Hints about how the content should be used
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        annotations: Option<Annotations>,
+    },
+}
+
+/// Intended recipient of a piece of content
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// The human user
+    User,
+    /// The model
+    Assistant,
+}
+
+/// Hints about how clients should use, display or prioritize content
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotations {
+    /// Who the content is intended for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<Vec<Role>>,
+    /// Importance from 0 (least) to 1 (most important)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f64>,
+    /// When the content was last modified, as an ISO 8601 timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
 }
 
 /// Content of a resource
@@ -124,6 +215,12 @@ pub struct ToolResult {
     /// Whether this result represents an error
     #[serde(rename = "isError", skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// Structured result matching the tool's output schema
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 /// Information about a tool
@@ -131,12 +228,55 @@ pub struct ToolResult {
 pub struct ToolInfo {
     /// Name of the tool
     pub name: String,
+    /// Human-readable title of the tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     /// Description of what the tool does
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     /// JSON Schema describing the tool's input parameters
     #[serde(rename = "inputSchema")]
     pub input_schema: serde_json::Value,
+    /// JSON Schema describing the tool's structured output
+    #[serde(rename = "outputSchema", skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+    /// Hints about the tool's behavior
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Hints describing how a tool behaves
+///
+/// Annotations are advisory: clients must not rely on them for security decisions
+/// when the server is untrusted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Human-readable title of the tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Whether the tool does not modify its environment (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// Whether the tool may perform destructive updates (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Whether repeated calls with the same arguments have no additional effect (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// Whether the tool interacts with external entities (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// Check whether the tool may modify its environment destructively
+    ///
+    /// Applies the protocol defaults for unset hints: a tool is destructive unless it
+    /// is marked read-only or its destructive hint is false.
+    pub fn is_destructive(&self) -> bool {
+        !self.read_only_hint.unwrap_or(false) && self.destructive_hint.unwrap_or(true)
+    }
 }
 
 /// Information about a resource
@@ -189,29 +329,9 @@ pub struct PromptMessage {
 }
 
 /// Content of a prompt message
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum PromptContent {
-    /// Text content
-    Text {
-        /// Type identifier
-        #[serde(rename = "type")]
-        content_type: String,
-        /// The text content
-        text: String,
-    },
-    /// Image content
-    Image {
-        /// Type identifier
-        #[serde(rename = "type")]
-        content_type: String,
-        /// Base64-encoded image data
-        data: String,
-        /// MIME type of the image
-        #[serde(rename = "mimeType")]
-        mime_type: String,
-    },
-}
+///
+/// Prompt messages share the content model of tool results.
+pub type PromptContent = Content;
 
 /// Result of prompt execution
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -221,17 +341,137 @@ pub struct PromptResult {
     pub description: Option<String>,
     /// Messages generated by the prompt
     pub messages: Vec<PromptMessage>,
+    /// Protocol and vendor metadata sent as `_meta`
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 // JSON-RPC 2.0 message types
 
+/// ID correlating a JSON-RPC request with its response
+///
+/// Only integers and strings are accepted; a number and a string with the same
+/// digits are different IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum RequestId {
+    /// Integer ID
+    Number(i64),
+    /// String ID
+    String(String),
+}
+
+impl<'de> Deserialize<'de> for RequestId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RequestIdVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for RequestIdVisitor {
+            type Value = RequestId;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an integer or string request ID")
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<RequestId, E> {
+                Ok(RequestId::Number(value))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<RequestId, E> {
+                i64::try_from(value)
+                    .map(RequestId::Number)
+                    .map_err(|_| E::custom("request ID is out of range"))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<RequestId, E> {
+                Ok(RequestId::String(value.to_string()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, value: String) -> Result<RequestId, E> {
+                Ok(RequestId::String(value))
+            }
+        }
+
+        deserializer.deserialize_any(RequestIdVisitor)
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestId::Number(id) => write!(f, "{}", id),
+            RequestId::String(id) => write!(f, "{:?}", id),
+        }
+    }
+}
+
+impl From<i64> for RequestId {
+    fn from(id: i64) -> Self {
+        RequestId::Number(id)
+    }
+}
+
+impl From<String> for RequestId {
+    fn from(id: String) -> Self {
+        RequestId::String(id)
+    }
+}
+
+impl From<&str> for RequestId {
+    fn from(id: &str) -> Self {
+        RequestId::String(id.to_string())
+    }
+}
+
+/// Generates request IDs that are never reused by the peer that owns it
+///
+/// The counter is not reset when a client reconnects, so a late response from an
+/// earlier connection can never be taken for the answer to a new request.
+#[derive(Debug, Default)]
+pub struct RequestIdGenerator {
+    prefix: Option<String>,
+    last: AtomicU64,
+}
+
+impl RequestIdGenerator {
+    /// Create a generator of integer IDs, starting at 1
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a generator of string IDs of the form `{prefix}-{n}`
+    ///
+    /// String IDs pass unchanged through proxies that rewrite integer IDs as strings.
+    pub fn with_prefix<S: Into<String>>(prefix: S) -> Self {
+        Self {
+            prefix: Some(prefix.into()),
+            last: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the next unused ID
+    pub fn next_id(&self) -> RequestId {
+        let n = self.last.fetch_add(1, Ordering::Relaxed) + 1;
+        match &self.prefix {
+            Some(prefix) => RequestId::String(format!("{}-{}", prefix, n)),
+            None => RequestId::Number(n as i64),
+        }
+    }
+}
+
+/// Deserialize a response ID, which must be present but may be `null`
+fn nullable_request_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<RequestId>, D::Error> {
+    Option::<RequestId>::deserialize(deserializer)
+}
+
 /// JSON-RPC 2.0 request message
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JsonRpcRequest {
     /// JSON-RPC version (always "2.0")
     pub jsonrpc: String,
     /// Request ID for correlation
-    pub id: serde_json::Value,
+    pub id: RequestId,
     /// Method name being called
     pub method: String,
     /// Method parameters
@@ -244,8 +484,9 @@ pub struct JsonRpcRequest {
 pub struct JsonRpcResponse {
     /// JSON-RPC version (always "2.0")
     pub jsonrpc: String,
-    /// Request ID for correlation
-    pub id: serde_json::Value,
+    /// ID of the request being answered, `None` if it could not be determined
+    #[serde(deserialize_with = "nullable_request_id")]
+    pub id: Option<RequestId>,
     /// Result of the method call (if successful)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<serde_json::Value>,
@@ -278,6 +519,26 @@ pub struct JsonRpcNotification {
     pub params: Option<serde_json::Value>,
 }
 
+/// A single JSON-RPC message or a batch of them sent as one JSON array
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum JsonRpcBatch<T> {
+    /// A single message
+    Single(T),
+    /// Several messages sent together
+    Batch(Vec<T>),
+}
+
+impl<T> JsonRpcBatch<T> {
+    /// Get the messages, whether there is one or several
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            JsonRpcBatch::Single(message) => vec![message],
+            JsonRpcBatch::Batch(messages) => messages,
+        }
+    }
+}
+
 // Standard JSON-RPC error codes
 /// Invalid JSON was received
 pub const PARSE_ERROR: i32 = -32700;
@@ -297,11 +558,19 @@ pub const TOOL_NOT_FOUND: i32 = -32000;
 pub const RESOURCE_NOT_FOUND: i32 = -32001;
 /// Prompt not found
 pub const PROMPT_NOT_FOUND: i32 = -32002;
+/// The request timed out
+pub const REQUEST_TIMEOUT: i32 = -32003;
+/// The request was cancelled
+pub const REQUEST_CANCELLED: i32 = -32004;
+/// The caller is not authenticated or not authorized
+pub const UNAUTHORIZED: i32 = -32005;
+/// Too many requests; `data.retryAfterMs` may say when to try again
+pub const RATE_LIMITED: i32 = -32006;
 
 impl JsonRpcRequest {
     /// Create a new JSON-RPC request
     pub fn new<T: Serialize>(
-        id: serde_json::Value,
+        id: RequestId,
         method: String,
         params: Option<T>,
     ) -> Result<Self, serde_json::Error> {
@@ -321,28 +590,27 @@ impl JsonRpcRequest {
 
 impl JsonRpcResponse {
     /// Create a successful JSON-RPC response
-    pub fn success<T: Serialize>(
-        id: serde_json::Value,
-        result: T,
-    ) -> Result<Self, serde_json::Error> {
+    pub fn success<T: Serialize>(id: RequestId, result: T) -> Result<Self, serde_json::Error> {
         Ok(Self {
             jsonrpc: "2.0".to_string(),
-            id,
+            id: Some(id),
             result: Some(serde_json::to_value(result)?),
             error: None,
         })
     }
 
     /// Create an error JSON-RPC response
-    pub fn error(
-        id: serde_json::Value,
+    ///
+    /// `id` is `None` when the ID of the failed request could not be determined.
+    pub fn error<I: Into<Option<RequestId>>>(
+        id: I,
         code: i32,
         message: String,
         data: Option<serde_json::Value>,
     ) -> Self {
         Self {
             jsonrpc: "2.0".to_string(),
-            id,
+            id: id.into(),
             result: None,
             error: Some(JsonRpcError {
                 code,
@@ -374,7 +642,10 @@ impl JsonRpcNotification {
 impl Content {
     /// Create text content
     pub fn text<S: Into<String>>(text: S) -> Self {
-        Self::Text { text: text.into() }
+        Self::Text {
+            text: text.into(),
+            annotations: None,
+        }
     }
 
     /// Create image content
@@ -382,27 +653,97 @@ impl Content {
         Self::Image {
             data: data.into(),
             mime_type: mime_type.into(),
+            annotations: None,
         }
     }
-}
 
-impl PromptContent {
-    /// Create text prompt content
-    pub fn text<S: Into<String>>(text: S) -> Self {
-        Self::Text {
-            content_type: "text".to_string(),
-            text: text.into(),
+    /// Create audio content
+    pub fn audio<S: Into<String>>(data: S, mime_type: S) -> Self {
+        Self::Audio {
+            data: data.into(),
+            mime_type: mime_type.into(),
+            annotations: None,
         }
     }
 
-    /// Create image prompt content
-    pub fn image<S: Into<String>>(data: S, mime_type: S) -> Self {
-        Self::Image {
-            content_type: "image".to_string(),
-            data: data.into(),
-            mime_type: mime_type.into(),
+    /// Create content embedding the given resource contents
+    pub fn resource(resource: ResourceContent) -> Self {
+        Self::Resource {
+            resource,
+            annotations: None,
+        }
+    }
+
+    /// Create a link to the resource with the given URI and name
+    pub fn resource_link<S: Into<String>>(uri: S, name: S) -> Self {
+        Self::ResourceLink {
+            uri: uri.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+            annotations: None,
+        }
+    }
+
+    /// Set the annotations of the content
+    pub fn with_annotations(mut self, value: Annotations) -> Self {
+        *self.annotations_mut() = Some(value);
+        self
+    }
+
+    /// Get the annotations of the content, if any
+    pub fn annotations(&self) -> Option<&Annotations> {
+        match self {
+            Self::Text { annotations, .. }
+            | Self::Image { annotations, .. }
+            | Self::Audio { annotations, .. }
+            | Self::Resource { annotations, .. }
+            | Self::ResourceLink { annotations, .. } => annotations.as_ref(),
+        }
+    }
+
+    fn annotations_mut(&mut self) -> &mut Option<Annotations> {
+        match self {
+            Self::Text { annotations, .. }
+            | Self::Image { annotations, .. }
+            | Self::Audio { annotations, .. }
+            | Self::Resource { annotations, .. }
+            | Self::ResourceLink { annotations, .. } => annotations,
+        }
+    }
+
+    /// Get the text of text content
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl ToolResult {
+    /// Create a result carrying structured content
+    ///
+    /// The value is also serialized into a text content block for clients that do
+    /// not read structured content.
+    pub fn structured(value: serde_json::Value) -> Self {
+        Self {
+            content: vec![Content::text(value.to_string())],
+            is_error: None,
+            structured_content: Some(value),
+            meta: None,
         }
     }
+
+    /// Deserialize the structured content into `T`
+    ///
+    /// # Returns
+    /// `None` if the result has no structured content, otherwise the deserialization result
+    pub fn structured_content_as<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> Option<Result<T, serde_json::Error>> {
+        self.structured_content.clone().map(serde_json::from_value)
+    }
 }
 
 #[cfg(test)]
@@ -432,12 +773,30 @@ mod tests {
                 "mimeType": "image/png"
             })
         );
+
+        let link =
+            Content::resource_link("file:///out.png", "out.png").with_annotations(Annotations {
+                audience: Some(vec![Role::User]),
+                priority: Some(0.5),
+                last_modified: None,
+            });
+        let json = serde_json::to_value(&link).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "type": "resource_link",
+                "uri": "file:///out.png",
+                "name": "out.png",
+                "annotations": {"audience": ["user"], "priority": 0.5}
+            })
+        );
+        assert_eq!(serde_json::from_value::<Content>(json).unwrap(), link);
     }
 
     #[test]
     fn test_jsonrpc_request() {
         let request = JsonRpcRequest::new(
-            json!(1),
+            RequestId::from(1),
             "test_method".to_string(),
             Some(json!({"param": "value"})),
         )
@@ -445,19 +804,55 @@ mod tests {
 
         assert_eq!(request.jsonrpc, "2.0");
         assert_eq!(request.method, "test_method");
-        assert_eq!(request.id, json!(1));
+        assert_eq!(request.id, RequestId::Number(1));
+    }
+
+    #[test]
+    fn test_request_id() {
+        assert_eq!(
+            serde_json::from_value::<RequestId>(json!(7)).unwrap(),
+            RequestId::Number(7)
+        );
+        assert_eq!(
+            serde_json::from_value::<RequestId>(json!("7")).unwrap(),
+            RequestId::String("7".to_string())
+        );
+        assert_ne!(RequestId::Number(7), RequestId::from("7"));
+        for invalid in [json!(1.5), json!(null), json!({"id": 1}), json!(u64::MAX)] {
+            assert!(serde_json::from_value::<RequestId>(invalid).is_err());
+        }
+
+        let response: JsonRpcResponse = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {"code": PARSE_ERROR, "message": "Parse error"}
+        }))
+        .unwrap();
+        assert_eq!(response.id, None);
+        assert_eq!(serde_json::to_value(&response).unwrap()["id"], json!(null));
+
+        // Notifications have no ID and must not be mistaken for responses
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        assert!(serde_json::from_value::<JsonRpcResponse>(notification).is_err());
+
+        let numbers = RequestIdGenerator::new();
+        assert_eq!(numbers.next_id(), RequestId::Number(1));
+        assert_eq!(numbers.next_id(), RequestId::Number(2));
+        let strings = RequestIdGenerator::with_prefix("client");
+        assert_eq!(strings.next_id(), RequestId::from("client-1"));
     }
 
     #[test]
     fn test_jsonrpc_response() {
-        let response = JsonRpcResponse::success(json!(1), json!({"result": "success"})).unwrap();
+        let response =
+            JsonRpcResponse::success(RequestId::from(1), json!({"result": "success"})).unwrap();
         assert_eq!(response.jsonrpc, "2.0");
-        assert_eq!(response.id, json!(1));
+        assert_eq!(response.id, Some(RequestId::Number(1)));
         assert!(response.result.is_some());
         assert!(response.error.is_none());
 
         let error_response = JsonRpcResponse::error(
-            json!(1),
+            RequestId::from(1),
             INVALID_PARAMS,
             "Invalid parameters".to_string(),
             None,
@@ -8,8 +8,29 @@ use crate::protocol::messages::*;
 use crate::protocol::types::*;
 use serde_json::Value;
 
-/// Validates that a JSON-RPC message conforms to the specification
+/// Validates that a JSON-RPC message or batch conforms to the specification
 pub fn validate_jsonrpc_message(message: &Value) -> McpResult<()> {
+    if let Some(batch) = message.as_array() {
+        if batch.is_empty() {
+            return Err(McpError::Validation("Batch cannot be empty".to_string()));
+        }
+        for (index, entry) in batch.iter().enumerate() {
+            if entry.is_array() {
+                return Err(McpError::Validation(format!(
+                    "Batch entry {} cannot itself be a batch",
+                    index
+                )));
+            }
+            validate_jsonrpc_message(entry).map_err(|e| match e {
+                McpError::Validation(message) => {
+                    McpError::Validation(format!("Batch entry {}: {}", index, message))
+                }
+                other => other,
+            })?;
+        }
+        return Ok(());
+    }
+
     let obj = message
         .as_object()
         .ok_or_else(|| McpError::Validation("Message must be a JSON object".to_string()))?;
@@ -155,6 +176,16 @@ pub fn validate_tool_info(tool: &ToolInfo) -> McpResult<()> {
         ));
     }
 
+    if tool
+        .output_schema
+        .as_ref()
+        .is_some_and(|schema| !schema.is_object())
+    {
+        return Err(McpError::Validation(
+            "Tool output_schema must be a JSON object".to_string(),
+        ));
+    }
+
     Ok(())
 }
 
@@ -329,32 +360,87 @@ pub fn validate_create_message_params(params: &CreateMessageParams) -> McpResult
 /// Validates content
 pub fn validate_content(content: &Content) -> McpResult<()> {
     match content {
-        Content::Text { text } => {
+        Content::Text { text, .. } => {
             if text.is_empty() {
                 return Err(McpError::Validation(
                     "Text content cannot be empty".to_string(),
                 ));
             }
         }
-        Content::Image { data, mime_type } => {
-            if data.is_empty() {
-                return Err(McpError::Validation(
-                    "Image data cannot be empty".to_string(),
-                ));
-            }
-            if mime_type.is_empty() {
+        Content::Image {
+            data, mime_type, ..
+        } => validate_media("Image", "image/", data, mime_type)?,
+        Content::Audio {
+            data, mime_type, ..
+        } => validate_media("Audio", "audio/", data, mime_type)?,
+        Content::Resource { resource, .. } => {
+            validate_uri(&resource.uri)?;
+            if resource.text.is_none() == resource.blob.is_none() {
                 return Err(McpError::Validation(
-                    "Image MIME type cannot be empty".to_string(),
+                    "Embedded resource must have exactly one of text or blob".to_string(),
                 ));
             }
-            if !mime_type.starts_with("image/") {
+        }
+        Content::ResourceLink { uri, name, .. } => {
+            validate_uri(uri)?;
+            if name.is_empty() {
                 return Err(McpError::Validation(
-                    "Image MIME type must start with 'image/'".to_string(),
+                    "Resource link name cannot be empty".to_string(),
                 ));
             }
         }
     }
 
+    if let Some(annotations) = content.annotations() {
+        validate_annotations(annotations)?;
+    }
+
+    Ok(())
+}
+
+/// Validates base64-encoded media content such as images and audio
+fn validate_media(kind: &str, mime_prefix: &str, data: &str, mime_type: &str) -> McpResult<()> {
+    if data.is_empty() {
+        return Err(McpError::Validation(format!(
+            "{} data cannot be empty",
+            kind
+        )));
+    }
+    if mime_type.is_empty() {
+        return Err(McpError::Validation(format!(
+            "{} MIME type cannot be empty",
+            kind
+        )));
+    }
+    if !mime_type.starts_with(mime_prefix) {
+        return Err(McpError::Validation(format!(
+            "{} MIME type must start with '{}'",
+            kind, mime_prefix
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validates content annotations
+pub fn validate_annotations(annotations: &Annotations) -> McpResult<()> {
+    if let Some(priority) = annotations.priority {
+        if !(0.0..=1.0).contains(&priority) {
+            return Err(McpError::Validation(
+                "Annotation priority must be between 0 and 1".to_string(),
+            ));
+        }
+    }
+    if annotations
+        .last_modified
+        .as_ref()
+        .is_some_and(|timestamp| timestamp.is_empty())
+    {
+        return Err(McpError::Validation(
+            "Annotation lastModified cannot be empty".to_string(),
+        ));
+    }
+
     Ok(())
 }
 
@@ -385,6 +471,8 @@ pub fn validate_method_name(method: &str) -> McpResult<()> {
     // Check for valid MCP method patterns
     match method {
         methods::INITIALIZE
+        | methods::INITIALIZED
+        | methods::CANCELLED
         | methods::PING
         | methods::TOOLS_LIST
         | methods::TOOLS_CALL
@@ -401,7 +489,9 @@ pub fn validate_method_name(method: &str) -> McpResult<()> {
         | methods::SAMPLING_CREATE_MESSAGE
         | methods::LOGGING_SET_LEVEL
         | methods::LOGGING_MESSAGE
-        | methods::PROGRESS => Ok(()),
+        | methods::PROGRESS
+        | methods::ROOTS_LIST
+        | methods::ROOTS_LIST_CHANGED => Ok(()),
         _ => {
             // Allow custom methods if they follow naming conventions
             if method.contains('/') || method.contains('.') {
@@ -535,7 +625,7 @@ mod tests {
     fn test_validate_jsonrpc_request() {
         let valid_request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
-            id: json!(1),
+            id: RequestId::from(1),
             method: "test_method".to_string(),
             params: None,
         };
@@ -543,13 +633,32 @@ mod tests {
 
         let invalid_request = JsonRpcRequest {
             jsonrpc: "1.0".to_string(),
-            id: json!(1),
+            id: RequestId::from(1),
             method: "test_method".to_string(),
             params: None,
         };
         assert!(validate_jsonrpc_request(&invalid_request).is_err());
     }
 
+    #[test]
+    fn test_validate_jsonrpc_batch() {
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "tools/list"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"}
+        ]);
+        assert!(validate_jsonrpc_message(&batch).is_ok());
+
+        assert!(validate_jsonrpc_message(&json!([])).is_err());
+        assert!(validate_jsonrpc_message(&json!([[]])).is_err());
+
+        let error = validate_jsonrpc_message(&json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+            {"jsonrpc": "1.0", "id": 2, "method": "ping"}
+        ]))
+        .unwrap_err();
+        assert!(error.to_string().contains("Batch entry 1"));
+    }
+
     #[test]
     fn test_validate_uri() {
         assert!(validate_uri("https://example.com").is_ok());
@@ -570,6 +679,9 @@ mod tests {
                     "param": {"type": "string"}
                 }
             }),
+            title: None,
+            output_schema: None,
+            annotations: None,
         };
         assert!(validate_tool_info(&valid_tool).is_ok());
 
@@ -577,8 +689,17 @@ mod tests {
             name: "".to_string(),
             description: None,
             input_schema: json!("not an object"),
+            title: None,
+            output_schema: None,
+            annotations: None,
         };
         assert!(validate_tool_info(&invalid_tool).is_err());
+
+        let invalid_output = ToolInfo {
+            output_schema: Some(json!(true)),
+            ..valid_tool
+        };
+        assert!(validate_tool_info(&invalid_output).is_err());
     }
 
     #[test]
@@ -593,6 +714,7 @@ mod tests {
             top_p: Some(0.9),
             stop_sequences: None,
             metadata: None,
+            meta: None,
         };
         assert!(validate_create_message_params(&valid_params).is_ok());
 
@@ -606,6 +728,7 @@ mod tests {
             top_p: None,
             stop_sequences: None,
             metadata: None,
+            meta: None,
         };
         assert!(validate_create_message_params(&invalid_params).is_err());
     }
@@ -618,16 +741,40 @@ mod tests {
         let valid_image = Content::image("base64data", "image/png");
         assert!(validate_content(&valid_image).is_ok());
 
-        let invalid_text = Content::Text {
-            text: "".to_string(),
-        };
+        let invalid_text = Content::text("");
         assert!(validate_content(&invalid_text).is_err());
 
-        let invalid_image = Content::Image {
-            data: "data".to_string(),
-            mime_type: "text/plain".to_string(), // Invalid MIME type for image
-        };
+        let invalid_image = Content::image("data", "text/plain"); // Invalid MIME type for image
         assert!(validate_content(&invalid_image).is_err());
+
+        let audio = Content::audio("data", "audio/wav");
+        assert!(validate_content(&audio).is_ok());
+        assert!(validate_content(&Content::audio("data", "image/png")).is_err());
+
+        let embedded = Content::resource(ResourceContent {
+            uri: "file:///report.csv".to_string(),
+            mime_type: Some("text/csv".to_string()),
+            text: Some("a,b".to_string()),
+            blob: None,
+        });
+        assert!(validate_content(&embedded).is_ok());
+        let empty_resource = Content::resource(ResourceContent {
+            uri: "file:///report.csv".to_string(),
+            mime_type: None,
+            text: None,
+            blob: None,
+        });
+        assert!(validate_content(&empty_resource).is_err());
+
+        let link = Content::resource_link("file:///report.csv", "report");
+        assert!(validate_content(&link).is_ok());
+        assert!(validate_content(&Content::resource_link("file:///report.csv", "")).is_err());
+
+        let prioritized = Content::text("hi").with_annotations(Annotations {
+            priority: Some(1.5),
+            ..Default::default()
+        });
+        assert!(validate_content(&prioritized).is_err());
     }
 
     #[test]
@@ -79,17 +79,14 @@ impl ToolHandler {
             .filter(|tool| tool.enabled)
             .map(|tool| {
                 // Convert from core::tool::ToolInfo to protocol::types::ToolInfo
-                ToolInfo {
-                    name: tool.info.name.clone(),
-                    description: tool.info.description.clone(),
-                    input_schema: tool.info.input_schema.clone(),
-                }
+                tool.info.clone()
             })
             .collect();
 
         Ok(ListToolsResult {
             tools,
             next_cursor: None,
+            meta: None,
         })
     }
 
@@ -126,11 +123,13 @@ impl ToolHandler {
         }
 
         let arguments = params.arguments.unwrap_or_default();
-        let result = tool.handler.call(arguments).await?;
+        let result = tool.call(arguments).await?;
 
         Ok(CallToolResult {
             content: result.content,
             is_error: result.is_error,
+            structured_content: result.structured_content,
+            meta: result.meta,
         })
     }
 }
@@ -168,6 +167,7 @@ impl ResourceHandler {
         Ok(ListResourcesResult {
             resources,
             next_cursor: None,
+            meta: None,
         })
     }
 
@@ -201,7 +201,10 @@ impl ResourceHandler {
         let query_params = HashMap::new();
         let contents = resource.handler.read(&params.uri, &query_params).await?;
 
-        Ok(ReadResourceResult { contents })
+        Ok(ReadResourceResult {
+            contents,
+            meta: None,
+        })
     }
 
     /// Handle resources/subscribe request
@@ -232,7 +235,7 @@ impl ResourceHandler {
 
         resource.handler.subscribe(&params.uri).await?;
 
-        Ok(SubscribeResourceResult {})
+        Ok(SubscribeResourceResult::default())
     }
 
     /// Handle resources/unsubscribe request
@@ -263,7 +266,7 @@ impl ResourceHandler {
 
         resource.handler.unsubscribe(&params.uri).await?;
 
-        Ok(UnsubscribeResourceResult {})
+        Ok(UnsubscribeResourceResult::default())
     }
 }
 
@@ -306,6 +309,7 @@ impl PromptHandler {
         Ok(ListPromptsResult {
             prompts,
             next_cursor: None,
+            meta: None,
         })
     }
 
@@ -339,30 +343,8 @@ impl PromptHandler {
 
         Ok(GetPromptResult {
             description: result.description,
-            messages: result
-                .messages
-                .into_iter()
-                .map(|msg| {
-                    // Convert from core::prompt::PromptMessage to protocol::types::PromptMessage
-                    PromptMessage {
-                        role: msg.role,
-                        content: match msg.content {
-                            crate::protocol::types::PromptContent::Text { content_type, text } => {
-                                PromptContent::Text { content_type, text }
-                            }
-                            crate::protocol::types::PromptContent::Image {
-                                content_type,
-                                data,
-                                mime_type,
-                            } => PromptContent::Image {
-                                content_type,
-                                data,
-                                mime_type,
-                            },
-                        },
-                    }
-                })
-                .collect(),
+            messages: result.messages.into_iter().collect(),
+            meta: result.meta,
         })
     }
 }
@@ -401,7 +383,7 @@ impl LoggingHandler {
         // Logging level management feature planned for future implementation
         // This would typically integrate with a logging framework like tracing
 
-        Ok(SetLoggingLevelResult {})
+        Ok(SetLoggingLevelResult::default())
     }
 }
 
@@ -411,7 +393,7 @@ pub struct PingHandler;
 impl PingHandler {
     /// Handle ping request
     pub async fn handle(_params: Option<Value>) -> McpResult<PingResult> {
-        Ok(PingResult {})
+        Ok(PingResult::default())
     }
 }
 
@@ -468,7 +450,7 @@ pub mod notifications {
     pub fn tools_list_changed() -> McpResult<JsonRpcNotification> {
         Ok(JsonRpcNotification::new(
             methods::TOOLS_LIST_CHANGED.to_string(),
-            Some(ToolListChangedParams {}),
+            Some(ToolListChangedParams::default()),
         )?)
     }
 
@@ -476,7 +458,7 @@ pub mod notifications {
     pub fn resources_list_changed() -> McpResult<JsonRpcNotification> {
         Ok(JsonRpcNotification::new(
             methods::RESOURCES_LIST_CHANGED.to_string(),
-            Some(ResourceListChangedParams {}),
+            Some(ResourceListChangedParams::default()),
         )?)
     }
 
@@ -484,7 +466,7 @@ pub mod notifications {
     pub fn prompts_list_changed() -> McpResult<JsonRpcNotification> {
         Ok(JsonRpcNotification::new(
             methods::PROMPTS_LIST_CHANGED.to_string(),
-            Some(PromptListChangedParams {}),
+            Some(PromptListChangedParams::default()),
         )?)
     }
 
@@ -492,7 +474,7 @@ pub mod notifications {
     pub fn resource_updated(uri: String) -> McpResult<JsonRpcNotification> {
         Ok(JsonRpcNotification::new(
             methods::RESOURCES_UPDATED.to_string(),
-            Some(ResourceUpdatedParams { uri }),
+            Some(ResourceUpdatedParams { uri, meta: None }),
         )?)
     }
 
@@ -508,6 +490,7 @@ pub mod notifications {
                 progress_token,
                 progress,
                 total,
+                meta: None,
             }),
         )?)
     }
@@ -524,6 +507,7 @@ pub mod notifications {
                 level,
                 logger,
                 data,
+                meta: None,
             }),
         )?)
     }
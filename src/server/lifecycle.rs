@@ -641,7 +641,7 @@ mod tests {
         // Check that events were captured
         let events = events.lock().await;
         assert!(
-            events.len() > 0,
+            !events.is_empty(),
             "Expected at least one event, but got: {:?}",
             *events
         );
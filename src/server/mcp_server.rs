@@ -4,20 +4,27 @@
 //! manages resources, tools, and prompts, and processes JSON-RPC requests according to
 //! the Model Context Protocol specification.
 
+use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
+use tokio::sync::{Mutex, Notify, RwLock};
+
+use std::future::Future;
+use std::pin::Pin;
 
 use crate::core::{
+    context::{Extensions, Peer, PeerSender, RequestContext, Session, SessionData},
     error::{McpError, McpResult},
-    prompt::{Prompt, PromptHandler},
-    resource::{Resource, ResourceHandler},
-    tool::{Tool, ToolHandler},
+    extract::{ExtractHandler, Handler},
+    prompt::{FnPromptHandler, Prompt, PromptHandler},
+    resource::{FnResourceHandler, IntoResourceContents, Resource, ResourceHandler},
+    tool::{FnToolHandler, Tool, ToolHandler},
     PromptInfo, ResourceInfo, ToolInfo,
 };
 use crate::protocol::{messages::*, types::*, validation::*};
-use crate::transport::traits::ServerTransport;
+use crate::transport::traits::{ServerSender, ServerTransport, SessionHandlers};
 
 /// Configuration for the MCP server
 #[derive(Debug, Clone)]
@@ -30,6 +37,8 @@ pub struct ServerConfig {
     pub validate_requests: bool,
     /// Whether to enable detailed logging
     pub enable_logging: bool,
+    /// Whether to normalize the arguments of every registered tool
+    pub normalize_tool_arguments: bool,
 }
 
 impl Default for ServerConfig {
@@ -39,10 +48,50 @@ impl Default for ServerConfig {
             request_timeout_ms: 30000,
             validate_requests: true,
             enable_logging: true,
+            normalize_tool_arguments: false,
         }
     }
 }
 
+/// Handler registered for a client notification method
+type NotificationHandlerFn = Arc<
+    dyn Fn(Option<Value>, RequestContext) -> Pin<Box<dyn Future<Output = McpResult<()>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// Hook run when a client session starts or ends
+type SessionHookFn = Arc<dyn Fn(Session) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Client requests currently being handled, with the signal that cancels each one
+type InFlightRequests = StdMutex<HashMap<RequestId, Arc<Notify>>>;
+
+/// State the server keeps for one client connection
+#[derive(Default)]
+struct SessionState {
+    /// ID the transport assigned to the connection, if it serves several
+    id: Option<String>,
+    /// Session negotiated during initialization
+    session: RwLock<Option<Session>>,
+    /// Whether the client has sent `notifications/initialized`
+    initialized: AtomicBool,
+    /// Roots of the client, cached until it reports that they changed
+    roots: RwLock<Option<Vec<Root>>>,
+    /// Bumped whenever the client's roots change, to discard roots fetched before
+    roots_generation: AtomicU64,
+    /// Client requests currently being handled
+    in_flight: InFlightRequests,
+}
+
+/// Hooks run over the lifecycle of client sessions
+#[derive(Default)]
+struct SessionHooks {
+    /// Run when a client initializes a session
+    started: Vec<SessionHookFn>,
+    /// Run when the connection of an initialized session closes
+    ended: Vec<SessionHookFn>,
+}
+
 /// Main MCP server implementation
 pub struct McpServer {
     /// Server information
@@ -59,11 +108,22 @@ pub struct McpServer {
     prompts: Arc<RwLock<HashMap<String, Prompt>>>,
     /// Active transport
     transport: Arc<Mutex<Option<Box<dyn ServerTransport>>>>,
+    /// Handle for sending to the clients of the transport while it is serving
+    sender: Arc<RwLock<Option<Arc<dyn ServerSender>>>>,
     /// Server state
     state: Arc<RwLock<ServerState>>,
-    /// Request ID counter
-    #[allow(dead_code)]
-    request_counter: Arc<Mutex<u64>>,
+    /// Generator for the IDs of requests sent to the client
+    request_ids: Arc<RequestIdGenerator>,
+    /// Handlers for client notifications, keyed by method
+    notification_handlers: Arc<RwLock<HashMap<String, Vec<NotificationHandlerFn>>>>,
+    /// Application state shared with handlers
+    extensions: Extensions,
+    /// State of the client of a transport that serves a single connection
+    default_session: Arc<SessionState>,
+    /// State of every open session of transports that serve several connections
+    sessions: Arc<StdRwLock<HashMap<String, Arc<SessionState>>>>,
+    /// Hooks run when sessions start and end
+    session_hooks: Arc<RwLock<SessionHooks>>,
 }
 
 /// Internal server state
@@ -104,8 +164,14 @@ impl McpServer {
             tools: Arc::new(RwLock::new(HashMap::new())),
             prompts: Arc::new(RwLock::new(HashMap::new())),
             transport: Arc::new(Mutex::new(None)),
+            sender: Arc::new(RwLock::new(None)),
             state: Arc::new(RwLock::new(ServerState::Uninitialized)),
-            request_counter: Arc::new(Mutex::new(0)),
+            request_ids: Arc::new(RequestIdGenerator::new()),
+            notification_handlers: Arc::new(RwLock::new(HashMap::new())),
+            extensions: Extensions::new(),
+            default_session: Arc::new(SessionState::default()),
+            sessions: Arc::new(StdRwLock::new(HashMap::new())),
+            session_hooks: Arc::new(RwLock::new(SessionHooks::default())),
         }
     }
 
@@ -136,6 +202,210 @@ impl McpServer {
         &self.config
     }
 
+    /// Register application state available to handlers through `State<T>`
+    ///
+    /// Registering a value of a type that is already present replaces it.
+    pub fn add_state<T: Send + Sync + 'static>(&mut self, value: T) {
+        self.extensions.insert(value);
+    }
+
+    /// Get the session of the initialized client, if any
+    ///
+    /// This is the client of a transport serving a single connection, such as STDIO;
+    /// the clients of other transports are listed by [`McpServer::sessions`].
+    pub async fn session(&self) -> Option<Session> {
+        self.default_session.session.read().await.clone()
+    }
+
+    /// Get the initialized sessions of the connections served by the transport
+    pub async fn sessions(&self) -> Vec<Session> {
+        let states: Vec<_> = self
+            .sessions
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        let mut sessions = Vec::with_capacity(states.len());
+        for state in states {
+            if let Some(session) = state.session.read().await.clone() {
+                sessions.push(session);
+            }
+        }
+        sessions
+    }
+
+    /// Check whether the client has confirmed initialization with `notifications/initialized`
+    pub fn is_client_initialized(&self) -> bool {
+        self.default_session.initialized.load(Ordering::SeqCst)
+    }
+
+    /// Register a hook run whenever a client initializes a session
+    pub async fn on_session_start<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Session) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: SessionHookFn = Arc::new(move |session| Box::pin(hook(session)));
+        self.session_hooks.write().await.started.push(hook);
+    }
+
+    /// Register a hook run whenever the connection of an initialized session closes
+    pub async fn on_session_end<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Session) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: SessionHookFn = Arc::new(move |session| Box::pin(hook(session)));
+        self.session_hooks.write().await.ended.push(hook);
+    }
+
+    /// Open the session of a connection whose client is initializing
+    ///
+    /// Requests and notifications are only handled for open sessions, so a message
+    /// that arrives after its connection closed cannot bring the session back.
+    /// Opening a session that is already open keeps its state.
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the connection
+    pub fn open_session(&self, session_id: &str) {
+        self.sessions
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(session_id.to_string())
+            .or_insert_with(|| {
+                tracing::debug!("Opening session {}", session_id);
+                Arc::new(SessionState {
+                    id: Some(session_id.to_string()),
+                    ..SessionState::default()
+                })
+            });
+    }
+
+    /// Tear down the session of a connection that closed
+    ///
+    /// Requests still being handled for the connection are cancelled, and the
+    /// session end hooks run if the client had initialized the session.
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the connection
+    pub async fn close_session(&self, session_id: &str) {
+        let removed = self
+            .sessions
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id);
+        let Some(state) = removed else {
+            tracing::debug!("Session {} to close is not open", session_id);
+            return;
+        };
+        tracing::debug!("Closing session {}", session_id);
+
+        for cancel in state
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+        {
+            cancel.notify_one();
+        }
+
+        let session = state.session.write().await.take();
+        if let Some(session) = session {
+            let hooks = self.session_hooks.read().await.ended.clone();
+            for hook in hooks {
+                hook(session.clone()).await;
+            }
+        }
+    }
+
+    /// Get the state of the given connection, or `None` if its session is not open
+    fn session_state(&self, session_id: Option<&str>) -> Option<Arc<SessionState>> {
+        match session_id {
+            Some(session_id) => self
+                .sessions
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(session_id)
+                .cloned(),
+            None => Some(self.default_session.clone()),
+        }
+    }
+
+    /// Get the roots of the client that sent a request or notification
+    ///
+    /// The roots are requested with `roots/list` and cached per session until the
+    /// client sends `notifications/roots/list_changed`.
+    ///
+    /// # Arguments
+    /// * `context` - Context passed to the handler, whose session selects the client;
+    ///   without a session, the client of a transport serving a single connection
+    ///
+    /// # Returns
+    /// Result containing the client's roots or an error if its session has closed
+    pub async fn list_roots(&self, context: &RequestContext) -> McpResult<Vec<Root>> {
+        let session_id = context
+            .session
+            .as_ref()
+            .and_then(|session| session.id.as_deref());
+        let state = self.session_state(session_id).ok_or_else(|| {
+            McpError::connection(format!(
+                "Session {} is not open",
+                session_id.unwrap_or_default()
+            ))
+        })?;
+        if let Some(roots) = state.roots.read().await.clone() {
+            return Ok(roots);
+        }
+
+        let generation = state.roots_generation.load(Ordering::SeqCst);
+        let result = self
+            .peer(state.id.clone())
+            .send_request(
+                methods::ROOTS_LIST,
+                Some(serde_json::to_value(ListRootsParams::default())?),
+            )
+            .await?;
+        let ListRootsResult { roots, .. } = serde_json::from_value(result)?;
+
+        let mut cached = state.roots.write().await;
+        if state.roots_generation.load(Ordering::SeqCst) == generation {
+            *cached = Some(roots.clone());
+        }
+        Ok(roots)
+    }
+
+    /// Build the context passed to handlers serving the given request
+    pub async fn request_context(&self, request_id: Option<RequestId>) -> RequestContext {
+        self.session_context(&self.default_session, request_id)
+            .await
+    }
+
+    /// Build the peer sending to the client of the given session
+    fn peer(&self, session_id: Option<String>) -> TransportPeer {
+        TransportPeer {
+            transport: self.transport.clone(),
+            sender: self.sender.clone(),
+            request_ids: self.request_ids.clone(),
+            session_id,
+        }
+    }
+
+    /// Build the context passed to handlers serving a request of the given session
+    async fn session_context(
+        &self,
+        state: &SessionState,
+        request_id: Option<RequestId>,
+    ) -> RequestContext {
+        RequestContext {
+            request_id,
+            session: state.session.read().await.clone(),
+            state: self.extensions.clone(),
+            peer: Some(Peer::new(Arc::new(self.peer(state.id.clone())))),
+            ..RequestContext::default()
+        }
+    }
+
     // ========================================================================
     // Resource Management
     // ========================================================================
@@ -146,25 +416,14 @@ impl McpServer {
         H: ResourceHandler + 'static,
     {
         let resource_info = ResourceInfo {
-            uri: uri.clone(),
-            name: name.clone(),
+            uri,
+            name,
             description: None,
             mime_type: None,
         };
 
-        validate_resource_info(&resource_info)?;
-
-        let resource = Resource::new(resource_info, handler);
-
-        {
-            let mut resources = self.resources.write().await;
-            resources.insert(uri, resource);
-        }
-
-        // Emit list changed notification if we have an active transport
-        self.emit_resources_list_changed().await?;
-
-        Ok(())
+        self.register_resource(Resource::new(resource_info, handler))
+            .await
     }
 
     /// Add a resource with detailed information
@@ -172,16 +431,37 @@ impl McpServer {
     where
         H: ResourceHandler + 'static,
     {
-        validate_resource_info(&info)?;
+        self.register_resource(Resource::new(info, handler)).await
+    }
+
+    /// Add a resource whose content is produced by an async closure
+    ///
+    /// The closure receives the requested URI and the request context.
+    pub async fn add_resource_fn<F, Fut, O>(
+        &self,
+        name: String,
+        uri: String,
+        handler: F,
+    ) -> McpResult<()>
+    where
+        F: Fn(String, RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<O>> + Send + 'static,
+        O: IntoResourceContents + 'static,
+    {
+        self.add_resource(name, uri, FnResourceHandler::new(handler))
+            .await
+    }
 
-        let uri = info.uri.clone();
-        let resource = Resource::new(info, handler);
+    /// Register a resource that has already been constructed
+    pub async fn register_resource(&self, resource: Resource) -> McpResult<()> {
+        validate_resource_info(&resource.info)?;
 
         {
             let mut resources = self.resources.write().await;
-            resources.insert(uri, resource);
+            resources.insert(resource.info.uri.clone(), resource);
         }
 
+        // Emit list changed notification if we have an active transport
         self.emit_resources_list_changed().await?;
 
         Ok(())
@@ -209,12 +489,25 @@ impl McpServer {
 
     /// Read a resource
     pub async fn read_resource(&self, uri: &str) -> McpResult<Vec<ResourceContent>> {
+        self.read_resource_with_context(uri, self.request_context(None).await)
+            .await
+    }
+
+    /// Read a resource on behalf of the request described by `context`
+    pub async fn read_resource_with_context(
+        &self,
+        uri: &str,
+        context: RequestContext,
+    ) -> McpResult<Vec<ResourceContent>> {
         let resources = self.resources.read().await;
 
         match resources.get(uri) {
             Some(resource) => {
                 let params = HashMap::new(); // URL parameter extraction will be implemented in future versions
-                resource.handler.read(uri, &params).await
+                resource
+                    .handler
+                    .read_with_context(uri, &params, context)
+                    .await
             }
             None => Err(McpError::ResourceNotFound(uri.to_string())),
         }
@@ -236,48 +529,90 @@ impl McpServer {
         H: ToolHandler + 'static,
     {
         let tool_info = ToolInfo {
-            name: name.clone(),
+            name,
+            title: None,
             description,
             input_schema: schema,
+            output_schema: None,
+            annotations: None,
         };
 
-        validate_tool_info(&tool_info)?;
-
-        let tool = Tool::new(
-            name.clone(),
-            tool_info.description.clone(),
-            tool_info.input_schema.clone(),
-            handler,
-        );
-
-        {
-            let mut tools = self.tools.write().await;
-            tools.insert(name, tool);
-        }
-
-        self.emit_tools_list_changed().await?;
-
-        Ok(())
+        self.add_tool_detailed(tool_info, handler).await
     }
 
     /// Add a tool with detailed information
+    ///
+    /// The title, output schema and annotations are kept, and results of a tool with
+    /// an output schema are checked against it.
     pub async fn add_tool_detailed<H>(&self, info: ToolInfo, handler: H) -> McpResult<()>
     where
         H: ToolHandler + 'static,
     {
-        validate_tool_info(&info)?;
-
-        let name = info.name.clone();
-        let tool = Tool::new(
-            name.clone(),
-            info.description.clone(),
-            info.input_schema.clone(),
-            handler,
-        );
+        let ToolInfo {
+            name,
+            title,
+            description,
+            input_schema,
+            output_schema,
+            annotations,
+        } = info;
+        let mut tool = Tool::new(name, description, input_schema, handler);
+        tool.info.title = title;
+        tool.info.output_schema = output_schema;
+        tool.info.annotations = annotations;
+        self.register_tool(tool).await
+    }
+
+    /// Add a tool implemented by an async closure
+    ///
+    /// The closure receives the tool arguments and the request context.
+    pub async fn add_tool_fn<F, Fut>(
+        &self,
+        name: String,
+        description: Option<String>,
+        schema: Value,
+        handler: F,
+    ) -> McpResult<()>
+    where
+        F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<ToolResult>> + Send + 'static,
+    {
+        self.add_tool(name, description, schema, FnToolHandler::new(handler))
+            .await
+    }
+
+    /// Add a tool whose handler takes extractor parameters
+    ///
+    /// The handler is an async function whose parameters are extractors such as
+    /// `State<T>`, `Args<T>`, `Session`, `Meta` or `Peer`.
+    pub async fn add_tool_handler<H, T>(
+        &self,
+        name: String,
+        description: Option<String>,
+        schema: Value,
+        handler: H,
+    ) -> McpResult<()>
+    where
+        H: Handler<T, ToolResult>,
+        T: 'static,
+    {
+        self.add_tool(name, description, schema, ExtractHandler::new(handler))
+            .await
+    }
+
+    /// Register a tool that has already been constructed
+    ///
+    /// The tool's input schema is compiled before it is registered.
+    pub async fn register_tool(&self, mut tool: Tool) -> McpResult<()> {
+        validate_tool_info(&tool.info)?;
+        tool.compile_schema()?;
+        if self.config.normalize_tool_arguments {
+            tool.set_normalize_arguments(true);
+        }
 
         {
             let mut tools = self.tools.write().await;
-            tools.insert(name, tool);
+            tools.insert(tool.info.name.clone(), tool);
         }
 
         self.emit_tools_list_changed().await?;
@@ -285,6 +620,28 @@ impl McpServer {
         Ok(())
     }
 
+    /// Add a tool implemented as an async function taking typed arguments
+    ///
+    /// The input schema is derived from `Args`, and arguments are deserialized into it
+    /// before the function is called.
+    #[cfg(feature = "typed")]
+    pub async fn add_typed_tool<Args, Out, F, Fut>(
+        &self,
+        name: String,
+        description: Option<String>,
+        handler: F,
+    ) -> McpResult<()>
+    where
+        Args: serde::de::DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        Out: serde::Serialize + Send + 'static,
+        F: Fn(Args) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = McpResult<Out>> + Send + 'static,
+    {
+        let handler = crate::core::typed_tool::TypedToolHandler::new(handler);
+        let schema = handler.input_schema();
+        self.add_tool(name, description, schema, handler).await
+    }
+
     /// Remove a tool from the server
     pub async fn remove_tool(&self, name: &str) -> McpResult<bool> {
         let removed = {
@@ -310,6 +667,17 @@ impl McpServer {
         &self,
         name: &str,
         arguments: Option<HashMap<String, Value>>,
+    ) -> McpResult<ToolResult> {
+        self.call_tool_with_context(name, arguments, self.request_context(None).await)
+            .await
+    }
+
+    /// Call a tool on behalf of the request described by `context`
+    pub async fn call_tool_with_context(
+        &self,
+        name: &str,
+        arguments: Option<HashMap<String, Value>>,
+        context: RequestContext,
     ) -> McpResult<ToolResult> {
         let tools = self.tools.read().await;
 
@@ -322,8 +690,10 @@ impl McpServer {
                     )));
                 }
 
-                let args = arguments.unwrap_or_default();
-                tool.handler.call(args).await
+                let args = tool.prepare_arguments(arguments.unwrap_or_default())?;
+                let result = tool.handler.call_with_context(args, context).await?;
+                tool.validate_output(&result)?;
+                Ok(result)
             }
             None => Err(McpError::ToolNotFound(name.to_string())),
         }
@@ -338,14 +708,27 @@ impl McpServer {
     where
         H: PromptHandler + 'static,
     {
-        validate_prompt_info(&info)?;
+        self.register_prompt(Prompt::new(info, handler)).await
+    }
+
+    /// Add a prompt implemented by an async closure
+    ///
+    /// The closure receives the prompt arguments and the request context.
+    pub async fn add_prompt_fn<F, Fut>(&self, info: PromptInfo, handler: F) -> McpResult<()>
+    where
+        F: Fn(HashMap<String, Value>, RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<PromptResult>> + Send + 'static,
+    {
+        self.add_prompt(info, FnPromptHandler::new(handler)).await
+    }
 
-        let name = info.name.clone();
-        let prompt = Prompt::new(info, handler);
+    /// Register a prompt that has already been constructed
+    pub async fn register_prompt(&self, prompt: Prompt) -> McpResult<()> {
+        validate_prompt_info(&prompt.info)?;
 
         {
             let mut prompts = self.prompts.write().await;
-            prompts.insert(name, prompt);
+            prompts.insert(prompt.info.name.clone(), prompt);
         }
 
         self.emit_prompts_list_changed().await?;
@@ -373,18 +756,38 @@ impl McpServer {
         Ok(prompts.values().map(|p| p.info.clone()).collect())
     }
 
+    /// Add a prompt whose handler takes extractor parameters
+    pub async fn add_prompt_handler<H, T>(&self, info: PromptInfo, handler: H) -> McpResult<()>
+    where
+        H: Handler<T, PromptResult>,
+        T: 'static,
+    {
+        self.add_prompt(info, ExtractHandler::new(handler)).await
+    }
+
     /// Get a prompt
     pub async fn get_prompt(
         &self,
         name: &str,
         arguments: Option<HashMap<String, Value>>,
+    ) -> McpResult<PromptResult> {
+        self.get_prompt_with_context(name, arguments, self.request_context(None).await)
+            .await
+    }
+
+    /// Get a prompt on behalf of the request described by `context`
+    pub async fn get_prompt_with_context(
+        &self,
+        name: &str,
+        arguments: Option<HashMap<String, Value>>,
+        context: RequestContext,
     ) -> McpResult<PromptResult> {
         let prompts = self.prompts.read().await;
 
         match prompts.get(name) {
             Some(prompt) => {
                 let args = arguments.unwrap_or_default();
-                prompt.handler.get(args).await
+                prompt.handler.get_with_context(args, context).await
             }
             None => Err(McpError::PromptNotFound(name.to_string())),
         }
@@ -395,6 +798,10 @@ impl McpServer {
     // ========================================================================
 
     /// Start the server with the given transport
+    ///
+    /// The server answers the requests and notifications the transport receives.
+    /// Transports serving a single connection, such as STDIO, serve it before this
+    /// returns. Application state added afterwards is not seen by those requests.
     pub async fn start<T>(&mut self, transport: T) -> McpResult<()>
     where
         T: ServerTransport + 'static,
@@ -411,18 +818,15 @@ impl McpServer {
         drop(state);
 
         // Set up the transport
-        {
-            let mut transport_guard = self.transport.lock().await;
-            *transport_guard = Some(Box::new(transport));
-        }
+        let mut transport: Box<dyn ServerTransport> = Box::new(transport);
+        transport.set_handlers(self.session_handlers()).await;
+        *self.sender.write().await = transport.sender();
 
-        // Start the transport
-        {
-            let mut transport_guard = self.transport.lock().await;
-            if let Some(transport) = transport_guard.as_mut() {
-                transport.start().await?;
-            }
-        }
+        // Start the transport without holding its lock, so that handlers can send
+        // through its sender while it serves
+        let result = transport.start().await;
+        *self.transport.lock().await = Some(transport);
+        result?;
 
         // Update state to running
         {
@@ -448,6 +852,7 @@ impl McpServer {
         drop(state);
 
         // Stop the transport
+        *self.sender.write().await = None;
         {
             let mut transport_guard = self.transport.lock().await;
             if let Some(transport) = transport_guard.as_mut() {
@@ -464,6 +869,79 @@ impl McpServer {
         Ok(())
     }
 
+    /// Build the callbacks through which the transport passes messages to the server
+    fn session_handlers(&self) -> SessionHandlers {
+        let server = Arc::new(self.share());
+
+        let request_server = server.clone();
+        let notification_server = server.clone();
+        SessionHandlers {
+            request: Arc::new(move |session_id, request| {
+                // Opened before the request is spawned, so it cannot race the session closing
+                if let Some(session_id) = &session_id {
+                    if request.method == methods::INITIALIZE {
+                        request_server.open_session(session_id);
+                    }
+                }
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let server = request_server.clone();
+                tokio::spawn(async move {
+                    let id = request.id.clone();
+                    let response = server
+                        .handle_session_request(session_id.as_deref(), request)
+                        .await
+                        .unwrap_or_else(|error| {
+                            let JsonRpcError {
+                                code,
+                                message,
+                                data,
+                            } = error.to_rpc_error();
+                            JsonRpcResponse::error(id, code, message, data)
+                        });
+                    let _ = sender.send(response);
+                });
+                receiver
+            }),
+            notification: Arc::new(move |session_id, notification| {
+                let server = notification_server.clone();
+                tokio::spawn(async move {
+                    let method = notification.method.clone();
+                    if let Err(e) = server
+                        .handle_session_notification(session_id.as_deref(), notification)
+                        .await
+                    {
+                        tracing::warn!("Failed to handle notification {}: {}", method, e);
+                    }
+                });
+            }),
+            closed: Arc::new(move |session_id| {
+                let server = server.clone();
+                tokio::spawn(async move { server.close_session(&session_id).await });
+            }),
+        }
+    }
+
+    /// Create a handle on this server that shares its registries, sessions and transport
+    fn share(&self) -> Self {
+        Self {
+            info: self.info.clone(),
+            capabilities: self.capabilities.clone(),
+            config: self.config.clone(),
+            resources: self.resources.clone(),
+            tools: self.tools.clone(),
+            prompts: self.prompts.clone(),
+            transport: self.transport.clone(),
+            sender: self.sender.clone(),
+            state: self.state.clone(),
+            request_ids: self.request_ids.clone(),
+            notification_handlers: self.notification_handlers.clone(),
+            extensions: self.extensions.clone(),
+            default_session: self.default_session.clone(),
+            sessions: self.sessions.clone(),
+            session_hooks: self.session_hooks.clone(),
+        }
+    }
+
     /// Check if the server is running
     pub async fn is_running(&self) -> bool {
         let state = self.state.read().await;
@@ -476,51 +954,242 @@ impl McpServer {
         state.clone()
     }
 
+    // ========================================================================
+    // Notification Handling
+    // ========================================================================
+
+    /// Register a handler for a client notification
+    ///
+    /// Several handlers may be registered for the same method. They run in the order
+    /// they were added, after the server's built-in handling of the notification.
+    ///
+    /// # Arguments
+    /// * `method` - Method name of the notification, such as [`methods::ROOTS_LIST_CHANGED`]
+    /// * `handler` - Async closure receiving the notification parameters and a context
+    pub async fn on_notification<F, Fut>(&self, method: &str, handler: F)
+    where
+        F: Fn(Option<Value>, RequestContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<()>> + Send + 'static,
+    {
+        let handler: NotificationHandlerFn =
+            Arc::new(move |params, context| Box::pin(handler(params, context)));
+        self.notification_handlers
+            .write()
+            .await
+            .entry(method.to_string())
+            .or_default()
+            .push(handler);
+    }
+
+    /// Handle an incoming JSON-RPC notification
+    ///
+    /// `notifications/initialized`, `notifications/cancelled` and
+    /// `notifications/roots/list_changed` are handled by the server itself before the
+    /// registered handlers run.
+    ///
+    /// # Returns
+    /// Result indicating success, or the first error returned by a handler
+    pub async fn handle_notification(&self, notification: JsonRpcNotification) -> McpResult<()> {
+        self.handle_session_notification(None, notification).await
+    }
+
+    /// Handle a JSON-RPC notification received on a connection of the transport
+    ///
+    /// Notifications for a session that is not open are dropped.
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the connection, or `None` for
+    ///   the client of a transport serving a single connection
+    /// * `notification` - The notification to handle
+    ///
+    /// # Returns
+    /// Result indicating success, or the first error returned by a handler
+    pub async fn handle_session_notification(
+        &self,
+        session_id: Option<&str>,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        let Some(state) = self.session_state(session_id) else {
+            tracing::debug!(
+                "Dropping notification {} for session {:?}, which is not open",
+                notification.method,
+                session_id
+            );
+            return Ok(());
+        };
+        if self.config.validate_requests {
+            validate_jsonrpc_notification(&notification)?;
+        }
+
+        match ClientNotification::from_parts(&notification.method, notification.params.clone()) {
+            Ok(ClientNotification::Initialized(_)) => {
+                state.initialized.store(true, Ordering::SeqCst);
+            }
+            Ok(ClientNotification::Cancelled(params)) => Self::cancel_request(&state, params),
+            Ok(ClientNotification::RootsListChanged(_)) => {
+                state.roots_generation.fetch_add(1, Ordering::SeqCst);
+                *state.roots.write().await = None;
+            }
+            Ok(ClientNotification::Progress(_)) => {}
+            // Other notifications are left to the registered handlers
+            Err(McpError::MethodNotFound(_)) => {}
+            Err(error) => return Err(error),
+        }
+
+        let handlers = self
+            .notification_handlers
+            .read()
+            .await
+            .get(&notification.method)
+            .cloned()
+            .unwrap_or_default();
+        if handlers.is_empty() {
+            tracing::debug!(
+                "No handler registered for notification {}",
+                notification.method
+            );
+            return Ok(());
+        }
+
+        let context = self.session_context(&state, None).await;
+        let mut result = Ok(());
+        for handler in handlers {
+            if let Err(error) = handler(notification.params.clone(), context.clone()).await {
+                tracing::warn!(
+                    "Notification handler for {} failed: {}",
+                    notification.method,
+                    error
+                );
+                if result.is_ok() {
+                    result = Err(error);
+                }
+            }
+        }
+        result
+    }
+
+    /// Cancel a client request that is still being handled
+    fn cancel_request(state: &SessionState, params: CancelledParams) {
+        let in_flight = state.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        match in_flight.get(&params.request_id) {
+            Some(cancel) => {
+                tracing::debug!(
+                    "Cancelling request {}: {}",
+                    params.request_id,
+                    params.reason.as_deref().unwrap_or("no reason given")
+                );
+                cancel.notify_one();
+            }
+            None => tracing::debug!("Request {} to cancel is not in flight", params.request_id),
+        }
+    }
+
     // ========================================================================
     // Request Handling
     // ========================================================================
 
     /// Handle an incoming JSON-RPC request
     pub async fn handle_request(&self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
-        // Validate the request if configured to do so
-        if self.config.validate_requests {
-            validate_jsonrpc_request(&request)?;
-            validate_mcp_request(&request.method, request.params.as_ref())?;
+        self.handle_session_request(None, request).await
+    }
+
+    /// Handle a JSON-RPC request received on a connection of the transport
+    ///
+    /// Each connection has its own session, so clients are initialized, cancel
+    /// requests and pick request IDs independently of each other. The session must
+    /// have been opened with [`McpServer::open_session`].
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the connection, or `None` for
+    ///   the client of a transport serving a single connection
+    /// * `request` - The request to handle
+    ///
+    /// # Returns
+    /// Result containing the JSON-RPC response or an error
+    pub async fn handle_session_request(
+        &self,
+        session_id: Option<&str>,
+        request: JsonRpcRequest,
+    ) -> McpResult<JsonRpcResponse> {
+        let state = self.session_state(session_id).ok_or_else(|| {
+            McpError::connection(format!(
+                "Session {} is not open",
+                session_id.unwrap_or_default()
+            ))
+        })?;
+
+        // Responses are matched to requests by ID, so two requests must not share one
+        let Some(in_flight) = InFlight::track(&state.in_flight, &request.id) else {
+            let JsonRpcError {
+                code,
+                message,
+                data,
+            } = McpError::protocol(format!("Request ID {} is already in flight", request.id))
+                .to_rpc_error();
+            return Ok(JsonRpcResponse::error(request.id, code, message, data));
+        };
+
+        let mut context = self.session_context(&state, Some(request.id.clone())).await;
+        if let Some(meta) = request
+            .params
+            .as_ref()
+            .and_then(|params| params.get("_meta"))
+            .and_then(Value::as_object)
+        {
+            context.meta = meta.clone();
         }
 
-        // Route the request to the appropriate handler
-        let result = match request.method.as_str() {
-            methods::INITIALIZE => self.handle_initialize(request.params).await,
-            methods::PING => self.handle_ping().await,
-            methods::TOOLS_LIST => self.handle_tools_list(request.params).await,
-            methods::TOOLS_CALL => self.handle_tools_call(request.params).await,
-            methods::RESOURCES_LIST => self.handle_resources_list(request.params).await,
-            methods::RESOURCES_READ => self.handle_resources_read(request.params).await,
-            methods::RESOURCES_SUBSCRIBE => self.handle_resources_subscribe(request.params).await,
-            methods::RESOURCES_UNSUBSCRIBE => {
-                self.handle_resources_unsubscribe(request.params).await
-            }
-            methods::PROMPTS_LIST => self.handle_prompts_list(request.params).await,
-            methods::PROMPTS_GET => self.handle_prompts_get(request.params).await,
-            methods::LOGGING_SET_LEVEL => self.handle_logging_set_level(request.params).await,
-            _ => Err(McpError::Protocol(format!(
-                "Unknown method: {}",
-                request.method
-            ))),
+        let id = request.id.clone();
+        let result = tokio::select! {
+            result = self.dispatch(&state, request, context) => result,
+            _ = in_flight.cancelled() => {
+                Err(McpError::Cancelled("request cancelled by the client".to_string()))
+            }
         };
 
         // Convert the result to a JSON-RPC response
         match result {
-            Ok(result_value) => Ok(JsonRpcResponse::success(request.id, result_value)?),
+            Ok(result_value) => Ok(JsonRpcResponse::success(id, result_value)?),
             Err(error) => {
-                let (code, message) = match error {
-                    McpError::ToolNotFound(_) => (TOOL_NOT_FOUND, error.to_string()),
-                    McpError::ResourceNotFound(_) => (RESOURCE_NOT_FOUND, error.to_string()),
-                    McpError::PromptNotFound(_) => (PROMPT_NOT_FOUND, error.to_string()),
-                    McpError::Validation(_) => (INVALID_PARAMS, error.to_string()),
-                    _ => (INTERNAL_ERROR, error.to_string()),
-                };
-                Ok(JsonRpcResponse::error(request.id, code, message, None))
+                let JsonRpcError {
+                    code,
+                    message,
+                    data,
+                } = error.to_rpc_error();
+                Ok(JsonRpcResponse::error(id, code, message, data))
+            }
+        }
+    }
+
+    /// Validate a request and route it to the handler for its method
+    async fn dispatch(
+        &self,
+        state: &SessionState,
+        request: JsonRpcRequest,
+        context: RequestContext,
+    ) -> McpResult<Value> {
+        // Validate the request if configured to do so
+        if self.config.validate_requests {
+            validate_jsonrpc_request(&request)?;
+            validate_mcp_request(&request.method, request.params.as_ref())?;
+        }
+
+        // Route the request to the appropriate handler
+        match ClientRequest::try_from(request)? {
+            ClientRequest::Initialize(params) => self.handle_initialize(state, params).await,
+            ClientRequest::Ping(_) => self.handle_ping().await,
+            ClientRequest::ListTools(params) => self.handle_tools_list(params).await,
+            ClientRequest::CallTool(params) => self.handle_tools_call(params, context).await,
+            ClientRequest::ListResources(params) => self.handle_resources_list(params).await,
+            ClientRequest::ReadResource(params) => {
+                self.handle_resources_read(params, context).await
+            }
+            ClientRequest::Subscribe(params) => self.handle_resources_subscribe(params).await,
+            ClientRequest::Unsubscribe(params) => self.handle_resources_unsubscribe(params).await,
+            ClientRequest::ListPrompts(params) => self.handle_prompts_list(params).await,
+            ClientRequest::GetPrompt(params) => self.handle_prompts_get(params, context).await,
+            ClientRequest::SetLoggingLevel(params) => {
+                self.handle_logging_set_level(state, params).await
             }
         }
     }
@@ -529,17 +1198,31 @@ impl McpServer {
     // Individual Request Handlers
     // ========================================================================
 
-    async fn handle_initialize(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: InitializeParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing initialize parameters".to_string(),
-                ))
-            }
+    async fn handle_initialize(
+        &self,
+        state: &SessionState,
+        params: InitializeParams,
+    ) -> McpResult<Value> {
+        validate_initialize_params(&params)?;
+
+        // A new session starts without the previous client's state
+        state.initialized.store(false, Ordering::SeqCst);
+        state.roots_generation.fetch_add(1, Ordering::SeqCst);
+        *state.roots.write().await = None;
+        let session = Session {
+            id: state.id.clone(),
+            client_info: params.client_info,
+            capabilities: params.capabilities,
+            protocol_version: params.protocol_version,
+            log_level: None,
+            data: SessionData::new(),
         };
+        *state.session.write().await = Some(session.clone());
 
-        validate_initialize_params(&params)?;
+        let hooks = self.session_hooks.read().await.started.clone();
+        for hook in hooks {
+            hook(session.clone()).await;
+        }
 
         let result = InitializeResult::new(
             self.info.clone(),
@@ -551,150 +1234,118 @@ impl McpServer {
     }
 
     async fn handle_ping(&self) -> McpResult<Value> {
-        Ok(serde_json::to_value(PingResult {})?)
+        Ok(serde_json::to_value(PingResult::default())?)
     }
 
-    async fn handle_tools_list(&self, params: Option<Value>) -> McpResult<Value> {
-        let _params: ListToolsParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => ListToolsParams::default(),
-        };
-
+    async fn handle_tools_list(&self, _params: ListToolsParams) -> McpResult<Value> {
         let tools = self.list_tools().await?;
         let result = ListToolsResult {
             tools,
             next_cursor: None, // Pagination support will be added in future versions
+            meta: None,
         };
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_tools_call(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: CallToolParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing tool call parameters".to_string(),
-                ))
-            }
-        };
-
+    async fn handle_tools_call(
+        &self,
+        params: CallToolParams,
+        context: RequestContext,
+    ) -> McpResult<Value> {
         validate_call_tool_params(&params)?;
 
-        let result = self.call_tool(&params.name, params.arguments).await?;
+        let result = self
+            .call_tool_with_context(&params.name, params.arguments, context)
+            .await?;
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_resources_list(&self, params: Option<Value>) -> McpResult<Value> {
-        let _params: ListResourcesParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => ListResourcesParams::default(),
-        };
-
+    async fn handle_resources_list(&self, _params: ListResourcesParams) -> McpResult<Value> {
         let resources = self.list_resources().await?;
         let result = ListResourcesResult {
             resources,
             next_cursor: None, // Pagination support will be added in future versions
+            meta: None,
         };
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_resources_read(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: ReadResourceParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing resource read parameters".to_string(),
-                ))
-            }
-        };
-
+    async fn handle_resources_read(
+        &self,
+        params: ReadResourceParams,
+        context: RequestContext,
+    ) -> McpResult<Value> {
         validate_read_resource_params(&params)?;
 
-        let contents = self.read_resource(&params.uri).await?;
-        let result = ReadResourceResult { contents };
+        let contents = self
+            .read_resource_with_context(&params.uri, context)
+            .await?;
+        let result = ReadResourceResult {
+            contents,
+            meta: None,
+        };
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_resources_subscribe(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: SubscribeResourceParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing resource subscribe parameters".to_string(),
-                ))
-            }
-        };
-
+    async fn handle_resources_subscribe(
+        &self,
+        params: SubscribeResourceParams,
+    ) -> McpResult<Value> {
         // Resource subscriptions functionality planned for future implementation
         let _uri = params.uri;
-        let result = SubscribeResourceResult {};
+        let result = SubscribeResourceResult::default();
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_resources_unsubscribe(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: UnsubscribeResourceParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing resource unsubscribe parameters".to_string(),
-                ))
-            }
-        };
-
+    async fn handle_resources_unsubscribe(
+        &self,
+        params: UnsubscribeResourceParams,
+    ) -> McpResult<Value> {
         // Resource subscriptions functionality planned for future implementation
         let _uri = params.uri;
-        let result = UnsubscribeResourceResult {};
+        let result = UnsubscribeResourceResult::default();
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_prompts_list(&self, params: Option<Value>) -> McpResult<Value> {
-        let _params: ListPromptsParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => ListPromptsParams::default(),
-        };
-
+    async fn handle_prompts_list(&self, _params: ListPromptsParams) -> McpResult<Value> {
         let prompts = self.list_prompts().await?;
         let result = ListPromptsResult {
             prompts,
             next_cursor: None, // Pagination support will be added in future versions
+            meta: None,
         };
 
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_prompts_get(&self, params: Option<Value>) -> McpResult<Value> {
-        let params: GetPromptParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing prompt get parameters".to_string(),
-                ))
-            }
-        };
-
+    async fn handle_prompts_get(
+        &self,
+        params: GetPromptParams,
+        context: RequestContext,
+    ) -> McpResult<Value> {
         validate_get_prompt_params(&params)?;
 
-        let result = self.get_prompt(&params.name, params.arguments).await?;
+        let result = self
+            .get_prompt_with_context(&params.name, params.arguments, context)
+            .await?;
         Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_logging_set_level(&self, params: Option<Value>) -> McpResult<Value> {
-        let _params: SetLoggingLevelParams = match params {
-            Some(p) => serde_json::from_value(p)?,
-            None => {
-                return Err(McpError::Validation(
-                    "Missing logging level parameters".to_string(),
-                ))
-            }
-        };
-
-        // Logging level management feature planned for future implementation
-        let result = SetLoggingLevelResult {};
+    async fn handle_logging_set_level(
+        &self,
+        state: &SessionState,
+        params: SetLoggingLevelParams,
+    ) -> McpResult<Value> {
+        match state.session.write().await.as_mut() {
+            Some(session) => session.log_level = Some(params.level),
+            None => tracing::debug!("Ignoring logging level set before initialization"),
+        }
+        let result = SetLoggingLevelResult::default();
         Ok(serde_json::to_value(result)?)
     }
 
@@ -703,39 +1354,52 @@ impl McpServer {
     // ========================================================================
 
     async fn emit_resources_list_changed(&self) -> McpResult<()> {
-        let notification = JsonRpcNotification::new(
-            methods::RESOURCES_LIST_CHANGED.to_string(),
-            Some(ResourceListChangedParams {}),
-        )?;
-
+        let notification =
+            ServerNotification::ResourceListChanged(Default::default()).into_notification()?;
         self.send_notification(notification).await
     }
 
     async fn emit_tools_list_changed(&self) -> McpResult<()> {
-        let notification = JsonRpcNotification::new(
-            methods::TOOLS_LIST_CHANGED.to_string(),
-            Some(ToolListChangedParams {}),
-        )?;
-
+        let notification =
+            ServerNotification::ToolListChanged(Default::default()).into_notification()?;
         self.send_notification(notification).await
     }
 
     async fn emit_prompts_list_changed(&self) -> McpResult<()> {
-        let notification = JsonRpcNotification::new(
-            methods::PROMPTS_LIST_CHANGED.to_string(),
-            Some(PromptListChangedParams {}),
-        )?;
-
+        let notification =
+            ServerNotification::PromptListChanged(Default::default()).into_notification()?;
         self.send_notification(notification).await
     }
 
-    /// Send a notification through the transport
+    /// Send a notification to the client of a single session
+    ///
+    /// Use this for notifications that concern one client only, such as progress,
+    /// log messages and updates of resources it subscribed to.
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the client's connection
+    /// * `notification` - The notification to send
+    ///
+    /// # Returns
+    /// Result indicating success or an error
+    pub async fn notify_session(
+        &self,
+        session_id: &str,
+        notification: ServerNotification,
+    ) -> McpResult<()> {
+        let notification = notification.into_notification()?;
+        self.peer(Some(session_id.to_string()))
+            .notify(notification)
+            .await
+    }
+
+    /// Send a notification to every client of the transport
     async fn send_notification(&self, notification: JsonRpcNotification) -> McpResult<()> {
-        let mut transport_guard = self.transport.lock().await;
-        if let Some(transport) = transport_guard.as_mut() {
-            transport.send_notification(notification).await?;
+        // Changes made before the server starts are not announced
+        if self.sender.read().await.is_none() && self.transport.lock().await.is_none() {
+            return Ok(());
         }
-        Ok(())
+        self.peer(None).notify(notification).await
     }
 
     // ========================================================================
@@ -743,10 +1407,113 @@ impl McpServer {
     // ========================================================================
 
     #[allow(dead_code)]
-    async fn next_request_id(&self) -> u64 {
-        let mut counter = self.request_counter.lock().await;
-        *counter += 1;
-        *counter
+    fn next_request_id(&self) -> RequestId {
+        self.request_ids.next_id()
+    }
+}
+
+/// Marks a request ID as in flight until dropped
+struct InFlight<'a> {
+    ids: &'a InFlightRequests,
+    id: RequestId,
+    cancel: Arc<Notify>,
+}
+
+impl<'a> InFlight<'a> {
+    /// Mark `id` as in flight, unless a request with that ID already is
+    fn track(ids: &'a InFlightRequests, id: &RequestId) -> Option<Self> {
+        let mut in_flight = ids.lock().unwrap_or_else(|e| e.into_inner());
+        if in_flight.contains_key(id) {
+            return None;
+        }
+        let cancel = Arc::new(Notify::new());
+        in_flight.insert(id.clone(), cancel.clone());
+        Some(Self {
+            ids,
+            id: id.clone(),
+            cancel,
+        })
+    }
+
+    /// Wait until the client cancels the request
+    async fn cancelled(&self) {
+        self.cancel.notified().await
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+        in_flight.remove(&self.id);
+    }
+}
+
+/// Sends requests and notifications to the client through the server's transport
+///
+/// Messages go through the transport's sender. Transports without one are
+/// locked instead, which only succeeds while they are not serving.
+struct TransportPeer {
+    transport: Arc<Mutex<Option<Box<dyn ServerTransport>>>>,
+    sender: Arc<RwLock<Option<Arc<dyn ServerSender>>>>,
+    request_ids: Arc<RequestIdGenerator>,
+    /// Session of the client, whose notifications are not sent to other clients
+    session_id: Option<String>,
+}
+
+impl TransportPeer {
+    /// Send a notification to the session's client, or to every client without a session
+    async fn notify(&self, notification: JsonRpcNotification) -> McpResult<()> {
+        if let Some(sender) = self.sender.read().await.clone() {
+            return sender
+                .send_notification(self.session_id.as_deref(), notification)
+                .await;
+        }
+
+        let mut transport_guard = self.transport.lock().await;
+        let transport = transport_guard
+            .as_mut()
+            .ok_or_else(|| McpError::connection("Server transport is not running"))?;
+        match &self.session_id {
+            Some(session_id) => {
+                transport
+                    .send_session_notification(session_id, notification)
+                    .await
+            }
+            None => transport.send_notification(notification).await,
+        }
+    }
+}
+
+#[async_trait]
+impl PeerSender for TransportPeer {
+    async fn send_request(&self, method: &str, params: Option<Value>) -> McpResult<Value> {
+        let request = JsonRpcRequest::new(self.request_ids.next_id(), method.to_string(), params)?;
+
+        let sender = self.sender.read().await.clone();
+        let response = match sender {
+            Some(sender) => {
+                sender
+                    .send_request(self.session_id.as_deref(), request)
+                    .await?
+            }
+            None => {
+                let mut transport_guard = self.transport.lock().await;
+                let transport = transport_guard
+                    .as_mut()
+                    .ok_or_else(|| McpError::connection("Server transport is not running"))?;
+                transport.send_request(request).await?
+            }
+        };
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(McpError::from_rpc_error(error)),
+            (result, None) => Ok(result.unwrap_or(Value::Null)),
+        }
+    }
+
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> McpResult<()> {
+        let notification = JsonRpcNotification::new(method.to_string(), params)?;
+        self.notify(notification).await
     }
 }
 
@@ -783,6 +1550,8 @@ mod tests {
                 Ok(ToolResult {
                     content: vec![Content::text("Hello from tool")],
                     is_error: None,
+                    structured_content: None,
+                    meta: None,
                 })
             }
         }
@@ -807,6 +1576,139 @@ mod tests {
         assert_eq!(result.content.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_add_tool_detailed() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let info = ToolInfo {
+            name: "stats".to_string(),
+            title: Some("Statistics".to_string()),
+            description: Some("Summarize numbers".to_string()),
+            input_schema: json!({"type": "object"}),
+            output_schema: Some(json!({
+                "type": "object",
+                "properties": {"mean": {"type": "number"}},
+                "required": ["mean"]
+            })),
+            annotations: Some(ToolAnnotations {
+                read_only_hint: Some(true),
+                ..ToolAnnotations::default()
+            }),
+        };
+        let handler = FnToolHandler::new(|_arguments, _context| async {
+            Ok(ToolResult {
+                content: vec![Content::text("no structured content")],
+                is_error: None,
+                structured_content: None,
+                meta: None,
+            })
+        });
+        server
+            .add_tool_detailed(info.clone(), handler)
+            .await
+            .unwrap();
+
+        assert_eq!(server.list_tools().await.unwrap(), vec![info.clone()]);
+
+        // The output schema is enforced on results
+        let error = server.call_tool("stats", None).await.unwrap_err();
+        assert!(
+            error.to_string().contains("structured content"),
+            "{}",
+            error
+        );
+
+        #[cfg(feature = "validation")]
+        {
+            let invalid = ToolInfo {
+                output_schema: Some(json!({"type": 42})),
+                ..info
+            };
+            let handler = FnToolHandler::new(|_arguments, _context| async {
+                Ok(ToolResult {
+                    content: vec![],
+                    is_error: None,
+                    structured_content: None,
+                    meta: None,
+                })
+            });
+            assert!(server.add_tool_detailed(invalid, handler).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_closure_handlers() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let counter = calls.clone();
+        server
+            .add_tool_fn(
+                "count".to_string(),
+                None,
+                json!({"type": "object"}),
+                move |_args, context| {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        Ok(ToolResult {
+                            content: vec![Content::text(format!("{:?}", context.request_id))],
+                            is_error: None,
+                            structured_content: None,
+                            meta: None,
+                        })
+                    }
+                },
+            )
+            .await
+            .unwrap();
+
+        server
+            .add_prompt_fn(
+                PromptInfo {
+                    name: "hello".to_string(),
+                    description: None,
+                    arguments: None,
+                },
+                |_args, _context| async {
+                    Ok(PromptResult {
+                        description: Some("hello".to_string()),
+                        messages: vec![],
+                        meta: None,
+                    })
+                },
+            )
+            .await
+            .unwrap();
+
+        server
+            .add_resource_fn(
+                "Echo".to_string(),
+                "echo://uri".to_string(),
+                |uri, _context| async move { Ok(uri) },
+            )
+            .await
+            .unwrap();
+
+        let request = JsonRpcRequest::new(
+            RequestId::from(7),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "count"})),
+        )
+        .unwrap();
+        let response = server.handle_request(request).await.unwrap();
+        assert_eq!(
+            response.result.unwrap()["content"][0]["text"],
+            "Some(Number(7))"
+        );
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let prompt = server.get_prompt("hello", None).await.unwrap();
+        assert_eq!(prompt.description.as_deref(), Some("hello"));
+
+        let contents = server.read_resource("echo://uri").await.unwrap();
+        assert_eq!(contents[0].text.as_deref(), Some("echo://uri"));
+    }
+
     #[tokio::test]
     async fn test_initialize_request() {
         let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
@@ -820,12 +1722,1203 @@ mod tests {
             MCP_PROTOCOL_VERSION.to_string(),
         );
 
-        let request =
-            JsonRpcRequest::new(json!(1), methods::INITIALIZE.to_string(), Some(init_params))
-                .unwrap();
+        let request = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::INITIALIZE.to_string(),
+            Some(init_params),
+        )
+        .unwrap();
 
         let response = server.handle_request(request).await.unwrap();
         assert!(response.result.is_some());
         assert!(response.error.is_none());
     }
+
+    #[tokio::test]
+    async fn test_extractor_handlers() {
+        use crate::core::extract::{Args, Meta, State};
+
+        struct Pool {
+            name: &'static str,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Query {
+            sql: String,
+        }
+
+        async fn query(
+            State(pool): State<Pool>,
+            Args(query): Args<Query>,
+            session: Session,
+            Meta(meta): Meta,
+        ) -> McpResult<ToolResult> {
+            Ok(ToolResult {
+                content: vec![Content::text(format!(
+                    "{} ran '{}' on {} ({})",
+                    session.client_info.name, query.sql, pool.name, meta["trace"]
+                ))],
+                is_error: None,
+                structured_content: None,
+                meta: None,
+            })
+        }
+
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server.add_state(Pool { name: "main" });
+        server
+            .add_tool_handler("query".to_string(), None, json!({"type": "object"}), query)
+            .await
+            .unwrap();
+
+        let call = JsonRpcRequest::new(
+            RequestId::from(2),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({
+                "name": "query",
+                "arguments": {"sql": "select 1"},
+                "_meta": {"trace": "abc"}
+            })),
+        )
+        .unwrap();
+        let response = server.handle_request(call.clone()).await.unwrap();
+        assert!(response.error.is_some());
+
+        let init_params = InitializeParams::new(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ClientCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let init = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::INITIALIZE.to_string(),
+            Some(init_params),
+        )
+        .unwrap();
+        server.handle_request(init).await.unwrap();
+        assert_eq!(
+            server.session().await.unwrap().client_info.name,
+            "test-client"
+        );
+
+        let response = server.handle_request(call).await.unwrap();
+        assert_eq!(
+            response.result.unwrap()["content"][0]["text"],
+            "test-client ran 'select 1' on main (\"abc\")"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_codes() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+
+        let unknown = JsonRpcRequest::new(
+            RequestId::from(1),
+            "tools/frobnicate".to_string(),
+            None::<Value>,
+        )
+        .unwrap();
+        let error = server.handle_request(unknown).await.unwrap().error.unwrap();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+        assert_eq!(error.data, Some(json!({"method": "tools/frobnicate"})));
+
+        let malformed = JsonRpcRequest::new(
+            RequestId::from(2),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"arguments": {}})),
+        )
+        .unwrap();
+        let error = server
+            .handle_request(malformed)
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        let missing_tool = JsonRpcRequest::new(
+            RequestId::from(3),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "search"})),
+        )
+        .unwrap();
+        let error = server
+            .handle_request(missing_tool)
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert_eq!(error.code, TOOL_NOT_FOUND);
+        assert_eq!(error.data, Some(json!({"name": "search"})));
+    }
+
+    /// Tool that completes once it is released
+    struct BlockingTool(Arc<Notify>);
+
+    #[async_trait::async_trait]
+    impl ToolHandler for BlockingTool {
+        async fn call(&self, _arguments: HashMap<String, Value>) -> McpResult<ToolResult> {
+            self.0.notified().await;
+            Ok(ToolResult {
+                content: vec![Content::text("done")],
+                is_error: None,
+                structured_content: None,
+                meta: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_request_id_rejected() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+
+        let release = Arc::new(Notify::new());
+        server
+            .add_tool(
+                "block".to_string(),
+                None,
+                json!({"type": "object"}),
+                BlockingTool(release.clone()),
+            )
+            .await
+            .unwrap();
+
+        let call = JsonRpcRequest::new(
+            RequestId::from("call"),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "block"})),
+        )
+        .unwrap();
+        let (first, second) = tokio::join!(server.handle_request(call.clone()), async {
+            let response = server.handle_request(call.clone()).await;
+            release.notify_one();
+            response
+        });
+
+        assert!(first.unwrap().result.is_some());
+        let error = second.unwrap().error.unwrap();
+        assert_eq!(error.code, INVALID_REQUEST);
+
+        // The ID can be reused once the first request has completed
+        release.notify_one();
+        let response = server.handle_request(call).await.unwrap();
+        assert!(response.result.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_sessions() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let ended = Arc::new(StdMutex::new(Vec::new()));
+        server
+            .on_session_start(|session| async move {
+                session.data.insert(session.client_info.name.clone());
+            })
+            .await;
+        server
+            .on_session_end({
+                let ended = ended.clone();
+                move |session| {
+                    let ended = ended.clone();
+                    async move {
+                        let name = session.data.get::<String>().unwrap();
+                        ended.lock().unwrap().push(name.to_string());
+                    }
+                }
+            })
+            .await;
+
+        for (session_id, name) in [("a", "alice"), ("b", "bob")] {
+            server.open_session(session_id);
+            let init_params = InitializeParams::new(
+                ClientInfo {
+                    name: name.to_string(),
+                    version: "1.0.0".to_string(),
+                },
+                ClientCapabilities::default(),
+                MCP_PROTOCOL_VERSION.to_string(),
+            );
+            let request = JsonRpcRequest::new(
+                RequestId::from(1),
+                methods::INITIALIZE.to_string(),
+                Some(init_params),
+            )
+            .unwrap();
+            let response = server
+                .handle_session_request(Some(session_id), request)
+                .await
+                .unwrap();
+            assert!(response.error.is_none());
+        }
+        assert!(server.session().await.is_none());
+
+        // Sessions pick request IDs independently of each other
+        let release = Arc::new(Notify::new());
+        server
+            .add_tool(
+                "block".to_string(),
+                None,
+                json!({"type": "object"}),
+                BlockingTool(release.clone()),
+            )
+            .await
+            .unwrap();
+        let call = JsonRpcRequest::new(
+            RequestId::from("call"),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "block"})),
+        )
+        .unwrap();
+        let (first, second, _) = tokio::join!(
+            server.handle_session_request(Some("a"), call.clone()),
+            server.handle_session_request(Some("b"), call.clone()),
+            async {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                release.notify_waiters();
+            }
+        );
+        assert!(first.unwrap().error.is_none());
+        assert!(second.unwrap().error.is_none());
+
+        let set_level = JsonRpcRequest::new(
+            RequestId::from(2),
+            methods::LOGGING_SET_LEVEL.to_string(),
+            Some(json!({"level": "debug"})),
+        )
+        .unwrap();
+        server
+            .handle_session_request(Some("a"), set_level)
+            .await
+            .unwrap();
+
+        let mut sessions = server.sessions().await;
+        sessions.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id.as_deref(), Some("a"));
+        assert_eq!(sessions[0].log_level, Some(LoggingLevel::Debug));
+        assert_eq!(sessions[1].client_info.name, "bob");
+        assert_eq!(sessions[1].log_level, None);
+
+        server.close_session("a").await;
+        assert_eq!(*ended.lock().unwrap(), vec!["alice".to_string()]);
+        let sessions = server.sessions().await;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id.as_deref(), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn test_session_notifications() {
+        type Sent = Arc<StdMutex<Vec<(Option<String>, String)>>>;
+
+        // Records which session each notification was addressed to
+        struct RecordingTransport(Sent);
+
+        #[async_trait]
+        impl ServerTransport for RecordingTransport {
+            async fn start(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+
+            async fn handle_request(
+                &mut self,
+                _request: JsonRpcRequest,
+            ) -> McpResult<JsonRpcResponse> {
+                Err(McpError::transport("Not supported"))
+            }
+
+            async fn send_notification(
+                &mut self,
+                notification: JsonRpcNotification,
+            ) -> McpResult<()> {
+                self.0.lock().unwrap().push((None, notification.method));
+                Ok(())
+            }
+
+            async fn send_session_notification(
+                &mut self,
+                session_id: &str,
+                notification: JsonRpcNotification,
+            ) -> McpResult<()> {
+                let sent = (Some(session_id.to_string()), notification.method);
+                self.0.lock().unwrap().push(sent);
+                Ok(())
+            }
+
+            async fn stop(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+        }
+
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let sent = Sent::default();
+        server
+            .add_tool_fn(
+                "report".to_string(),
+                None,
+                json!({"type": "object"}),
+                |_arguments, context: RequestContext| async move {
+                    let progress = json!({"progressToken": "report", "progress": 1});
+                    context
+                        .peer
+                        .unwrap()
+                        .notify(methods::PROGRESS, Some(progress))
+                        .await?;
+                    Ok(ToolResult {
+                        content: vec![Content::text("done")],
+                        is_error: None,
+                        structured_content: None,
+                        meta: None,
+                    })
+                },
+            )
+            .await
+            .unwrap();
+        server
+            .start(RecordingTransport(sent.clone()))
+            .await
+            .unwrap();
+
+        let call = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "report"})),
+        )
+        .unwrap();
+        server.open_session("a");
+        let response = server
+            .handle_session_request(Some("a"), call.clone())
+            .await
+            .unwrap();
+        assert!(response.error.is_none());
+        server.handle_request(call).await.unwrap();
+        server
+            .notify_session("b", ServerNotification::ToolListChanged(Default::default()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *sent.lock().unwrap(),
+            vec![
+                (Some("a".to_string()), methods::PROGRESS.to_string()),
+                (None, methods::PROGRESS.to_string()),
+                (
+                    Some("b".to_string()),
+                    methods::TOOLS_LIST_CHANGED.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[cfg(feature = "stdio")]
+    #[tokio::test]
+    async fn test_stdio_tool_sends_notification() {
+        use crate::transport::{stdio::StdioServerTransport, traits::TransportConfig};
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let (client, server_end) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_end);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .add_tool_fn(
+                "report".to_string(),
+                None,
+                json!({"type": "object"}),
+                |_arguments, context: RequestContext| async move {
+                    let progress = json!({"progressToken": "report", "progress": 1});
+                    context
+                        .peer
+                        .unwrap()
+                        .notify(methods::PROGRESS, Some(progress))
+                        .await?;
+                    Ok(ToolResult {
+                        content: vec![Content::text("done")],
+                        is_error: None,
+                        structured_content: None,
+                        meta: None,
+                    })
+                },
+            )
+            .await
+            .unwrap();
+
+        // STDIO serves until its input ends, so the server runs on its own task
+        let serving = tokio::spawn(async move {
+            let transport = StdioServerTransport::with_streams(
+                server_read,
+                server_write,
+                TransportConfig::default(),
+            );
+            server.start(transport).await
+        });
+
+        let call = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "report"})),
+        )
+        .unwrap();
+        let line = format!("{}\n", serde_json::to_string(&call).unwrap());
+        client_write.write_all(line.as_bytes()).await.unwrap();
+
+        // The notification is written while the tool runs, before its response
+        let mut lines = BufReader::new(client_read).lines();
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let line = tokio::time::timeout(std::time::Duration::from_secs(5), lines.next_line())
+                .await
+                .expect("the server stopped answering")
+                .unwrap()
+                .unwrap();
+            received.push(serde_json::from_str::<Value>(&line).unwrap());
+        }
+        assert_eq!(received[0]["method"], methods::PROGRESS);
+        let response: JsonRpcResponse = serde_json::from_value(received.remove(1)).unwrap();
+        assert_eq!(response.id, Some(RequestId::from(1)));
+        assert!(response.error.is_none());
+
+        client_write.shutdown().await.unwrap();
+        serving.await.unwrap().unwrap();
+    }
+
+    /// Server whose `workspace` tool asks the calling client for its roots
+    #[cfg(feature = "http")]
+    async fn roots_server() -> McpServer {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .add_tool_fn(
+                "workspace".to_string(),
+                None,
+                json!({"type": "object"}),
+                |_arguments, context: RequestContext| async move {
+                    let roots: ListRootsResult = context
+                        .peer
+                        .unwrap()
+                        .request(methods::ROOTS_LIST, None::<Value>)
+                        .await?;
+                    Ok(ToolResult {
+                        content: vec![Content::text(roots.roots[0].uri.clone())],
+                        is_error: None,
+                        structured_content: None,
+                        meta: None,
+                    })
+                },
+            )
+            .await
+            .unwrap();
+        server
+    }
+
+    /// Client answering `roots/list` with a single root
+    #[cfg(feature = "http")]
+    fn roots_client() -> crate::client::McpClient {
+        let mut client =
+            crate::client::McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        client.on_server_request(|request| async move {
+            match request {
+                ServerRequest::ListRoots(_) => Ok(json!({"roots": [{"uri": "file:///workspace"}]})),
+                _ => Err(McpError::MethodNotFound("unexpected request".to_string())),
+            }
+        });
+        client
+    }
+
+    #[cfg(feature = "http")]
+    async fn call_workspace(client: &crate::client::McpClient) -> String {
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            client.call_tool("workspace".to_string(), None),
+        )
+        .await
+        .expect("the tool call did not complete")
+        .unwrap();
+        match &result.content[0] {
+            Content::Text { text, .. } => text.clone(),
+            other => panic!("unexpected content: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn test_websocket_tool_requests_client_roots() {
+        use crate::transport::websocket::{WebSocketClientTransport, WebSocketServerTransport};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = roots_server().await;
+        server
+            .start(WebSocketServerTransport::new(format!("127.0.0.1:{}", port)))
+            .await
+            .unwrap();
+
+        let mut client = roots_client();
+        let transport = WebSocketClientTransport::new(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        client.connect(transport).await.unwrap();
+
+        // The request goes out to the calling client and its answer comes back
+        assert_eq!(call_workspace(&client).await, "file:///workspace");
+
+        client.disconnect().await.unwrap();
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_http_tool_requests_client_roots() {
+        use crate::transport::http::{HttpClientTransport, HttpServerTransport};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = roots_server().await;
+        server
+            .start(HttpServerTransport::new(format!("127.0.0.1:{}", port)))
+            .await
+            .unwrap();
+
+        // Requests reach the client on its session's event stream
+        let mut client = roots_client();
+        let url = format!("http://127.0.0.1:{}", port);
+        let events = format!("{}/mcp/events", url);
+        let transport = HttpClientTransport::new(url, Some(events)).await.unwrap();
+        client.connect(transport).await.unwrap();
+
+        assert_eq!(call_workspace(&client).await, "file:///workspace");
+
+        client.disconnect().await.unwrap();
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn test_websocket_session_removed_after_disconnect() {
+        use crate::transport::websocket::WebSocketServerTransport;
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .add_tool(
+                "block".to_string(),
+                None,
+                json!({"type": "object"}),
+                BlockingTool(Arc::new(Notify::new())),
+            )
+            .await
+            .unwrap();
+        server
+            .start(WebSocketServerTransport::new(format!("127.0.0.1:{}", port)))
+            .await
+            .unwrap();
+        let open_sessions = || server.sessions.read().unwrap().len();
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        let init_params = InitializeParams::new(
+            ClientInfo {
+                name: "test-client".to_string(),
+                version: "1.0.0".to_string(),
+            },
+            ClientCapabilities::default(),
+            MCP_PROTOCOL_VERSION.to_string(),
+        );
+        let initialize = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::INITIALIZE.to_string(),
+            Some(init_params),
+        )
+        .unwrap();
+        let text = serde_json::to_string(&initialize).unwrap();
+        client.send(Message::Text(text)).await.unwrap();
+        client.next().await.unwrap().unwrap();
+        assert_eq!(open_sessions(), 1);
+
+        // Disconnect while a request is still being handled
+        let call = JsonRpcRequest::new(
+            RequestId::from(2),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "block"})),
+        )
+        .unwrap();
+        let text = serde_json::to_string(&call).unwrap();
+        client.send(Message::Text(text)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        client.close(None).await.unwrap();
+        drop(client);
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while open_sessions() > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the session was not closed");
+        // Nothing handled after the close brings the session back
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(open_sessions(), 0);
+
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "websocket")]
+    #[tokio::test]
+    async fn test_result_meta_reaches_client() {
+        use crate::transport::websocket::{WebSocketClientTransport, WebSocketServerTransport};
+
+        let meta = json!({"vendor/trace": "abc"}).as_object().unwrap().clone();
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let tool_meta = meta.clone();
+        server
+            .add_tool_fn(
+                "traced".to_string(),
+                None,
+                json!({"type": "object"}),
+                move |_arguments, _context| {
+                    let meta = tool_meta.clone();
+                    async move {
+                        Ok(ToolResult {
+                            content: vec![Content::text("done")],
+                            is_error: None,
+                            structured_content: None,
+                            meta: Some(meta),
+                        })
+                    }
+                },
+            )
+            .await
+            .unwrap();
+        let prompt_meta = meta.clone();
+        server
+            .add_prompt_fn(
+                PromptInfo {
+                    name: "traced".to_string(),
+                    description: None,
+                    arguments: None,
+                },
+                move |_args, _context| {
+                    let meta = prompt_meta.clone();
+                    async move {
+                        Ok(PromptResult {
+                            description: None,
+                            messages: vec![],
+                            meta: Some(meta),
+                        })
+                    }
+                },
+            )
+            .await
+            .unwrap();
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        server
+            .start(WebSocketServerTransport::new(format!("127.0.0.1:{}", port)))
+            .await
+            .unwrap();
+
+        let mut client =
+            crate::client::McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let transport = WebSocketClientTransport::new(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        client.connect(transport).await.unwrap();
+
+        let tool = client.call_tool("traced".to_string(), None).await.unwrap();
+        assert_eq!(tool.meta, Some(meta.clone()));
+        let prompt = client.get_prompt("traced".to_string(), None).await.unwrap();
+        assert_eq!(prompt.meta, Some(meta));
+
+        client.disconnect().await.unwrap();
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "http")]
+    #[tokio::test]
+    async fn test_http_request_without_session_has_no_session() {
+        use crate::core::extract::Session;
+        use crate::transport::http::{HttpClientTransport, HttpServerTransport};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .add_tool_handler(
+                "whoami".to_string(),
+                None,
+                json!({"type": "object"}),
+                |session: Session| async move {
+                    Ok(ToolResult {
+                        content: vec![Content::text(session.client_info.name)],
+                        is_error: None,
+                        structured_content: None,
+                        meta: None,
+                    })
+                },
+            )
+            .await
+            .unwrap();
+        server
+            .start(HttpServerTransport::new(format!("127.0.0.1:{}", port)))
+            .await
+            .unwrap();
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let mut client =
+            crate::client::McpClient::new("test-client".to_string(), "1.0.0".to_string());
+        let transport = HttpClientTransport::new(url.clone(), None).await.unwrap();
+        client.connect(transport).await.unwrap();
+        let result = client.call_tool("whoami".to_string(), None).await.unwrap();
+        assert_eq!(result.content[0], Content::text("test-client"));
+
+        // The same call without the session header is not attributed to that client
+        let call = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "whoami"})),
+        )
+        .unwrap();
+        let response: JsonRpcResponse = reqwest::Client::new()
+            .post(format!("{}/mcp", url))
+            .json(&call)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let error = response
+            .error
+            .expect("a session-less request has no session");
+        assert!(error.message.contains("session"), "{}", error.message);
+
+        client.disconnect().await.unwrap();
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_request_cancelled() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server
+            .add_tool(
+                "block".to_string(),
+                None,
+                json!({"type": "object"}),
+                BlockingTool(Arc::new(Notify::new())),
+            )
+            .await
+            .unwrap();
+
+        let call = JsonRpcRequest::new(
+            RequestId::from(9),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "block"})),
+        )
+        .unwrap();
+        let cancel = ClientNotification::Cancelled(CancelledParams {
+            request_id: RequestId::from(9),
+            reason: Some("user abort".to_string()),
+            meta: None,
+        })
+        .into_notification()
+        .unwrap();
+
+        let (response, cancelled) = tokio::join!(
+            server.handle_request(call),
+            server.handle_notification(cancel)
+        );
+        cancelled.unwrap();
+        let error = response.unwrap().error.unwrap();
+        assert_eq!(error.code, REQUEST_CANCELLED);
+        assert!(server.default_session.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notification_handling() {
+        use std::sync::atomic::AtomicUsize;
+
+        /// Transport answering `roots/list` with a different root each time
+        struct RootsTransport(Arc<AtomicUsize>);
+
+        #[async_trait]
+        impl ServerTransport for RootsTransport {
+            async fn start(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+
+            async fn handle_request(
+                &mut self,
+                _request: JsonRpcRequest,
+            ) -> McpResult<JsonRpcResponse> {
+                Err(McpError::transport("Not supported"))
+            }
+
+            async fn send_notification(
+                &mut self,
+                _notification: JsonRpcNotification,
+            ) -> McpResult<()> {
+                Ok(())
+            }
+
+            async fn send_request(
+                &mut self,
+                request: JsonRpcRequest,
+            ) -> McpResult<JsonRpcResponse> {
+                assert_eq!(request.method, methods::ROOTS_LIST);
+                let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                let roots = json!({"roots": [{"uri": format!("file:///workspace{}", count)}]});
+                Ok(JsonRpcResponse::success(request.id, roots)?)
+            }
+
+            async fn stop(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+        }
+
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        let fetches = Arc::new(AtomicUsize::new(0));
+        server.start(RootsTransport(fetches.clone())).await.unwrap();
+
+        let changes = Arc::new(AtomicUsize::new(0));
+        let counter = changes.clone();
+        server
+            .on_notification(methods::ROOTS_LIST_CHANGED, move |_params, _context| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            })
+            .await;
+
+        let notification =
+            |method: &str| JsonRpcNotification::new(method.to_string(), None::<Value>).unwrap();
+
+        assert!(!server.is_client_initialized());
+        server
+            .handle_notification(notification(methods::INITIALIZED))
+            .await
+            .unwrap();
+        assert!(server.is_client_initialized());
+
+        // Roots are cached until the client reports a change
+        let context = server.request_context(None).await;
+        assert_eq!(
+            server.list_roots(&context).await.unwrap()[0].uri,
+            "file:///workspace1"
+        );
+        assert_eq!(
+            server.list_roots(&context).await.unwrap()[0].uri,
+            "file:///workspace1"
+        );
+        server
+            .handle_notification(notification(methods::ROOTS_LIST_CHANGED))
+            .await
+            .unwrap();
+        assert_eq!(changes.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            server.list_roots(&context).await.unwrap()[0].uri,
+            "file:///workspace2"
+        );
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // Unknown notifications without handlers are ignored, malformed built-in ones rejected
+        server
+            .handle_notification(notification("vendor/event"))
+            .await
+            .unwrap();
+        assert!(server
+            .handle_notification(notification(methods::CANCELLED))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_roots_per_session() {
+        /// Sender answering `roots/list` with a root named after the asking session
+        struct RootsSender;
+
+        #[async_trait]
+        impl ServerSender for RootsSender {
+            async fn send_notification(
+                &self,
+                _session_id: Option<&str>,
+                _notification: JsonRpcNotification,
+            ) -> McpResult<()> {
+                Ok(())
+            }
+
+            async fn send_request(
+                &self,
+                session_id: Option<&str>,
+                request: JsonRpcRequest,
+            ) -> McpResult<JsonRpcResponse> {
+                let uri = format!("file:///{}", session_id.unwrap_or("default"));
+                Ok(JsonRpcResponse::success(
+                    request.id,
+                    json!({"roots": [{"uri": uri}]}),
+                )?)
+            }
+        }
+
+        struct RootsTransport;
+
+        #[async_trait]
+        impl ServerTransport for RootsTransport {
+            async fn start(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+
+            async fn handle_request(
+                &mut self,
+                _request: JsonRpcRequest,
+            ) -> McpResult<JsonRpcResponse> {
+                Err(McpError::transport("Not supported"))
+            }
+
+            async fn send_notification(
+                &mut self,
+                _notification: JsonRpcNotification,
+            ) -> McpResult<()> {
+                Ok(())
+            }
+
+            fn sender(&self) -> Option<Arc<dyn ServerSender>> {
+                Some(Arc::new(RootsSender))
+            }
+
+            async fn stop(&mut self) -> McpResult<()> {
+                Ok(())
+            }
+        }
+
+        let mut server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+        server.start(RootsTransport).await.unwrap();
+
+        let contexts = Arc::new(StdMutex::new(Vec::new()));
+        let recorded = contexts.clone();
+        server
+            .on_notification(methods::ROOTS_LIST_CHANGED, move |_params, context| {
+                recorded.lock().unwrap().push(context);
+                async { Ok(()) }
+            })
+            .await;
+
+        for session_id in ["a", "b"] {
+            server.open_session(session_id);
+            let init_params = InitializeParams::new(
+                ClientInfo {
+                    name: session_id.to_string(),
+                    version: "1.0.0".to_string(),
+                },
+                ClientCapabilities::default(),
+                MCP_PROTOCOL_VERSION.to_string(),
+            );
+            let request = JsonRpcRequest::new(
+                RequestId::from(1),
+                methods::INITIALIZE.to_string(),
+                Some(init_params),
+            )
+            .unwrap();
+            server
+                .handle_session_request(Some(session_id), request)
+                .await
+                .unwrap();
+            let notification =
+                JsonRpcNotification::new(methods::ROOTS_LIST_CHANGED.to_string(), None::<Value>)
+                    .unwrap();
+            server
+                .handle_session_notification(Some(session_id), notification)
+                .await
+                .unwrap();
+        }
+
+        // Each notification's context asks the client that sent it
+        let contexts = contexts.lock().unwrap().clone();
+        assert_eq!(
+            server.list_roots(&contexts[0]).await.unwrap()[0].uri,
+            "file:///a"
+        );
+        assert_eq!(
+            server.list_roots(&contexts[1]).await.unwrap()[0].uri,
+            "file:///b"
+        );
+        assert_eq!(
+            server.list_roots(&RequestContext::new()).await.unwrap()[0].uri,
+            "file:///default"
+        );
+
+        server.close_session("a").await;
+        assert!(server.list_roots(&contexts[0]).await.is_err());
+    }
+
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_tool_arguments_validated_against_schema() {
+        let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+
+        let invalid = server
+            .add_tool(
+                "broken".to_string(),
+                None,
+                json!({"type": "object", "properties": {"x": {"type": 5}}}),
+                crate::core::tool::EchoTool,
+            )
+            .await;
+        assert!(matches!(invalid, Err(McpError::Validation(_))));
+
+        server
+            .add_tool(
+                "echo".to_string(),
+                None,
+                json!({
+                    "type": "object",
+                    "properties": {"message": {"type": "string"}},
+                    "required": ["message"]
+                }),
+                crate::core::tool::EchoTool,
+            )
+            .await
+            .unwrap();
+
+        let request = JsonRpcRequest::new(
+            RequestId::from(1),
+            methods::TOOLS_CALL.to_string(),
+            Some(json!({"name": "echo", "arguments": {"message": 42}})),
+        )
+        .unwrap();
+        let response = server.handle_request(request).await.unwrap();
+        let error = response.error.unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(error.data.unwrap()["violations"][0]["path"], "/message");
+
+        let result = server
+            .call_tool(
+                "echo",
+                Some(HashMap::from([("message".to_string(), json!("hi"))])),
+            )
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[cfg(feature = "macros")]
+    mod macros {
+        use super::*;
+        use crate::{mcp_prompt, mcp_resource, mcp_server, mcp_tool};
+
+        /// Add two numbers
+        #[mcp_tool]
+        async fn add(#[arg(description = "First addend")] a: f64, b: f64) -> McpResult<f64> {
+            Ok(a + b)
+        }
+
+        /// Greet someone by name
+        #[mcp_prompt(name = "greeting")]
+        async fn greet(name: String, style: Option<String>) -> McpResult<PromptResult> {
+            Ok(PromptResult {
+                description: style,
+                messages: vec![PromptMessage {
+                    role: "user".to_string(),
+                    content: PromptContent::text(format!("Hello, {}!", name)),
+                }],
+                meta: None,
+            })
+        }
+
+        #[mcp_resource(uri = "config://app", mime_type = "application/json")]
+        async fn app_config(uri: &str) -> McpResult<String> {
+            Ok(format!("{{\"uri\":\"{}\"}}", uri))
+        }
+
+        struct Counter {
+            step: i64,
+        }
+
+        #[mcp_server]
+        impl Counter {
+            /// Advance a value by the configured step
+            #[tool(name = "advance")]
+            async fn advance(&self, value: i64) -> McpResult<i64> {
+                Ok(value + self.step)
+            }
+
+            #[resource(uri = "counter://step")]
+            async fn step(&self) -> McpResult<String> {
+                Ok(self.step.to_string())
+            }
+        }
+
+        #[tokio::test]
+        async fn test_function_macros() {
+            let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+            server.register_tool(add_tool()).await.unwrap();
+            server.register_prompt(greet_prompt()).await.unwrap();
+            server
+                .register_resource(app_config_resource())
+                .await
+                .unwrap();
+
+            let tools = server.list_tools().await.unwrap();
+            assert_eq!(tools[0].description.as_deref(), Some("Add two numbers"));
+            assert_eq!(
+                tools[0].input_schema["properties"]["a"]["description"],
+                "First addend"
+            );
+
+            let args = HashMap::from([("a".to_string(), json!(2)), ("b".to_string(), json!(3))]);
+            let result = server.call_tool("add", Some(args)).await.unwrap();
+            assert_eq!(result.content, vec![Content::text("5.0")]);
+
+            let prompts = server.list_prompts().await.unwrap();
+            let arguments = prompts[0].arguments.as_ref().unwrap();
+            assert_eq!(prompts[0].name, "greeting");
+            assert!(arguments[0].required);
+            assert!(!arguments[1].required);
+
+            let args = HashMap::from([("name".to_string(), json!("Ada"))]);
+            let prompt = server.get_prompt("greeting", Some(args)).await.unwrap();
+            assert_eq!(prompt.messages.len(), 1);
+
+            let contents = server.read_resource("config://app").await.unwrap();
+            assert_eq!(
+                contents[0].text.as_deref(),
+                Some("{\"uri\":\"config://app\"}")
+            );
+            assert_eq!(contents[0].mime_type.as_deref(), Some("application/json"));
+        }
+
+        #[tokio::test]
+        async fn test_server_macro_registers_methods() {
+            let server = McpServer::new("test-server".to_string(), "1.0.0".to_string());
+            Counter { step: 2 }.register(&server).await.unwrap();
+
+            let args = HashMap::from([("value".to_string(), json!(40))]);
+            let result = server.call_tool("advance", Some(args)).await.unwrap();
+            assert_eq!(result.content, vec![Content::text("42")]);
+
+            let contents = server.read_resource("counter://step").await.unwrap();
+            assert_eq!(contents[0].text.as_deref(), Some("2"));
+        }
+    }
 }
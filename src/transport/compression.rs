@@ -0,0 +1,41 @@
+//! Body compression helpers for the HTTP transports
+//!
+//! Compression is only applied when `TransportConfig::compression` is set and
+//! the peer has advertised support for it, so uncompressed peers keep working.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+use crate::core::error::McpResult;
+
+/// Messages smaller than this are sent uncompressed
+pub(crate) const MIN_COMPRESS_SIZE: usize = 1024;
+
+/// Compress `data` with gzip
+pub(crate) fn gzip(data: &[u8]) -> McpResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Check whether a comma-separated header value lists `token`
+pub(crate) fn header_lists(value: &str, token: &str) -> bool {
+    value.split(',').any(|item| {
+        item.split(';')
+            .next()
+            .is_some_and(|name| name.trim().eq_ignore_ascii_case(token))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_lists() {
+        assert!(header_lists("gzip, br", "br"));
+        assert!(header_lists("mcp; v=1, other", "mcp"));
+        assert!(!header_lists("identity", "gzip"));
+    }
+}
@@ -5,29 +5,89 @@
 
 use async_trait::async_trait;
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    response::{sse::Event, Sse},
+    body::Body,
+    extract::{DefaultBodyLimit, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{sse::Event, IntoResponse, Response, Sse},
     routing::{get, post},
     Json, Router,
 };
 use reqwest::Client;
 use serde_json::Value;
-use std::{collections::HashMap, convert::Infallible, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 
-#[cfg(all(feature = "futures", feature = "tokio-stream"))]
-use futures::stream::Stream;
+use futures::stream::{BoxStream, Stream};
 
-#[cfg(feature = "tokio-stream")]
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 
 use tower::ServiceBuilder;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::decompression::RequestDecompressionLayer;
 
 use crate::core::error::{McpError, McpResult};
-use crate::protocol::types::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
-use crate::transport::traits::{ConnectionState, ServerTransport, Transport, TransportConfig};
+use crate::protocol::messages::methods;
+use crate::protocol::types::{
+    JsonRpcBatch, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, PARSE_ERROR,
+};
+use crate::transport::compression::{gzip, header_lists, MIN_COMPRESS_SIZE};
+#[cfg(feature = "tls")]
+use crate::transport::tls::TlsServerConfig;
+use crate::transport::traits::{
+    answer_server_request, batch_response_text, deliver_notification, deliver_response,
+    dispatch_batch, message_too_large_response, parse_client_response, recover_message_id,
+    request_client, session_notification_handler, session_request_handler, CancelOnDrop,
+    ClientRequestHandler, ClientRequests, ConnectionState, PendingResponses, RequestCallback,
+    ServerSender, ServerTransport, SessionClosedCallback, SessionHandlers,
+    SessionNotificationCallback, SessionRequestCallback, Transport, TransportConfig,
+    OVERSIZED_PREFIX_LEN,
+};
+
+/// Header carrying the ID of the session an HTTP request belongs to
+pub const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+// ============================================================================
+// Message Size Limits
+// ============================================================================
+
+/// Outcome of reading a size-limited message body
+enum BoundedBody {
+    /// The whole body, within the limit
+    Complete(Vec<u8>),
+    /// The body exceeded the limit; only its leading bytes were kept
+    TooLarge(Vec<u8>),
+}
+
+/// Collect a stream of body chunks, stopping as soon as `max_size` is exceeded
+///
+/// Reading stops at the limit so an oversized body is never held in memory.
+async fn read_bounded_body<S, B, E>(
+    mut chunks: S,
+    max_size: Option<usize>,
+) -> Result<BoundedBody, E>
+where
+    S: Stream<Item = Result<B, E>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let mut body = Vec::new();
+
+    while let Some(chunk) = chunks.next().await {
+        body.extend_from_slice(chunk?.as_ref());
+        if max_size.is_some_and(|max| body.len() > max) {
+            body.truncate(OVERSIZED_PREFIX_LEN);
+            return Ok(BoundedBody::TooLarge(body));
+        }
+    }
+
+    Ok(BoundedBody::Complete(body))
+}
 
 // ============================================================================
 // HTTP Client Transport
@@ -42,11 +102,16 @@ pub struct HttpClientTransport {
     base_url: String,
     sse_url: Option<String>,
     headers: HeaderMap,
-    pending_requests: Arc<Mutex<HashMap<Value, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
+    pending_requests: Arc<Mutex<PendingResponses>>,
+    notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
     notification_receiver: Option<mpsc::UnboundedReceiver<JsonRpcNotification>>,
+    request_handler: ClientRequestHandler,
+    events_task: Option<tokio::task::JoinHandle<()>>,
     config: TransportConfig,
     state: ConnectionState,
     request_id_counter: Arc<Mutex<u64>>,
+    server_accepts_gzip: bool,
+    session_id: Option<String>,
 }
 
 impl HttpClientTransport {
@@ -76,15 +141,25 @@ impl HttpClientTransport {
         sse_url: Option<S>,
         config: TransportConfig,
     ) -> McpResult<Self> {
-        let client_builder = Client::builder()
+        let mut client_builder = Client::builder()
             .timeout(Duration::from_millis(
                 config.read_timeout_ms.unwrap_or(60_000),
             ))
             .connect_timeout(Duration::from_millis(
                 config.connect_timeout_ms.unwrap_or(30_000),
-            ));
-
-        // Note: reqwest doesn't have a gzip() method, it's enabled by default with features
+            ))
+            // Advertise and decode compressed responses only when enabled
+            .gzip(config.compression)
+            .brotli(config.compression);
+        #[cfg(feature = "tls")]
+        {
+            client_builder = client_builder.use_rustls_tls();
+        }
+        for pem in &config.root_certificates {
+            let certificate = reqwest::Certificate::from_pem(pem)
+                .map_err(|e| McpError::Http(format!("Invalid root certificate: {}", e)))?;
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
 
         let client = client_builder
             .build()
@@ -106,120 +181,155 @@ impl HttpClientTransport {
 
         let (notification_sender, notification_receiver) = mpsc::unbounded_channel();
 
-        // Set up SSE connection for notifications if URL provided
-        if let Some(sse_url) = &sse_url {
-            let sse_url = sse_url.as_ref().to_string();
-            let client_clone = client.clone();
-            let headers_clone = headers.clone();
-
-            tokio::spawn(async move {
-                if let Err(e) = Self::handle_sse_stream(
-                    client_clone,
-                    sse_url,
-                    headers_clone,
-                    notification_sender,
-                )
-                .await
-                {
-                    tracing::error!("SSE stream error: {}", e);
-                }
-            });
-        }
-
-        Ok(Self {
+        let mut transport = Self {
             client,
             base_url: base_url.as_ref().to_string(),
             sse_url: sse_url.map(|s| s.as_ref().to_string()),
             headers,
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            notification_sender,
             notification_receiver: Some(notification_receiver),
+            request_handler: ClientRequestHandler::default(),
+            events_task: None,
             config,
             state: ConnectionState::Connected,
             request_id_counter: Arc::new(Mutex::new(0)),
-        })
+            server_accepts_gzip: false,
+            session_id: None,
+        };
+
+        // Set up SSE connection for notifications if URL provided
+        if let Err(e) = transport.open_event_stream().await {
+            tracing::error!("SSE stream error: {}", e);
+        }
+
+        Ok(transport)
     }
 
-    async fn handle_sse_stream(
-        client: Client,
-        sse_url: String,
-        headers: HeaderMap,
-        notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
-    ) -> McpResult<()> {
-        let mut request = client.get(&sse_url);
-        for (name, value) in headers.iter() {
-            // Convert axum headers to reqwest headers
-            let name_str = name.as_str();
-            let value_bytes = value.as_bytes();
-            request = request.header(name_str, value_bytes);
+    /// Open the SSE stream, replacing the current one, if the transport has an SSE URL
+    ///
+    /// The stream is opened within the client's session once it has one, so that
+    /// it also carries the notifications and requests the server sends to the session.
+    async fn open_event_stream(&mut self) -> McpResult<()> {
+        let Some(sse_url) = &self.sse_url else {
+            return Ok(());
+        };
+        if let Some(task) = self.events_task.take() {
+            task.abort();
         }
 
-        let response = request
+        let response = self
+            .with_headers(self.client.get(sse_url))
+            // Unlike other requests, the event stream stays open indefinitely
+            .timeout(Duration::MAX)
             .send()
             .await
             .map_err(|e| McpError::Http(format!("SSE connection failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(McpError::Http(format!(
+                "SSE connection failed: {}",
+                response.status()
+            )));
+        }
 
-        let mut stream = response.bytes_stream();
-
-        #[cfg(feature = "tokio-stream")]
-        {
-            while let Some(chunk) = stream.next().await {
-                match chunk {
-                    Ok(bytes) => {
-                        let text = String::from_utf8_lossy(&bytes);
-                        for line in text.lines() {
-                            if line.starts_with("data: ") {
-                                let data = &line[6..]; // Remove "data: " prefix
-                                if let Ok(notification) =
-                                    serde_json::from_str::<JsonRpcNotification>(data)
-                                {
-                                    if notification_sender.send(notification).is_err() {
-                                        tracing::debug!("Notification receiver dropped");
-                                        return Ok(());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("SSE stream error: {}", e);
-                        break;
-                    }
-                }
+        let events = EventStream {
+            client: self.client.clone(),
+            post_url: format!("{}/mcp", self.base_url),
+            headers: self.session_headers(),
+            notification_sender: self.notification_sender.clone(),
+            request_handler: self.request_handler.clone(),
+            config: self.config.clone(),
+        };
+        self.events_task = Some(tokio::spawn(async move {
+            if let Err(e) = events.run(response).await {
+                tracing::error!("SSE stream error: {}", e);
             }
-        }
+        }));
+        Ok(())
+    }
 
-        #[cfg(not(feature = "tokio-stream"))]
+    /// The configured headers plus the session ID, if the client has a session
+    fn session_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+        if let Some(session_id) = self
+            .session_id
+            .as_ref()
+            .and_then(|session_id| HeaderValue::from_str(session_id).ok())
         {
-            tracing::warn!("SSE streaming requires tokio-stream feature");
+            headers.insert(HeaderName::from_static(SESSION_ID_HEADER), session_id);
         }
-
-        Ok(())
+        headers
     }
 
-    async fn next_request_id(&self) -> u64 {
-        let mut counter = self.request_id_counter.lock().await;
-        *counter += 1;
-        *counter
+    /// Attach a JSON body, gzip-compressing it if the server accepts compressed requests
+    fn with_body(
+        &self,
+        request: reqwest::RequestBuilder,
+        body: Vec<u8>,
+    ) -> McpResult<reqwest::RequestBuilder> {
+        if self.config.compression && self.server_accepts_gzip && body.len() >= MIN_COMPRESS_SIZE {
+            return Ok(request
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(gzip(&body)?));
+        }
+        Ok(request.body(body))
     }
-}
 
-#[async_trait]
-impl Transport for HttpClientTransport {
-    async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
-        let url = format!("{}/mcp", self.base_url);
+    /// Get the ID of the session the server assigned to this client, if any
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
 
-        let mut http_request = self.client.post(&url);
+    /// Add the configured headers and the session ID to a request
+    fn with_headers(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         for (name, value) in self.headers.iter() {
-            let name_str = name.as_str();
-            let value_bytes = value.as_bytes();
-            http_request = http_request.header(name_str, value_bytes);
+            request = request.header(name.as_str(), value.as_bytes());
         }
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_ID_HEADER, session_id.as_str());
+        }
+        request
+    }
 
-        let response = http_request
-            .json(&request)
+    /// Remember the session ID the server assigned in response to `initialize`
+    ///
+    /// # Returns
+    /// True if the client joined a new session
+    fn note_session_id(&mut self, response: &reqwest::Response) -> bool {
+        let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return false;
+        };
+        if self.session_id.as_deref() == Some(session_id) {
+            return false;
+        }
+        self.session_id = Some(session_id.to_string());
+        true
+    }
+
+    /// POST a JSON-RPC message to the MCP endpoint and read the response body
+    ///
+    /// A response larger than `max_message_size` is reported as
+    /// [`BoundedBody::TooLarge`] without being read in full.
+    async fn post_message(&mut self, body: Vec<u8>) -> McpResult<BoundedBody> {
+        let url = format!("{}/mcp", self.base_url);
+        let http_request = self.with_headers(self.client.post(&url));
+
+        let response = self
+            .with_body(http_request, body)?
             .send()
             .await
             .map_err(|e| McpError::Http(format!("HTTP request failed: {}", e)))?;
+        self.note_accepted_encodings(&response);
+        if self.note_session_id(&response) {
+            // Listen on the new session before its requests can be answered
+            if let Err(e) = self.open_event_stream().await {
+                tracing::error!("SSE stream error: {}", e);
+            }
+        }
 
         if !response.status().is_success() {
             return Err(McpError::Http(format!(
@@ -229,29 +339,202 @@ impl Transport for HttpClientTransport {
             )));
         }
 
-        let json_response: JsonRpcResponse = response
-            .json()
+        let max_size = self.config.max_message_size;
+        if let (Some(max), Some(length)) = (max_size, response.content_length()) {
+            if length > max as u64 {
+                return Ok(BoundedBody::TooLarge(Vec::new()));
+            }
+        }
+
+        read_bounded_body(response.bytes_stream(), max_size)
             .await
-            .map_err(|e| McpError::Http(format!("Failed to parse response: {}", e)))?;
+            .map_err(|e| McpError::Http(format!("Failed to read response: {}", e)))
+    }
+
+    /// Remember whether the server advertised support for gzip-compressed requests
+    fn note_accepted_encodings(&mut self, response: &reqwest::Response) {
+        if let Some(value) = response.headers().get(reqwest::header::ACCEPT_ENCODING) {
+            self.server_accepts_gzip = value
+                .to_str()
+                .is_ok_and(|encodings| header_lists(encodings, "gzip"));
+        }
+    }
+
+    async fn next_request_id(&self) -> u64 {
+        let mut counter = self.request_id_counter.lock().await;
+        *counter += 1;
+        *counter
+    }
+}
+
+/// Task reading the SSE stream of an HTTP client transport
+struct EventStream {
+    client: Client,
+    post_url: String,
+    headers: HeaderMap,
+    notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
+    request_handler: ClientRequestHandler,
+    config: TransportConfig,
+}
+
+impl EventStream {
+    /// Dispatch the events of an open SSE response until the stream ends
+    async fn run(self, response: reqwest::Response) -> McpResult<()> {
+        let max_message_size = self.config.max_message_size;
+        let mut stream = response.bytes_stream();
+        // Events can be split across chunks, so only complete lines are parsed
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut discarding = false;
+
+        while let Some(chunk) = stream.next().await {
+            let bytes = chunk.map_err(|e| McpError::Http(format!("SSE stream error: {}", e)))?;
+            buffer.extend_from_slice(&bytes);
+
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if std::mem::take(&mut discarding) {
+                    continue;
+                }
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim_end().strip_prefix("data: ") else {
+                    continue;
+                };
+                if max_message_size.is_some_and(|max| data.len() > max) {
+                    tracing::warn!("Discarded SSE event exceeding the maximum message size");
+                    continue;
+                }
+                if !self.dispatch(data) {
+                    return Ok(());
+                }
+            }
+
+            // Drop the rest of an oversized line rather than buffering it
+            if max_message_size.is_some_and(|max| buffer.len() > max.saturating_add(16)) {
+                tracing::warn!("Discarded SSE event exceeding the maximum message size");
+                buffer.clear();
+                discarding = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Route one event to the notification channel or the request handler
+    ///
+    /// # Returns
+    /// False once the transport no longer receives notifications
+    fn dispatch(&self, data: &str) -> bool {
+        if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(data) {
+            tokio::spawn(self.answer(request));
+        } else if let Ok(notification) = serde_json::from_str::<JsonRpcNotification>(data) {
+            if self.notification_sender.send(notification).is_err() {
+                tracing::debug!("Notification receiver dropped");
+                return false;
+            }
+        }
+        true
+    }
 
-        Ok(json_response)
+    /// Answer a request from the server by POSTing the response to the MCP endpoint
+    fn answer(&self, request: JsonRpcRequest) -> impl std::future::Future<Output = ()> + Send {
+        let handler = self.request_handler.clone();
+        let client = self.client.clone();
+        let url = self.post_url.clone();
+        let headers = self.headers.clone();
+        let config = self.config.clone();
+        async move {
+            let result = match answer_server_request(&handler, request, &config).await {
+                Ok(body) => headers
+                    .iter()
+                    .fold(client.post(&url), |request, (name, value)| {
+                        request.header(name.as_str(), value.as_bytes())
+                    })
+                    .body(body)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map(|_| ())
+                    .map_err(|e| McpError::Http(format!("HTTP request failed: {}", e))),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to answer server request: {}", e);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpClientTransport {
+    async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        let request_body = serde_json::to_vec(&request).map_err(McpError::serialization)?;
+        self.config.check_message_size(request_body.len())?;
+
+        match self.post_message(request_body).await? {
+            BoundedBody::Complete(body) => serde_json::from_slice(&body)
+                .map_err(|e| McpError::Http(format!("Failed to parse response: {}", e))),
+            BoundedBody::TooLarge(_) => {
+                let max_size = self.config.max_message_size.unwrap_or_default();
+                tracing::warn!(
+                    "Response to request {} exceeds the maximum size of {} bytes",
+                    request.id,
+                    max_size
+                );
+                Ok(message_too_large_response(Some(request.id), max_size))
+            }
+        }
+    }
+
+    async fn send_batch(
+        &mut self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        let batch_body = serde_json::to_vec(&requests).map_err(McpError::serialization)?;
+        self.config.check_message_size(batch_body.len())?;
+
+        match self.post_message(batch_body).await? {
+            BoundedBody::Complete(body) => {
+                match serde_json::from_slice(&body)
+                    .map_err(|e| McpError::Http(format!("Failed to parse response: {}", e)))?
+                {
+                    JsonRpcBatch::Batch(responses) => Ok(responses),
+                    // The server rejected the batch as a whole
+                    JsonRpcBatch::Single(response) => match response.error {
+                        Some(error) => Err(McpError::from_rpc_error(error)),
+                        None => Err(McpError::protocol(
+                            "Server answered a batch with a single response",
+                        )),
+                    },
+                }
+            }
+            BoundedBody::TooLarge(_) => {
+                let max_size = self.config.max_message_size.unwrap_or_default();
+                tracing::warn!(
+                    "Response to batch exceeds the maximum size of {} bytes",
+                    max_size
+                );
+                Ok(requests
+                    .into_iter()
+                    .map(|request| message_too_large_response(Some(request.id), max_size))
+                    .collect())
+            }
+        }
     }
 
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
         let url = format!("{}/mcp/notify", self.base_url);
+        let http_request = self.with_headers(self.client.post(&url));
 
-        let mut http_request = self.client.post(&url);
-        for (name, value) in self.headers.iter() {
-            let name_str = name.as_str();
-            let value_bytes = value.as_bytes();
-            http_request = http_request.header(name_str, value_bytes);
-        }
+        let notification_body =
+            serde_json::to_vec(&notification).map_err(McpError::serialization)?;
+        self.config.check_message_size(notification_body.len())?;
 
-        let response = http_request
-            .json(&notification)
+        let response = self
+            .with_body(http_request, notification_body)?
             .send()
             .await
             .map_err(|e| McpError::Http(format!("HTTP notification failed: {}", e)))?;
+        self.note_accepted_encodings(&response);
 
         if !response.status().is_success() {
             return Err(McpError::Http(format!(
@@ -279,11 +562,31 @@ impl Transport for HttpClientTransport {
     }
 
     async fn close(&mut self) -> McpResult<()> {
+        // End the session so the server can release it right away
+        if self.session_id.is_some() {
+            let url = format!("{}/mcp", self.base_url);
+            if let Err(e) = self.with_headers(self.client.delete(&url)).send().await {
+                tracing::warn!("Failed to end HTTP session: {}", e);
+            }
+            self.session_id = None;
+        }
+
+        if let Some(task) = self.events_task.take() {
+            task.abort();
+        }
+
         self.state = ConnectionState::Disconnected;
         self.notification_receiver = None;
         Ok(())
     }
 
+    fn set_request_handler(&mut self, handler: RequestCallback) {
+        *self
+            .request_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(handler);
+    }
+
     fn is_connected(&self) -> bool {
         matches!(self.state, ConnectionState::Connected)
     }
@@ -300,15 +603,150 @@ impl Transport for HttpClientTransport {
 // HTTP Server Transport
 // ============================================================================
 
+/// Number of messages buffered for a session's event stream before it lags
+const SESSION_EVENT_CAPACITY: usize = 100;
+
+/// A session started by an `initialize` request
+#[derive(Clone)]
+struct HttpSession {
+    /// Messages for the event streams the session's client has open
+    events: broadcast::Sender<String>,
+    /// Requests sent to the client, waiting for it to POST their responses
+    requests: ClientRequests,
+    /// When the client was last seen using the session
+    last_seen: Arc<std::sync::Mutex<Instant>>,
+}
+
+impl HttpSession {
+    fn new() -> Self {
+        let (events, _) = broadcast::channel(SESSION_EVENT_CAPACITY);
+        Self {
+            events,
+            requests: ClientRequests::default(),
+            last_seen: Arc::new(std::sync::Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Record that the client is using the session
+    fn touch(&self) {
+        *self.last_seen.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Check whether the client has not used the session for `idle_timeout`
+    ///
+    /// A session with an open event stream is in use.
+    fn is_idle(&self, idle_timeout: Duration) -> bool {
+        if self.events.receiver_count() > 0 {
+            self.touch();
+            return false;
+        }
+        self.last_seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed()
+            >= idle_timeout
+    }
+
+    /// Fail the requests still waiting for the session's client
+    fn end(&self) {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
 /// Shared state for HTTP server transport
 #[derive(Clone)]
 struct HttpServerState {
     notification_sender: broadcast::Sender<JsonRpcNotification>,
-    request_handler: Option<
-        Arc<
-            dyn Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse> + Send + Sync,
-        >,
-    >,
+    max_message_size: Option<usize>,
+    keep_alive_ms: Option<u64>,
+    max_sessions: Option<usize>,
+    request_handler: Option<SessionRequestCallback>,
+    notification_handler: Option<SessionNotificationCallback>,
+    closed_handler: Option<SessionClosedCallback>,
+    sessions: HashMap<String, HttpSession>,
+}
+
+/// Sends messages to the clients of an HTTP server transport over Server-Sent Events
+struct HttpServerSender {
+    state: Arc<RwLock<HttpServerState>>,
+    config: TransportConfig,
+}
+
+impl HttpServerSender {
+    /// Look up a session
+    async fn session(&self, session_id: &str) -> McpResult<HttpSession> {
+        let session = self.state.read().await.sessions.get(session_id).cloned();
+        session
+            .ok_or_else(|| McpError::connection(format!("HTTP session {} not found", session_id)))
+    }
+}
+
+#[async_trait]
+impl ServerSender for HttpServerSender {
+    async fn send_notification(
+        &self,
+        session_id: Option<&str>,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        let notification_text =
+            serde_json::to_string(&notification).map_err(McpError::serialization)?;
+        self.config.check_message_size(notification_text.len())?;
+
+        if let Some(session_id) = session_id {
+            if self
+                .session(session_id)
+                .await?
+                .events
+                .send(notification_text)
+                .is_err()
+            {
+                tracing::warn!("HTTP session {} has no event stream open", session_id);
+            }
+            return Ok(());
+        }
+
+        if self
+            .state
+            .read()
+            .await
+            .notification_sender
+            .send(notification)
+            .is_err()
+        {
+            tracing::warn!("No SSE clients connected to receive notification");
+        }
+
+        Ok(())
+    }
+
+    async fn send_request(
+        &self,
+        session_id: Option<&str>,
+        request: JsonRpcRequest,
+    ) -> McpResult<JsonRpcResponse> {
+        let session_id = session_id.ok_or_else(|| {
+            McpError::protocol("Requests to HTTP clients need the session of the client")
+        })?;
+        let session = self.session(session_id).await?;
+
+        let request_text = serde_json::to_string(&request).map_err(McpError::serialization)?;
+        self.config.check_message_size(request_text.len())?;
+
+        // The request goes out on the session's event stream and the client POSTs the response
+        let wait = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
+        request_client(&session.requests, request, wait, |_| async {
+            session.events.send(request_text).map(|_| ()).map_err(|_| {
+                McpError::connection(format!(
+                    "HTTP session {} has no event stream open",
+                    session_id
+                ))
+            })
+        })
+        .await
+    }
 }
 
 /// HTTP transport for MCP servers
@@ -320,7 +758,10 @@ pub struct HttpServerTransport {
     config: TransportConfig,
     state: Arc<RwLock<HttpServerState>>,
     server_handle: Option<tokio::task::JoinHandle<()>>,
+    expiry_handle: Option<tokio::task::JoinHandle<()>>,
     running: Arc<RwLock<bool>>,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsServerConfig>,
 }
 
 impl HttpServerTransport {
@@ -348,16 +789,38 @@ impl HttpServerTransport {
 
         Self {
             bind_addr: bind_addr.into(),
-            config,
             state: Arc::new(RwLock::new(HttpServerState {
                 notification_sender,
+                max_message_size: config.max_message_size,
+                keep_alive_ms: config.keep_alive_ms,
+                max_sessions: config.max_sessions,
                 request_handler: None,
+                notification_handler: None,
+                closed_handler: None,
+                sessions: HashMap::new(),
             })),
+            config,
             server_handle: None,
+            expiry_handle: None,
             running: Arc::new(RwLock::new(false)),
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
+    /// Serve HTTPS with the given certificate instead of plain HTTP
+    ///
+    /// # Arguments
+    /// * `tls` - Certificate configuration; keep a clone to reload the certificate later
+    ///
+    /// # Returns
+    /// The transport, serving HTTPS once started
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsServerConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     /// Set the request handler function
     ///
     /// # Arguments
@@ -368,10 +831,75 @@ impl HttpServerTransport {
             + Send
             + Sync
             + 'static,
+    {
+        self.set_session_request_handler(move |_, request| handler(request))
+            .await;
+    }
+
+    /// Set a request handler function that is told which session a request belongs to
+    ///
+    /// An `initialize` request without an `Mcp-Session-Id` header starts a new
+    /// session, whose ID is returned in that header; requests without the header
+    /// belong to no session.
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming requests, given their session ID
+    pub async fn set_session_request_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(Option<String>, JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>
+            + Send
+            + Sync
+            + 'static,
     {
         let mut state = self.state.write().await;
         state.request_handler = Some(Arc::new(handler));
     }
+
+    /// Set the notification handler function
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming notifications
+    pub async fn set_notification_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(JsonRpcNotification) + Send + Sync + 'static,
+    {
+        self.set_session_notification_handler(move |_, notification| handler(notification))
+            .await;
+    }
+
+    /// Set a notification handler function that is told which session a notification belongs to
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming notifications, given their session ID
+    pub async fn set_session_notification_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(Option<String>, JsonRpcNotification) + Send + Sync + 'static,
+    {
+        let mut state = self.state.write().await;
+        state.notification_handler = Some(Arc::new(handler));
+    }
+
+    /// Set the function called with the ID of every session a client ends
+    ///
+    /// Clients end their session with a `DELETE` request to the MCP endpoint.
+    ///
+    /// # Arguments
+    /// * `handler` - Function that tears down an ended session
+    pub async fn set_session_closed_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        let mut state = self.state.write().await;
+        state.closed_handler = Some(Arc::new(handler));
+    }
+
+    /// Build a sender sharing the transport's event streams
+    fn http_sender(&self) -> HttpServerSender {
+        HttpServerSender {
+            state: self.state.clone(),
+            config: self.config.clone(),
+        }
+    }
 }
 
 #[async_trait]
@@ -384,22 +912,47 @@ impl ServerTransport for HttpServerTransport {
         let running = self.running.clone();
 
         // Create the Axum app
-        let app = Router::new()
-            .route("/mcp", post(handle_mcp_request))
+        let mut app = Router::new()
+            .route(
+                "/mcp",
+                post(handle_mcp_request).delete(handle_session_delete),
+            )
             .route("/mcp/notify", post(handle_mcp_notification))
             .route("/mcp/events", get(handle_sse_events))
             .route("/health", get(handle_health_check))
+            .layer(match self.config.max_message_size {
+                Some(max) => DefaultBodyLimit::max(max),
+                None => DefaultBodyLimit::disable(),
+            })
             .layer(
                 ServiceBuilder::new()
                     .layer(
                         CorsLayer::new()
-                            .allow_origin(Any)
+                            .allow_origin(allowed_origins(&self.config))
                             .allow_methods(Any)
-                            .allow_headers(Any),
+                            .allow_headers(Any)
+                            .expose_headers([HeaderName::from_static(SESSION_ID_HEADER)]),
                     )
                     .into_inner(),
-            )
-            .with_state(state);
+            );
+
+        if self.config.compression {
+            // SSE is compressed too; the encoder flushes whenever the event stream is idle
+            let predicate = SizeAbove::new(MIN_COMPRESS_SIZE as u16)
+                .and(NotForContentType::GRPC)
+                .and(NotForContentType::IMAGES);
+            app = app
+                .layer(RequestDecompressionLayer::new().gzip(true).br(true))
+                .layer(
+                    CompressionLayer::new()
+                        .gzip(true)
+                        .br(true)
+                        .compress_when(predicate),
+                )
+                .layer(axum::middleware::map_response(advertise_request_encodings));
+        }
+
+        let app = app.with_state(state);
 
         // Start the server
         let listener = tokio::net::TcpListener::bind(&bind_addr)
@@ -408,6 +961,34 @@ impl ServerTransport for HttpServerTransport {
 
         *running.write().await = true;
 
+        if let Some(idle_timeout) = self.config.session_idle_timeout_ms {
+            let idle_timeout = Duration::from_millis(idle_timeout);
+            let period =
+                (idle_timeout / 2).clamp(Duration::from_millis(10), Duration::from_secs(60));
+            let state = self.state.clone();
+            self.expiry_handle = Some(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    interval.tick().await;
+                    expire_idle_sessions(&state, idle_timeout).await;
+                }
+            }));
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some(tls) = self.tls.clone() {
+            let handshake_timeout =
+                Duration::from_millis(self.config.connect_timeout_ms.unwrap_or(30_000));
+            self.server_handle = Some(tokio::spawn(serve_tls(
+                listener,
+                app,
+                tls,
+                handshake_timeout,
+            )));
+            tracing::info!("HTTPS server started successfully on {}", self.bind_addr);
+            return Ok(());
+        }
+
         let server_handle = tokio::spawn(async move {
             if let Err(e) = axum::serve(listener, app).await {
                 tracing::error!("HTTP server error: {}", e);
@@ -424,7 +1005,7 @@ impl ServerTransport for HttpServerTransport {
         let state = self.state.read().await;
 
         if let Some(ref handler) = state.request_handler {
-            let response_rx = handler(request);
+            let response_rx = handler(None, request);
             drop(state); // Release the lock
 
             match response_rx.await {
@@ -434,7 +1015,7 @@ impl ServerTransport for HttpServerTransport {
         } else {
             Ok(JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
-                id: request.id,
+                id: Some(request.id),
                 result: None,
                 error: Some(crate::protocol::types::JsonRpcError {
                     code: crate::protocol::types::METHOD_NOT_FOUND,
@@ -446,13 +1027,20 @@ impl ServerTransport for HttpServerTransport {
     }
 
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
-        let state = self.state.read().await;
+        self.http_sender()
+            .send_notification(None, notification)
+            .await
+    }
 
-        if let Err(_) = state.notification_sender.send(notification) {
-            tracing::warn!("No SSE clients connected to receive notification");
-        }
+    async fn set_handlers(&mut self, handlers: SessionHandlers) {
+        let mut state = self.state.write().await;
+        state.request_handler = Some(handlers.request);
+        state.notification_handler = Some(handlers.notification);
+        state.closed_handler = Some(handlers.closed);
+    }
 
-        Ok(())
+    fn sender(&self) -> Option<Arc<dyn ServerSender>> {
+        Some(Arc::new(self.http_sender()))
     }
 
     async fn stop(&mut self) -> McpResult<()> {
@@ -463,6 +1051,9 @@ impl ServerTransport for HttpServerTransport {
         if let Some(handle) = self.server_handle.take() {
             handle.abort();
         }
+        if let Some(handle) = self.expiry_handle.take() {
+            handle.abort();
+        }
 
         Ok(())
     }
@@ -477,6 +1068,52 @@ impl ServerTransport for HttpServerTransport {
     }
 }
 
+/// Serve `app` over TLS, handshaking each connection with the current certificate
+#[cfg(feature = "tls")]
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls: TlsServerConfig,
+    handshake_timeout: Duration,
+) {
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use tower::Service;
+
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::error!("Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+
+        let app = app.clone();
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            let stream = match tls.accept(stream, handshake_timeout).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!("Dropping connection from {}: {}", addr, e);
+                    return;
+                }
+            };
+
+            let service = hyper::service::service_fn(
+                move |request: axum::http::Request<hyper::body::Incoming>| {
+                    app.clone().call(request)
+                },
+            );
+            if let Err(e) = hyper_util::server::conn::auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("HTTPS connection from {} failed: {}", addr, e);
+            }
+        });
+    }
+}
+
 // ============================================================================
 // HTTP Route Handlers
 // ============================================================================
@@ -484,22 +1121,192 @@ impl ServerTransport for HttpServerTransport {
 /// Handle MCP JSON-RPC requests
 async fn handle_mcp_request(
     State(state): State<Arc<RwLock<HttpServerState>>>,
-    Json(request): Json<JsonRpcRequest>,
-) -> Result<Json<JsonRpcResponse>, StatusCode> {
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let max_size = state.read().await.max_message_size;
+    let session_id = match request_session(&state, &headers).await {
+        Ok(session_id) => session_id,
+        Err(response) => return response,
+    };
+
+    let body = match read_bounded_body(body.into_data_stream(), max_size).await {
+        Ok(BoundedBody::Complete(body)) => body,
+        Ok(BoundedBody::TooLarge(prefix)) => {
+            let max_size = max_size.unwrap_or_default();
+            let id = recover_message_id(&prefix);
+            tracing::warn!(
+                "Request {:?} exceeds the maximum size of {} bytes",
+                id,
+                max_size
+            );
+            let response = message_too_large_response(id, max_size);
+            return (StatusCode::PAYLOAD_TOO_LARGE, Json(response)).into_response();
+        }
+        Err(e) => {
+            tracing::warn!("Failed to read request body: {}", e);
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    // Dispatch a batch of messages concurrently and answer them together
+    if body.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[') {
+        let entries: Vec<Value> = match serde_json::from_slice(&body) {
+            Ok(entries) => entries,
+            Err(e) => {
+                let response = JsonRpcResponse::error(None, PARSE_ERROR, e.to_string(), None);
+                return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+            }
+        };
+        let (handler, notify, session) = {
+            let state = state.read().await;
+            (
+                session_request_handler(state.request_handler.clone(), session_id.as_deref()),
+                session_notification_handler(
+                    state.notification_handler.clone(),
+                    session_id.as_deref(),
+                ),
+                session_id
+                    .as_ref()
+                    .and_then(|session_id| state.sessions.get(session_id).cloned()),
+            )
+        };
+        // Requests are cancelled if the client hangs up before the batch is answered
+        let pending = CancelOnDrop::new(notify.clone());
+        let dispatch = |request: JsonRpcRequest| match &handler {
+            Some(handler) => {
+                pending.track(request.id.clone());
+                handler(request)
+            }
+            None => {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::error(
+                    request.id,
+                    crate::protocol::types::METHOD_NOT_FOUND,
+                    "No request handler configured".to_string(),
+                    None,
+                ));
+                receiver
+            }
+        };
+
+        let notify = |notification| deliver_notification(notify.as_ref(), notification);
+        let respond = |response: JsonRpcResponse| match &session {
+            Some(session) => deliver_response(&session.requests, response),
+            None => tracing::warn!(
+                "Dropping response {:?} sent outside of a session",
+                response.id
+            ),
+        };
+        let batch = dispatch_batch(entries, dispatch, notify, respond).await;
+        pending.disarm();
+        return match batch {
+            Some(JsonRpcBatch::Single(response)) => json_response_within_limit(response, max_size),
+            Some(JsonRpcBatch::Batch(responses)) => {
+                match batch_response_text(responses, max_size) {
+                    Ok(body) => {
+                        ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to serialize batch response: {}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                }
+            }
+            // Nothing to answer when the batch held only notifications
+            None => StatusCode::ACCEPTED.into_response(),
+        };
+    }
+
+    let request: JsonRpcRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            if let Ok(notification) = serde_json::from_slice::<JsonRpcNotification>(&body) {
+                let handler = state.read().await.notification_handler.clone();
+                let handler = session_notification_handler(handler, session_id.as_deref());
+                deliver_notification(handler.as_ref(), notification);
+                return StatusCode::ACCEPTED.into_response();
+            }
+            // Responses to requests sent on the session's event stream
+            if let Some(response) = serde_json::from_slice::<Value>(&body)
+                .ok()
+                .and_then(|message| parse_client_response(&message))
+            {
+                let session = match &session_id {
+                    Some(session_id) => state.read().await.sessions.get(session_id).cloned(),
+                    None => None,
+                };
+                let Some(session) = session else {
+                    tracing::warn!(
+                        "Rejecting response {:?} sent outside of a session",
+                        response.id
+                    );
+                    return StatusCode::BAD_REQUEST.into_response();
+                };
+                deliver_response(&session.requests, response);
+                return StatusCode::ACCEPTED.into_response();
+            }
+            let response = JsonRpcResponse::error(None, PARSE_ERROR, e.to_string(), None);
+            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        }
+    };
+
+    // An initialize request outside of a session starts a new one
+    let mut new_session = None;
+    if session_id.is_none() && request.method == methods::INITIALIZE {
+        let mut state = state.write().await;
+        if let Some(max) = state
+            .max_sessions
+            .filter(|max| state.sessions.len() >= *max)
+        {
+            tracing::warn!("Refusing a new HTTP session: {} sessions are open", max);
+            let response = JsonRpcResponse::error(
+                request.id,
+                INTERNAL_ERROR,
+                "Too many open sessions".to_string(),
+                Some(serde_json::json!({ "maxSessions": max })),
+            );
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(response)).into_response();
+        }
+        let session_id = uuid::Uuid::new_v4().to_string();
+        tracing::debug!("Starting HTTP session {}", session_id);
+        state
+            .sessions
+            .insert(session_id.clone(), HttpSession::new());
+        new_session = Some(session_id);
+    }
+    let session_id = session_id.or_else(|| new_session.clone());
+
     let state_guard = state.read().await;
 
-    if let Some(ref handler) = state_guard.request_handler {
+    if let Some(handler) =
+        session_request_handler(state_guard.request_handler.clone(), session_id.as_deref())
+    {
+        // The request is cancelled if the client hangs up before it is answered
+        let pending = CancelOnDrop::new(session_notification_handler(
+            state_guard.notification_handler.clone(),
+            session_id.as_deref(),
+        ));
+        pending.track(request.id.clone());
         let response_rx = handler(request);
         drop(state_guard); // Release the lock
 
-        match response_rx.await {
-            Ok(response) => Ok(Json(response)),
-            Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        let response = response_rx.await;
+        pending.disarm();
+        let mut response = match response {
+            Ok(response) => json_response_within_limit(response, max_size),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        };
+        if let Some(session_id) = new_session.and_then(|id| HeaderValue::from_str(&id).ok()) {
+            response
+                .headers_mut()
+                .insert(HeaderName::from_static(SESSION_ID_HEADER), session_id);
         }
+        response
     } else {
         let error_response = JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
-            id: request.id,
+            id: Some(request.id),
             result: None,
             error: Some(crate::protocol::types::JsonRpcError {
                 code: crate::protocol::types::METHOD_NOT_FOUND,
@@ -507,23 +1314,179 @@ async fn handle_mcp_request(
                 data: None,
             }),
         };
-        Ok(Json(error_response))
+        Json(error_response).into_response()
+    }
+}
+
+/// Look up the session a request belongs to from its `Mcp-Session-Id` header
+///
+/// The session is marked as in use, postponing its expiry.
+///
+/// # Returns
+/// The session ID, `None` for a request outside of a session, or the response
+/// rejecting a request for a session that does not exist
+async fn request_session(
+    state: &RwLock<HttpServerState>,
+    headers: &HeaderMap,
+) -> Result<Option<String>, Response> {
+    let Some(value) = headers.get(SESSION_ID_HEADER) else {
+        return Ok(None);
+    };
+    if let Ok(session_id) = value.to_str() {
+        if let Some(session) = state.read().await.sessions.get(session_id) {
+            session.touch();
+            return Ok(Some(session_id.to_string()));
+        }
+    }
+    tracing::debug!("Request for unknown HTTP session {:?}", value);
+    Err(StatusCode::NOT_FOUND.into_response())
+}
+
+/// End the session named by the `Mcp-Session-Id` header
+async fn handle_session_delete(
+    State(state): State<Arc<RwLock<HttpServerState>>>,
+    headers: HeaderMap,
+) -> StatusCode {
+    let session_id = match request_session(&state, &headers).await {
+        Ok(Some(session_id)) => session_id,
+        Ok(None) => return StatusCode::BAD_REQUEST,
+        Err(_) => return StatusCode::NOT_FOUND,
+    };
+
+    let closed_handler = {
+        let mut state = state.write().await;
+        if let Some(session) = state.sessions.remove(&session_id) {
+            session.end();
+        }
+        state.closed_handler.clone()
+    };
+    tracing::debug!("Client ended HTTP session {}", session_id);
+    if let Some(handler) = closed_handler {
+        handler(session_id);
+    }
+
+    StatusCode::NO_CONTENT
+}
+
+/// End the sessions whose clients have not used them for `idle_timeout`
+async fn expire_idle_sessions(state: &RwLock<HttpServerState>, idle_timeout: Duration) {
+    let (expired, closed_handler) = {
+        let mut state = state.write().await;
+        let expired: Vec<String> = state
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.is_idle(idle_timeout))
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        for session_id in &expired {
+            if let Some(session) = state.sessions.remove(session_id) {
+                session.end();
+            }
+        }
+        (expired, state.closed_handler.clone())
+    };
+
+    for session_id in expired {
+        tracing::debug!("HTTP session {} expired", session_id);
+        if let Some(handler) = &closed_handler {
+            handler(session_id);
+        }
+    }
+}
+
+/// Origins allowed cross-origin access to the server
+fn allowed_origins(config: &TransportConfig) -> AllowOrigin {
+    match &config.allowed_origins {
+        Some(origins) => AllowOrigin::list(origins.iter().filter_map(|origin| {
+            match HeaderValue::from_str(origin) {
+                Ok(origin) => Some(origin),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid allowed origin {:?}", origin);
+                    None
+                }
+            }
+        })),
+        None => AllowOrigin::any(),
+    }
+}
+
+/// Tell clients which encodings they may use for request bodies
+async fn advertise_request_encodings(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(header::ACCEPT_ENCODING, "gzip, br".parse().unwrap());
+    response
+}
+
+/// Serialize a response, replacing it with an error if it exceeds the size limit
+fn json_response_within_limit(response: JsonRpcResponse, max_size: Option<usize>) -> Response {
+    let mut body = match serde_json::to_vec(&response) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to serialize response: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if max_size.is_some_and(|max| body.len() > max) {
+        tracing::warn!(
+            "Response to request {:?} exceeds the maximum message size",
+            response.id
+        );
+        let error_response = JsonRpcResponse::error(
+            response.id,
+            INTERNAL_ERROR,
+            "Response exceeds the maximum message size".to_string(),
+            None,
+        );
+        body = match serde_json::to_vec(&error_response) {
+            Ok(body) => body,
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        };
     }
+
+    ([(header::CONTENT_TYPE, "application/json")], body).into_response()
 }
 
 /// Handle MCP notification requests
-async fn handle_mcp_notification(Json(_notification): Json<JsonRpcNotification>) -> StatusCode {
+async fn handle_mcp_notification(
+    State(state): State<Arc<RwLock<HttpServerState>>>,
+    headers: HeaderMap,
+    Json(notification): Json<JsonRpcNotification>,
+) -> StatusCode {
+    let session_id = match request_session(&state, &headers).await {
+        Ok(session_id) => session_id,
+        Err(_) => return StatusCode::NOT_FOUND,
+    };
+    let handler = state.read().await.notification_handler.clone();
+    let handler = session_notification_handler(handler, session_id.as_deref());
+    deliver_notification(handler.as_ref(), notification);
+
     // Notifications don't require a response
     StatusCode::OK
 }
 
 /// Handle Server-Sent Events for real-time notifications
+///
+/// A request with an `Mcp-Session-Id` header also receives the notifications and
+/// requests the server sends to that session.
 #[cfg(all(feature = "tokio-stream", feature = "futures"))]
 async fn handle_sse_events(
     State(state): State<Arc<RwLock<HttpServerState>>>,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    headers: HeaderMap,
+) -> Response {
+    let session_id = match request_session(&state, &headers).await {
+        Ok(session_id) => session_id,
+        Err(response) => return response,
+    };
+
     let state_guard = state.read().await;
     let receiver = state_guard.notification_sender.subscribe();
+    let session_receiver = session_id
+        .as_ref()
+        .and_then(|session_id| state_guard.sessions.get(session_id))
+        .map(|session| session.events.subscribe());
+    let keep_alive_ms = state_guard.keep_alive_ms;
     drop(state_guard);
 
     let stream = BroadcastStream::new(receiver).map(|result| {
@@ -538,12 +1501,32 @@ async fn handle_sse_events(
             Err(_) => Ok(Event::default().data("{}")), // Lagged or closed
         }
     });
-
-    Sse::new(stream).keep_alive(
-        axum::response::sse::KeepAlive::new()
-            .interval(Duration::from_secs(30))
-            .text("keep-alive"),
-    )
+    let stream: BoxStream<'static, Result<Event, Infallible>> = match session_receiver {
+        Some(receiver) => {
+            let session_stream = BroadcastStream::new(receiver).filter_map(|result| match result {
+                Ok(message) => Some(Ok(Event::default().data(message))),
+                Err(e) => {
+                    tracing::warn!("Session event stream lagged: {}", e);
+                    None
+                }
+            });
+            Box::pin(stream.merge(session_stream))
+        }
+        None => Box::pin(stream),
+    };
+
+    // Keep-alive comments let the server notice clients that went away
+    let sse = Sse::new(stream);
+    match keep_alive_ms.filter(|ms| *ms > 0) {
+        Some(ms) => sse
+            .keep_alive(
+                axum::response::sse::KeepAlive::new()
+                    .interval(Duration::from_millis(ms))
+                    .text("keep-alive"),
+            )
+            .into_response(),
+        None => sse.into_response(),
+    }
 }
 
 /// Handle Server-Sent Events (fallback when features not available)
@@ -569,6 +1552,7 @@ async fn handle_health_check() -> Json<Value> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::types::RequestId;
     use serde_json::json;
 
     #[tokio::test]
@@ -598,6 +1582,473 @@ mod tests {
         assert!(transport.config.compression);
     }
 
+    #[tokio::test]
+    async fn test_read_bounded_body() {
+        let chunks = || {
+            futures::stream::iter(vec![
+                Ok::<_, Infallible>(b"{\"id\":7,".to_vec()),
+                Ok(b"\"method\":\"test\"}".to_vec()),
+            ])
+        };
+
+        match read_bounded_body(chunks(), Some(64)).await.unwrap() {
+            BoundedBody::Complete(body) => assert_eq!(body, br#"{"id":7,"method":"test"}"#),
+            BoundedBody::TooLarge(_) => panic!("Expected complete body"),
+        }
+
+        match read_bounded_body(chunks(), Some(10)).await.unwrap() {
+            BoundedBody::TooLarge(prefix) => {
+                assert_eq!(recover_message_id(&prefix), Some(RequestId::from(7)))
+            }
+            BoundedBody::Complete(_) => panic!("Expected oversized body"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_server_rejects_oversized_request() {
+        let config = TransportConfig {
+            max_message_size: Some(64),
+            ..Default::default()
+        };
+        let transport = HttpServerTransport::with_config("127.0.0.1:0", config);
+
+        let request = JsonRpcRequest::new(
+            RequestId::from(5),
+            "test".to_string(),
+            Some("x".repeat(128)),
+        );
+        let body = Body::from(serde_json::to_vec(&request.unwrap()).unwrap());
+
+        let response =
+            handle_mcp_request(State(transport.state.clone()), HeaderMap::new(), body).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let response: JsonRpcResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.id, Some(RequestId::from(5)));
+        assert_eq!(
+            response.error.unwrap().code,
+            crate::protocol::types::INVALID_REQUEST
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_compression_negotiation() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            compression: true,
+            ..Default::default()
+        };
+
+        let mut server =
+            HttpServerTransport::with_config(format!("127.0.0.1:{}", port), config.clone());
+        server
+            .set_request_handler(|request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, request.params).unwrap());
+                receiver
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let base_url = format!("http://127.0.0.1:{}", port);
+        let mut compressed = HttpClientTransport::with_config(base_url.as_str(), None, config)
+            .await
+            .unwrap();
+        let mut plain = HttpClientTransport::new(base_url.as_str(), None)
+            .await
+            .unwrap();
+
+        for client in [&mut compressed, &mut plain] {
+            // The second request is gzip-compressed once the server has advertised support
+            for id in 1..=2 {
+                let params = json!({ "data": "x".repeat(8192) });
+                let request = JsonRpcRequest::new(
+                    RequestId::from(id),
+                    "echo".to_string(),
+                    Some(params.clone()),
+                );
+                let response = client.send_request(request.unwrap()).await.unwrap();
+                assert_eq!(response.result, Some(params));
+            }
+        }
+        assert!(compressed.server_accepts_gzip);
+
+        // Responses are gzip-encoded for clients that accept it, and only for them
+        let raw = reqwest::Client::builder()
+            .no_gzip()
+            .no_brotli()
+            .build()
+            .unwrap();
+        let request = JsonRpcRequest::new(
+            RequestId::from(3),
+            "echo".to_string(),
+            Some(json!({ "data": "x".repeat(8192) })),
+        )
+        .unwrap();
+        let body = serde_json::to_vec(&request).unwrap();
+        let response = raw
+            .post(format!("{}/mcp", base_url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::CONTENT_ENCODING, "gzip")
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+            .body(gzip(&body).unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_ENCODING],
+            "gzip"
+        );
+        let encoded = response.bytes().await.unwrap();
+        assert!(encoded.len() < body.len() / 4);
+        let mut decoded = Vec::new();
+        std::io::Read::read_to_end(
+            &mut flate2::read::GzDecoder::new(&encoded[..]),
+            &mut decoded,
+        )
+        .unwrap();
+        let response: JsonRpcResponse = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(response.result, request.params);
+
+        let response = raw
+            .post(format!("{}/mcp", base_url))
+            .json(&request)
+            .send()
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .is_none());
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_compressed_sse_is_flushed() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            compression: true,
+            keep_alive_ms: None,
+            ..Default::default()
+        };
+        let mut server = HttpServerTransport::with_config(format!("127.0.0.1:{}", port), config);
+        server.start().await.unwrap();
+
+        let raw = reqwest::Client::builder()
+            .no_gzip()
+            .no_brotli()
+            .build()
+            .unwrap();
+        let response = raw
+            .get(format!("http://127.0.0.1:{}/mcp/events", port))
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_ENCODING],
+            "gzip"
+        );
+
+        // A single small event reaches the client without waiting for more output
+        let notification =
+            JsonRpcNotification::new("test/event".to_string(), None::<Value>).unwrap();
+        server.send_notification(notification).await.unwrap();
+
+        let mut chunks = response.bytes_stream();
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        let received = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(chunk) = chunks.next().await {
+                std::io::Write::write_all(&mut decoder, &chunk.unwrap()).unwrap();
+                if String::from_utf8_lossy(decoder.get_ref()).contains("test/event") {
+                    return true;
+                }
+            }
+            false
+        })
+        .await;
+        assert_eq!(received, Ok(true));
+
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_https() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_pem = cert.serialize_pem().unwrap();
+        let tls = TlsServerConfig::from_pem(
+            cert_pem.as_bytes(),
+            cert.serialize_private_key_pem().as_bytes(),
+        )
+        .unwrap();
+
+        let mut server = HttpServerTransport::new(format!("127.0.0.1:{}", port)).with_tls(tls);
+        server
+            .set_request_handler(|request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, request.method).unwrap());
+                receiver
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let base_url = format!("https://localhost:{}", port);
+        let request = || JsonRpcRequest::new(RequestId::from(1), "ping".to_string(), None::<Value>);
+
+        // The self-signed certificate is only trusted when configured as a root
+        let mut untrusting = HttpClientTransport::new(base_url.as_str(), None)
+            .await
+            .unwrap();
+        assert!(untrusting.send_request(request().unwrap()).await.is_err());
+
+        let config = TransportConfig {
+            root_certificates: vec![cert_pem.into_bytes()],
+            ..Default::default()
+        };
+        let mut client = HttpClientTransport::with_config(base_url.as_str(), None, config)
+            .await
+            .unwrap();
+        let response = client.send_request(request().unwrap()).await.unwrap();
+        assert_eq!(response.result, Some(json!("ping")));
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_batch() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let mut server = HttpServerTransport::new(format!("127.0.0.1:{}", port));
+        server
+            .set_request_handler(|request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, request.method).unwrap());
+                receiver
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let mut client = HttpClientTransport::new(format!("http://127.0.0.1:{}", port), None)
+            .await
+            .unwrap();
+        let requests = vec![
+            JsonRpcRequest::new(RequestId::from(1), "tools/list".to_string(), None::<Value>)
+                .unwrap(),
+            JsonRpcRequest::new(
+                RequestId::from(2),
+                "prompts/list".to_string(),
+                None::<Value>,
+            )
+            .unwrap(),
+        ];
+        let responses = client.send_batch(requests).await.unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(responses
+            .iter()
+            .any(|r| r.id == Some(RequestId::from(2)) && r.result == Some(json!("prompts/list"))));
+
+        let (notified_tx, mut notified_rx) = mpsc::unbounded_channel();
+        server
+            .set_notification_handler(move |notification| {
+                let _ = notified_tx.send(notification.method);
+            })
+            .await;
+
+        let notifications = json!([{"jsonrpc": "2.0", "method": "notifications/initialized"}]);
+        let body = Body::from(serde_json::to_vec(&notifications).unwrap());
+        let response =
+            handle_mcp_request(State(server.state.clone()), HeaderMap::new(), body).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let notification = json!({"jsonrpc": "2.0", "method": "notifications/cancelled",
+            "params": {"requestId": 1}});
+        let body = Body::from(serde_json::to_vec(&notification).unwrap());
+        let response =
+            handle_mcp_request(State(server.state.clone()), HeaderMap::new(), body).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        assert_eq!(
+            notified_rx.recv().await.unwrap(),
+            "notifications/initialized"
+        );
+        assert_eq!(notified_rx.recv().await.unwrap(), "notifications/cancelled");
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_sessions() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        // Requests are answered with the session they arrived on
+        let mut server = HttpServerTransport::new(format!("127.0.0.1:{}", port));
+        server
+            .set_session_request_handler(|session_id, request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, session_id).unwrap());
+                receiver
+            })
+            .await;
+        let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+        server
+            .set_session_closed_handler(move |session_id| {
+                let _ = closed_tx.send(session_id);
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let mut client = HttpClientTransport::new(url.clone(), None).await.unwrap();
+        let ping = |id: i64| {
+            JsonRpcRequest::new(RequestId::from(id), "ping".to_string(), None::<Value>).unwrap()
+        };
+
+        // Requests outside of a session carry no session ID
+        let response = client.send_request(ping(1)).await.unwrap();
+        assert!(response.error.is_none());
+        assert_eq!(response.result.unwrap_or_default(), Value::Null);
+        assert!(client.session_id().is_none());
+
+        let initialize = JsonRpcRequest::new(
+            RequestId::from(2),
+            methods::INITIALIZE.to_string(),
+            None::<Value>,
+        );
+        let response = client.send_request(initialize.unwrap()).await.unwrap();
+        let session_id = client.session_id().unwrap().to_string();
+        assert_eq!(response.result, Some(json!(session_id)));
+
+        let response = client.send_request(ping(3)).await.unwrap();
+        assert_eq!(response.result, Some(json!(session_id)));
+
+        // Unknown sessions are rejected
+        let mut headers = HeaderMap::new();
+        headers.insert(SESSION_ID_HEADER, HeaderValue::from_static("unknown"));
+        let body = Body::from(serde_json::to_vec(&ping(4)).unwrap());
+        let response = handle_mcp_request(State(server.state.clone()), headers, body).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        client.close().await.unwrap();
+        assert_eq!(closed_rx.recv().await.unwrap(), session_id);
+        assert!(server.state.read().await.sessions.is_empty());
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_session_expiry_and_limit() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            session_idle_timeout_ms: Some(200),
+            max_sessions: Some(1),
+            ..Default::default()
+        };
+        let mut server = HttpServerTransport::with_config(format!("127.0.0.1:{}", port), config);
+        server
+            .set_session_request_handler(|_session_id, request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, json!({})).unwrap());
+                receiver
+            })
+            .await;
+        let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+        server
+            .set_session_closed_handler(move |session_id| {
+                let _ = closed_tx.send(session_id);
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let url = format!("http://127.0.0.1:{}/mcp", port);
+        let client = reqwest::Client::new();
+        let initialize = || {
+            let request = JsonRpcRequest::new(
+                RequestId::from(1),
+                methods::INITIALIZE.to_string(),
+                None::<Value>,
+            )
+            .unwrap();
+            client.post(&url).json(&request).send()
+        };
+
+        let response = initialize().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let session_id = response.headers()[SESSION_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        // No session is started beyond the limit
+        let response = initialize().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert!(response.headers().get(SESSION_ID_HEADER).is_none());
+
+        // Requests keep the session alive
+        let ping =
+            JsonRpcRequest::new(RequestId::from(2), "ping".to_string(), None::<Value>).unwrap();
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let response = client
+                .post(&url)
+                .header(SESSION_ID_HEADER, &session_id)
+                .json(&ping)
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+        }
+
+        // An idle session expires and makes room for a new one
+        let closed = tokio::time::timeout(Duration::from_secs(5), closed_rx.recv())
+            .await
+            .unwrap();
+        assert_eq!(closed.unwrap(), session_id);
+        let response = client
+            .post(&url)
+            .header(SESSION_ID_HEADER, &session_id)
+            .json(&ping)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(
+            initialize().await.unwrap().status(),
+            reqwest::StatusCode::OK
+        );
+
+        server.stop().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_http_client_with_sse() {
         let transport = HttpClientTransport::new(
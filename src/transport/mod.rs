@@ -2,31 +2,41 @@
 //!
 //! This module provides concrete implementations of the transport traits
 //! for different communication protocols including STDIO, HTTP, and WebSocket.
+//! HTTP and WebSocket servers can terminate TLS when the `tls` feature is enabled.
 
 pub mod traits;
 
 #[cfg(feature = "stdio")]
 pub mod stdio;
 
+#[cfg(feature = "http")]
+mod compression;
+
 #[cfg(feature = "http")]
 pub mod http;
 
 #[cfg(feature = "websocket")]
 pub mod websocket;
 
+#[cfg(feature = "tls")]
+pub mod tls;
+
 // Re-export commonly used types
 pub use traits::{
     ConnectionState, EventEmittingTransport, FilterableTransport, ReconnectConfig,
-    ReconnectableTransport, ServerTransport, Transport, TransportConfig, TransportEvent,
-    TransportStats,
+    ReconnectableTransport, RequestCallback, ServerSender, ServerTransport, SessionHandlers,
+    Transport, TransportConfig, TransportEvent, TransportStats,
 };
 
 // Re-export transport implementations when features are enabled
 #[cfg(feature = "stdio")]
-pub use stdio::{StdioClientTransport, StdioServerTransport};
+pub use stdio::{StderrHandling, StdioClientTransport, StdioServerCommand, StdioServerTransport};
 
 #[cfg(feature = "http")]
 pub use http::{HttpClientTransport, HttpServerTransport};
 
 #[cfg(feature = "websocket")]
 pub use websocket::{WebSocketClientTransport, WebSocketServerTransport};
+
+#[cfg(feature = "tls")]
+pub use tls::TlsServerConfig;
@@ -6,28 +6,456 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::process::Stdio;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
-use tokio::process::{Child, Command};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter,
+};
+use tokio::process::{Child, ChildStderr, Command};
 use tokio::sync::{mpsc, Mutex};
-use tokio::time::{timeout, Duration};
+use tokio::time::{sleep, timeout, Duration};
 
 use crate::core::error::{McpError, McpResult};
-use crate::protocol::types::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
-use crate::transport::traits::{ConnectionState, ServerTransport, Transport, TransportConfig};
+use crate::protocol::types::{
+    JsonRpcBatch, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId, PARSE_ERROR,
+};
+use crate::transport::traits::{
+    answer_server_request, batch_response_text, deliver_notification, deliver_response,
+    dispatch_batch, message_too_large_response, parse_client_response, recover_message_id,
+    register_pending, request_client, response_text, ClientRequestHandler, ClientRequests,
+    ConnectionState, NotificationCallback, PendingResponses, ReconnectConfig,
+    ReconnectableTransport, RequestCallback, ServerSender, ServerTransport, SessionHandlers,
+    Transport, TransportConfig, OVERSIZED_PREFIX_LEN,
+};
+
+/// How long to wait for an exiting server process to be reaped
+const EXIT_STATUS_WAIT: Duration = Duration::from_millis(500);
+
+/// Outcome of reading one newline-delimited message
+#[derive(Debug, PartialEq)]
+enum LineRead {
+    /// The stream ended
+    Eof,
+    /// A complete line, without its terminator, is in the buffer
+    Line,
+    /// The line exceeded the size limit; only its leading bytes are in the buffer
+    TooLong,
+}
+
+/// Read one line into `buf`, never holding more than `max_size` bytes of it in memory
+///
+/// The remainder of an oversized line is consumed and discarded so the next
+/// read starts at the following message.
+async fn read_bounded_line<R>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    max_size: Option<usize>,
+) -> std::io::Result<LineRead>
+where
+    R: AsyncBufRead + Unpin,
+{
+    buf.clear();
+    let mut too_long = false;
+
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            if buf.is_empty() && !too_long {
+                return Ok(LineRead::Eof);
+            }
+            break;
+        }
+
+        let (chunk, complete) = match available.iter().position(|b| *b == b'\n') {
+            Some(pos) => (&available[..pos], true),
+            None => (available, false),
+        };
+        let consumed = chunk.len() + usize::from(complete);
+
+        if !too_long {
+            buf.extend_from_slice(chunk);
+            // Leave room for a trailing carriage return
+            if max_size.is_some_and(|max| buf.len() > max.saturating_add(1)) {
+                buf.truncate(OVERSIZED_PREFIX_LEN);
+                too_long = true;
+            }
+        }
+
+        reader.consume(consumed);
+        if complete {
+            break;
+        }
+    }
+
+    if too_long {
+        return Ok(LineRead::TooLong);
+    }
+    if buf.last() == Some(&b'\r') {
+        buf.pop();
+    }
+    if max_size.is_some_and(|max| buf.len() > max) {
+        buf.truncate(OVERSIZED_PREFIX_LEN);
+        return Ok(LineRead::TooLong);
+    }
+    Ok(LineRead::Line)
+}
+
+/// Callback invoked with each line the server process writes to stderr
+pub type StderrCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// What to do with the stderr stream of a spawned server process
+#[derive(Clone, Default)]
+pub enum StderrHandling {
+    /// Forward each line to `tracing` at the `info` level
+    #[default]
+    Tracing,
+    /// Let the server process write to this process' stderr
+    Inherit,
+    /// Discard everything the server process writes to stderr
+    Discard,
+    /// Pass each line to a user callback
+    Callback(StderrCallback),
+}
+
+impl std::fmt::Debug for StderrHandling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tracing => write!(f, "Tracing"),
+            Self::Inherit => write!(f, "Inherit"),
+            Self::Discard => write!(f, "Discard"),
+            Self::Callback(_) => write!(f, "Callback(<fn>)"),
+        }
+    }
+}
+
+/// Description of an MCP server process launched by [`StdioClientTransport`]
+///
+/// The command is kept by the transport so the process can be respawned
+/// with exactly the same settings.
+///
+/// # Example
+/// ```rust,no_run
+/// use mcp_protocol_sdk::transport::stdio::{StdioClientTransport, StdioServerCommand};
+///
+/// # async fn example() -> mcp_protocol_sdk::McpResult<()> {
+/// let command = StdioServerCommand::new("my-mcp-server")
+///     .arg("--verbose")
+///     .env("API_KEY", "secret")
+///     .current_dir("/srv/mcp");
+/// let transport = StdioClientTransport::from_command(command).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StdioServerCommand {
+    program: String,
+    args: Vec<String>,
+    env: HashMap<String, String>,
+    env_remove: Vec<String>,
+    inherit_env: bool,
+    current_dir: Option<PathBuf>,
+    stderr: StderrHandling,
+    shutdown_timeout: Duration,
+    restart: ReconnectConfig,
+}
+
+impl StdioServerCommand {
+    /// Create a command that runs `program` with no arguments
+    pub fn new<S: Into<String>>(program: S) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            env_remove: Vec::new(),
+            inherit_env: true,
+            current_dir: None,
+            stderr: StderrHandling::default(),
+            shutdown_timeout: Duration::from_secs(5),
+            restart: ReconnectConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Append an argument
+    pub fn arg<S: Into<String>>(mut self, arg: S) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append several arguments
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set an environment variable for the server process
+    pub fn env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        let key = key.into();
+        self.env_remove.retain(|removed| *removed != key);
+        self.env.insert(key, value.into());
+        self
+    }
+
+    /// Set several environment variables for the server process
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        for (key, value) in vars {
+            self = self.env(key, value);
+        }
+        self
+    }
+
+    /// Remove an inherited environment variable from the server process
+    pub fn env_remove<K: Into<String>>(mut self, key: K) -> Self {
+        let key = key.into();
+        self.env.remove(&key);
+        self.env_remove.push(key);
+        self
+    }
+
+    /// Whether the server process inherits this process' environment (default: true)
+    ///
+    /// When disabled only variables set with [`env`](Self::env) are visible to the server.
+    pub fn inherit_env(mut self, inherit: bool) -> Self {
+        self.inherit_env = inherit;
+        self
+    }
+
+    /// Set the working directory of the server process
+    pub fn current_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Choose how the server's stderr stream is handled
+    pub fn stderr(mut self, handling: StderrHandling) -> Self {
+        self.stderr = handling;
+        self
+    }
+
+    /// Pass each stderr line of the server process to `callback`
+    pub fn on_stderr<F>(self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.stderr(StderrHandling::Callback(Arc::new(callback)))
+    }
+
+    /// How long `close` waits at each shutdown stage before escalating
+    ///
+    /// The transport first closes stdin, then sends `SIGTERM` (on Unix), and
+    /// finally kills the process, waiting this long after each step.
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Respawn the server process when it exits, using `config` for backoff
+    ///
+    /// The check happens before each outgoing message, so requests in flight
+    /// when the process dies still fail. A respawned server starts
+    /// uninitialized; callers must run the `initialize` handshake again.
+    pub fn restart_on_exit(mut self, config: ReconnectConfig) -> Self {
+        self.restart = config;
+        self
+    }
+
+    /// Program that will be executed
+    pub fn get_program(&self) -> &str {
+        &self.program
+    }
+
+    /// Arguments passed to the program
+    pub fn get_args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Working directory of the server process, if overridden
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.current_dir.as_deref()
+    }
+
+    fn build(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(match self.stderr {
+                StderrHandling::Inherit => Stdio::inherit(),
+                StderrHandling::Discard => Stdio::null(),
+                StderrHandling::Tracing | StderrHandling::Callback(_) => Stdio::piped(),
+            })
+            .kill_on_drop(true);
+
+        if !self.inherit_env {
+            command.env_clear();
+        }
+        for key in &self.env_remove {
+            command.env_remove(key);
+        }
+        command.envs(&self.env);
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+
+        command
+    }
+}
+
+/// Pending requests keyed by request ID
+type PendingRequests = Arc<Mutex<PendingResponses>>;
+
+/// Stdin of the server process, shared by the transport and the task answering the server's requests
+type StdinWriter = Arc<Mutex<BufWriter<tokio::process::ChildStdin>>>;
+
+/// Task reading the messages a server process writes to its stdout
+struct MessageProcessor {
+    notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
+    pending_requests: PendingRequests,
+    request_handler: ClientRequestHandler,
+    stdin_writer: StdinWriter,
+    config: TransportConfig,
+}
+
+impl MessageProcessor {
+    /// Route the messages read from the server until its stdout closes
+    async fn run(self, mut reader: BufReader<tokio::process::ChildStdout>) {
+        let max_message_size = self.config.max_message_size;
+        let mut buf = Vec::new();
+
+        loop {
+            match read_bounded_line(&mut reader, &mut buf, max_message_size).await {
+                Ok(LineRead::Eof) => {
+                    tracing::debug!("STDIO reader reached EOF");
+                    break;
+                }
+                Ok(LineRead::TooLong) => {
+                    let max_size = max_message_size.unwrap_or_default();
+                    let Some(id) = recover_message_id(&buf) else {
+                        tracing::warn!(
+                            "Discarded message exceeding the maximum size of {} bytes",
+                            max_size
+                        );
+                        continue;
+                    };
+
+                    tracing::warn!(
+                        "Response to request {} exceeds the maximum size of {} bytes",
+                        id,
+                        max_size
+                    );
+                    let mut pending = self.pending_requests.lock().await;
+                    if let Some(sender) = pending.remove(&id) {
+                        let _ = sender.send(message_too_large_response(Some(id), max_size));
+                    }
+                }
+                Ok(LineRead::Line) => {
+                    let Ok(line) = std::str::from_utf8(&buf) else {
+                        tracing::warn!("Received message that is not valid UTF-8");
+                        continue;
+                    };
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    tracing::trace!("Received: {}", line);
+
+                    // Requests from the server parse as responses too, so check for them first
+                    if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(line) {
+                        self.answer(request);
+                    }
+                    // Try to parse as a response or a batch of responses
+                    else if let Ok(responses) =
+                        serde_json::from_str::<JsonRpcBatch<JsonRpcResponse>>(line)
+                    {
+                        let mut pending = self.pending_requests.lock().await;
+                        for response in responses.into_vec() {
+                            let sender = response.id.as_ref().and_then(|id| pending.remove(id));
+                            if let Some(sender) = sender {
+                                let _ = sender.send(response);
+                            } else {
+                                tracing::warn!(
+                                    "Received response for unknown request ID: {:?}",
+                                    response.id
+                                );
+                            }
+                        }
+                    }
+                    // Try to parse as notification
+                    else if let Ok(notification) =
+                        serde_json::from_str::<JsonRpcNotification>(line)
+                    {
+                        if self.notification_sender.send(notification).is_err() {
+                            tracing::debug!("Notification receiver dropped");
+                            break;
+                        }
+                    } else {
+                        tracing::warn!("Failed to parse message: {}", line);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Error reading from stdout: {}", e);
+                    break;
+                }
+            }
+        }
+
+        // Nothing else will arrive, so fail outstanding requests right away
+        self.pending_requests.lock().await.clear();
+    }
+
+    /// Answer a request from the server in the background
+    fn answer(&self, request: JsonRpcRequest) {
+        let handler = self.request_handler.clone();
+        let writer = self.stdin_writer.clone();
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let line = match answer_server_request(&handler, request, &config).await {
+                Ok(line) => line,
+                Err(e) => {
+                    tracing::error!("Failed to answer server request: {}", e);
+                    return;
+                }
+            };
+            tracing::trace!("Sending: {}", line);
+            if let Err(e) =
+                StdioClientTransport::write_stdin(&mut *writer.lock().await, &line).await
+            {
+                tracing::error!("Failed to write response to server request: {}", e);
+            }
+        });
+    }
+}
 
 /// STDIO transport for MCP clients
 ///
 /// This transport communicates with an MCP server via STDIO (standard input/output).
 /// It's typically used when the server is a separate process.
 pub struct StdioClientTransport {
+    command: StdioServerCommand,
     child: Option<Child>,
-    stdin_writer: Option<BufWriter<tokio::process::ChildStdin>>,
+    stdin_writer: Option<StdinWriter>,
     #[allow(dead_code)]
     stdout_reader: Option<BufReader<tokio::process::ChildStdout>>,
     notification_receiver: Option<mpsc::UnboundedReceiver<JsonRpcNotification>>,
-    pending_requests: Arc<Mutex<HashMap<Value, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
+    pending_requests: PendingRequests,
+    request_handler: ClientRequestHandler,
+    stdout_closed: Arc<AtomicBool>,
+    restarts: u32,
     config: TransportConfig,
     state: ConnectionState,
 }
@@ -59,18 +487,78 @@ impl StdioClientTransport {
         args: Vec<S>,
         config: TransportConfig,
     ) -> McpResult<Self> {
-        let command_str = command.as_ref();
-        let args_str: Vec<&str> = args.iter().map(|s| s.as_ref()).collect();
+        let command = StdioServerCommand::new(command.as_ref())
+            .args(args.iter().map(|arg| arg.as_ref().to_string()));
+        Self::from_command_with_config(command, config).await
+    }
 
-        tracing::debug!("Starting MCP server: {} {:?}", command_str, args_str);
+    /// Create a new STDIO client transport from a server command
+    ///
+    /// # Arguments
+    /// * `command` - Server process description
+    ///
+    /// # Returns
+    /// Result containing the transport or an error
+    pub async fn from_command(command: StdioServerCommand) -> McpResult<Self> {
+        Self::from_command_with_config(command, TransportConfig::default()).await
+    }
 
-        let mut child = Command::new(command_str)
-            .args(&args_str)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| McpError::transport(format!("Failed to start server process: {}", e)))?;
+    /// Create a new STDIO client transport from a server command with custom configuration
+    ///
+    /// # Arguments
+    /// * `command` - Server process description
+    /// * `config` - Transport configuration
+    ///
+    /// # Returns
+    /// Result containing the transport or an error
+    pub async fn from_command_with_config(
+        command: StdioServerCommand,
+        config: TransportConfig,
+    ) -> McpResult<Self> {
+        let mut transport = Self {
+            command,
+            child: None,
+            stdin_writer: None,
+            stdout_reader: None, // Moved to processor task
+            notification_receiver: None,
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            request_handler: ClientRequestHandler::default(),
+            stdout_closed: Arc::new(AtomicBool::new(false)),
+            restarts: 0,
+            config,
+            state: ConnectionState::Connecting,
+        };
+        transport.spawn()?;
+        Ok(transport)
+    }
+
+    /// Get the command used to launch the server process
+    pub fn command(&self) -> &StdioServerCommand {
+        &self.command
+    }
+
+    /// Get the OS process ID of the running server, if any
+    pub fn process_id(&self) -> Option<u32> {
+        self.child.as_ref().and_then(|child| child.id())
+    }
+
+    /// Get the number of times the server process has been respawned
+    pub fn restart_count(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Launch the server process and start the reader tasks
+    fn spawn(&mut self) -> McpResult<()> {
+        tracing::debug!(
+            "Starting MCP server: {} {:?}",
+            self.command.program,
+            self.command.args
+        );
+
+        let mut child =
+            self.command.build().spawn().map_err(|e| {
+                McpError::transport(format!("Failed to start server process: {}", e))
+            })?;
 
         let stdin = child
             .stdin
@@ -81,155 +569,306 @@ impl StdioClientTransport {
             .take()
             .ok_or_else(|| McpError::transport("Failed to get stdout handle"))?;
 
-        let stdin_writer = BufWriter::new(stdin);
-        let stdout_reader = BufReader::new(stdout);
+        if let Some(stderr) = child.stderr.take() {
+            let handling = self.command.stderr.clone();
+            let program = self.command.program.clone();
+            let max_message_size = self.config.max_message_size;
+            tokio::spawn(async move {
+                Self::stderr_processor(stderr, handling, program, max_message_size).await;
+            });
+        }
 
         let (notification_sender, notification_receiver) = mpsc::unbounded_channel();
-        let pending_requests = Arc::new(Mutex::new(HashMap::new()));
+        let pending_requests: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let stdout_closed = Arc::new(AtomicBool::new(false));
+
+        let stdin_writer: StdinWriter = Arc::new(Mutex::new(BufWriter::new(stdin)));
 
         // Start message processing task
-        let reader_pending_requests = pending_requests.clone();
-        let reader = stdout_reader;
+        let processor = MessageProcessor {
+            notification_sender,
+            pending_requests: pending_requests.clone(),
+            request_handler: self.request_handler.clone(),
+            stdin_writer: stdin_writer.clone(),
+            config: self.config.clone(),
+        };
+        let reader_closed = stdout_closed.clone();
+        let reader = BufReader::new(stdout);
         tokio::spawn(async move {
-            Self::message_processor(reader, notification_sender, reader_pending_requests).await;
+            processor.run(reader).await;
+            reader_closed.store(true, Ordering::SeqCst);
         });
 
-        Ok(Self {
-            child: Some(child),
-            stdin_writer: Some(stdin_writer),
-            stdout_reader: None, // Moved to processor task
-            notification_receiver: Some(notification_receiver),
-            pending_requests,
-            config,
-            state: ConnectionState::Connected,
-        })
+        self.child = Some(child);
+        self.stdin_writer = Some(stdin_writer);
+        self.notification_receiver = Some(notification_receiver);
+        self.pending_requests = pending_requests;
+        self.stdout_closed = stdout_closed;
+        self.state = ConnectionState::Connected;
+        Ok(())
     }
 
-    async fn message_processor(
-        mut reader: BufReader<tokio::process::ChildStdout>,
-        notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
-        pending_requests: Arc<Mutex<HashMap<Value, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
-    ) {
-        let mut line = String::new();
+    /// Respawn the server process, retrying with backoff per the restart configuration
+    async fn respawn(&mut self, config: &ReconnectConfig) -> McpResult<()> {
+        self.state = ConnectionState::Reconnecting;
+        self.child = None;
+        self.stdin_writer = None;
 
+        let mut attempt: u32 = 0;
         loop {
-            line.clear();
-            match reader.read_line(&mut line).await {
-                Ok(0) => {
-                    tracing::debug!("STDIO reader reached EOF");
-                    break;
+            attempt += 1;
+            match self.spawn() {
+                Ok(()) => {
+                    self.restarts += 1;
+                    tracing::info!(
+                        "Restarted MCP server {} (restart #{})",
+                        self.command.program,
+                        self.restarts
+                    );
+                    return Ok(());
                 }
-                Ok(_) => {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        continue;
+                Err(error) => {
+                    if config.max_attempts.is_some_and(|max| attempt >= max) {
+                        self.state = ConnectionState::Error(error.to_string());
+                        return Err(error);
                     }
+                    tracing::warn!("Restart attempt {} failed: {}", attempt, error);
+                }
+            }
 
-                    tracing::trace!("Received: {}", line);
+            let delay = Duration::from_millis(std::cmp::min(
+                (config.initial_delay_ms as f64
+                    * config.backoff_multiplier.powi(attempt as i32 - 1)) as u64,
+                config.max_delay_ms,
+            ));
+            sleep(delay).await;
+        }
+    }
 
-                    // Try to parse as response first
-                    if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(line) {
-                        let mut pending = pending_requests.lock().await;
-                        if let Some(sender) = pending.remove(&response.id) {
-                            let _ = sender.send(response);
-                        } else {
-                            tracing::warn!(
-                                "Received response for unknown request ID: {:?}",
-                                response.id
-                            );
-                        }
-                    }
-                    // Try to parse as notification
-                    else if let Ok(notification) =
-                        serde_json::from_str::<JsonRpcNotification>(line)
-                    {
-                        if notification_sender.send(notification).is_err() {
-                            tracing::debug!("Notification receiver dropped");
-                            break;
-                        }
-                    } else {
-                        tracing::warn!("Failed to parse message: {}", line);
+    /// Make sure the server process is still alive before writing to it
+    ///
+    /// An exited process is respawned when restarts are enabled, otherwise its
+    /// exit status is reported as a connection error.
+    async fn ensure_running(&mut self) -> McpResult<()> {
+        let status = match self.child.as_mut() {
+            Some(child) => child.try_wait().map_err(McpError::io)?,
+            None => return Err(McpError::transport("Transport not connected")),
+        };
+
+        let Some(status) = status else {
+            return Ok(());
+        };
+
+        tracing::warn!("MCP server {} exited: {}", self.command.program, status);
+        if self.command.restart.enabled {
+            let config = self.command.restart.clone();
+            self.respawn(&config).await
+        } else {
+            Err(self.exited(status))
+        }
+    }
+
+    /// Record that the server process exited and build the matching error
+    fn exited(&mut self, status: ExitStatus) -> McpError {
+        let message = format!("Server process exited ({})", status);
+        self.state = ConnectionState::Error(message.clone());
+        McpError::connection(message)
+    }
+
+    /// Build the error for a server that stopped talking, including its exit status if it has one
+    async fn disconnect_error(&mut self, fallback: McpError) -> McpError {
+        let Some(child) = self.child.as_mut() else {
+            return fallback;
+        };
+
+        match timeout(EXIT_STATUS_WAIT, child.wait()).await {
+            Ok(Ok(status)) => self.exited(status),
+            _ => fallback,
+        }
+    }
+
+    /// Write a single newline-delimited message to the server's stdin
+    async fn write_line(&mut self, line: &str) -> McpResult<()> {
+        let writer = self
+            .stdin_writer
+            .clone()
+            .ok_or_else(|| McpError::transport("Transport not connected"))?;
+
+        let result = Self::write_stdin(&mut *writer.lock().await, line).await;
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let error = McpError::transport(format!("Failed to write message: {}", e));
+                Err(self.disconnect_error(error).await)
+            }
+        }
+    }
+
+    async fn write_stdin(
+        writer: &mut BufWriter<tokio::process::ChildStdin>,
+        line: &str,
+    ) -> std::io::Result<()> {
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+        writer.flush().await
+    }
+
+    async fn stderr_processor(
+        stderr: ChildStderr,
+        handling: StderrHandling,
+        program: String,
+        max_line_size: Option<usize>,
+    ) {
+        let mut reader = BufReader::new(stderr);
+        let mut buf = Vec::new();
+
+        loop {
+            match read_bounded_line(&mut reader, &mut buf, max_line_size).await {
+                Ok(LineRead::Eof) => break,
+                Ok(_) => {
+                    // Oversized lines are passed on truncated
+                    let line = String::from_utf8_lossy(&buf);
+                    match &handling {
+                        StderrHandling::Callback(callback) => callback(&line),
+                        _ => tracing::info!(
+                            target: "mcp_protocol_sdk::transport::stdio::stderr",
+                            "[{}] {}",
+                            program,
+                            line
+                        ),
                     }
                 }
                 Err(e) => {
-                    tracing::error!("Error reading from stdout: {}", e);
+                    tracing::debug!("Error reading server stderr: {}", e);
                     break;
                 }
             }
         }
     }
+
+    /// Stop the server process: close stdin, then terminate, then kill
+    async fn shutdown_child(mut child: Child, grace: Duration) {
+        match timeout(grace, child.wait()).await {
+            Ok(Ok(status)) => {
+                tracing::debug!("Server process exited with status: {}", status);
+                return;
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Error waiting for server process: {}", e);
+                return;
+            }
+            Err(_) => {}
+        }
+
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            tracing::debug!("Server process did not exit, sending SIGTERM");
+            // SAFETY: `pid` belongs to a child we have not reaped yet, so it cannot be reused
+            unsafe {
+                libc::kill(pid as libc::pid_t, libc::SIGTERM);
+            }
+            if let Ok(Ok(status)) = timeout(grace, child.wait()).await {
+                tracing::debug!("Server process exited with status: {}", status);
+                return;
+            }
+        }
+
+        tracing::warn!("Timeout waiting for server process, killing it");
+        let _ = child.kill().await;
+    }
 }
 
 #[async_trait]
 impl Transport for StdioClientTransport {
     async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
-        let writer = self
-            .stdin_writer
-            .as_mut()
-            .ok_or_else(|| McpError::transport("Transport not connected"))?;
+        self.ensure_running().await?;
 
-        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let request_line = serde_json::to_string(&request).map_err(McpError::serialization)?;
+        self.config.check_message_size(request_line.len())?;
 
         // Store the pending request
-        {
-            let mut pending = self.pending_requests.lock().await;
-            pending.insert(request.id.clone(), sender);
-        }
+        let receiver =
+            register_pending(&mut *self.pending_requests.lock().await, [&request.id])?.remove(0);
 
         // Send the request
-        let request_line = serde_json::to_string(&request).map_err(McpError::serialization)?;
-
         tracing::trace!("Sending: {}", request_line);
 
-        writer
-            .write_all(request_line.as_bytes())
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to write request: {}", e)))?;
-        writer
-            .write_all(b"\n")
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to write newline: {}", e)))?;
-        writer
-            .flush()
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to flush: {}", e)))?;
+        if let Err(error) = self.write_line(&request_line).await {
+            self.pending_requests.lock().await.remove(&request.id);
+            return Err(error);
+        }
 
         // Wait for response with timeout
         let timeout_duration = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
 
-        let response = timeout(timeout_duration, receiver)
-            .await
-            .map_err(|_| McpError::timeout("Request timeout"))?
-            .map_err(|_| McpError::transport("Response channel closed"))?;
+        match timeout(timeout_duration, receiver).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(self
+                .disconnect_error(McpError::transport("Response channel closed"))
+                .await),
+            Err(_) => {
+                self.pending_requests.lock().await.remove(&request.id);
+                Err(McpError::timeout("Request timeout"))
+            }
+        }
+    }
+
+    async fn send_batch(
+        &mut self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        self.ensure_running().await?;
+
+        let batch_line = serde_json::to_string(&requests).map_err(McpError::serialization)?;
+        self.config.check_message_size(batch_line.len())?;
+
+        let ids: Vec<RequestId> = requests.iter().map(|request| request.id.clone()).collect();
+        let receivers = register_pending(&mut *self.pending_requests.lock().await, &ids)?;
+
+        tracing::trace!("Sending batch: {}", batch_line);
 
-        Ok(response)
+        let forget = |pending: &mut PendingResponses| {
+            for id in &ids {
+                pending.remove(id);
+            }
+        };
+        if let Err(error) = self.write_line(&batch_line).await {
+            forget(&mut *self.pending_requests.lock().await);
+            return Err(error);
+        }
+
+        // Wait for every response within a single timeout
+        let timeout_duration = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
+        let collect = async {
+            let mut responses = Vec::with_capacity(receivers.len());
+            for receiver in receivers {
+                responses.push(receiver.await?);
+            }
+            Ok::<_, tokio::sync::oneshot::error::RecvError>(responses)
+        };
+
+        match timeout(timeout_duration, collect).await {
+            Ok(Ok(responses)) => Ok(responses),
+            Ok(Err(_)) => Err(self
+                .disconnect_error(McpError::transport("Response channel closed"))
+                .await),
+            Err(_) => {
+                forget(&mut *self.pending_requests.lock().await);
+                Err(McpError::timeout("Request timeout"))
+            }
+        }
     }
 
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
-        let writer = self
-            .stdin_writer
-            .as_mut()
-            .ok_or_else(|| McpError::transport("Transport not connected"))?;
+        self.ensure_running().await?;
 
         let notification_line =
             serde_json::to_string(&notification).map_err(McpError::serialization)?;
+        self.config.check_message_size(notification_line.len())?;
 
         tracing::trace!("Sending notification: {}", notification_line);
 
-        writer
-            .write_all(notification_line.as_bytes())
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to write notification: {}", e)))?;
-        writer
-            .write_all(b"\n")
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to write newline: {}", e)))?;
-        writer
-            .flush()
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to flush: {}", e)))?;
-
-        Ok(())
+        self.write_line(&notification_line).await
     }
 
     async fn receive_notification(&mut self) -> McpResult<Option<JsonRpcNotification>> {
@@ -252,54 +891,120 @@ impl Transport for StdioClientTransport {
         self.state = ConnectionState::Closing;
 
         // Close stdin to signal the server to shut down
-        if let Some(mut writer) = self.stdin_writer.take() {
-            let _ = writer.shutdown().await;
+        if let Some(writer) = self.stdin_writer.take() {
+            let _ = writer.lock().await.shutdown().await;
         }
 
-        // Wait for the child process to exit
-        if let Some(mut child) = self.child.take() {
-            match timeout(Duration::from_secs(5), child.wait()).await {
-                Ok(Ok(status)) => {
-                    tracing::debug!("Server process exited with status: {}", status);
-                }
-                Ok(Err(e)) => {
-                    tracing::warn!("Error waiting for server process: {}", e);
-                }
-                Err(_) => {
-                    tracing::warn!("Timeout waiting for server process, killing it");
-                    let _ = child.kill().await;
-                }
-            }
+        // Wait for the child process to exit, escalating if it does not
+        if let Some(child) = self.child.take() {
+            Self::shutdown_child(child, self.command.shutdown_timeout).await;
         }
 
         self.state = ConnectionState::Disconnected;
         Ok(())
     }
 
+    fn set_request_handler(&mut self, handler: RequestCallback) {
+        *self
+            .request_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(handler);
+    }
+
     fn is_connected(&self) -> bool {
         matches!(self.state, ConnectionState::Connected)
+            && !self.stdout_closed.load(Ordering::SeqCst)
     }
 
     fn connection_info(&self) -> String {
-        format!("STDIO transport (state: {:?})", self.state)
+        match self.process_id() {
+            Some(pid) => format!(
+                "STDIO transport ({} pid {}, state: {:?})",
+                self.command.program, pid, self.state
+            ),
+            None => format!("STDIO transport (state: {:?})", self.state),
+        }
+    }
+}
+
+#[async_trait]
+impl ReconnectableTransport for StdioClientTransport {
+    async fn reconnect(&mut self) -> McpResult<()> {
+        if let Some(writer) = self.stdin_writer.take() {
+            let _ = writer.lock().await.shutdown().await;
+        }
+        if let Some(child) = self.child.take() {
+            Self::shutdown_child(child, self.command.shutdown_timeout).await;
+        }
+
+        let config = self.command.restart.clone();
+        self.respawn(&config).await
+    }
+
+    fn set_reconnect_config(&mut self, config: ReconnectConfig) {
+        self.command.restart = config;
+    }
+
+    fn connection_state(&self) -> ConnectionState {
+        self.state.clone()
     }
 }
 
+/// Stream a STDIO server transport writes its messages to
+type OutputWriter = BufWriter<Box<dyn AsyncWrite + Send + Unpin>>;
+
+/// Output of a STDIO server transport, shared by its read loop and its sender
+type SharedWriter = Arc<Mutex<OutputWriter>>;
+
 /// STDIO transport for MCP servers
 ///
 /// This transport communicates with an MCP client via STDIO (standard input/output).
 /// It reads requests from stdin and writes responses to stdout.
 pub struct StdioServerTransport {
-    stdin_reader: Option<BufReader<tokio::io::Stdin>>,
-    stdout_writer: Option<BufWriter<tokio::io::Stdout>>,
-    #[allow(dead_code)]
+    stdin_reader: Option<BufReader<Box<dyn AsyncRead + Send + Sync + Unpin>>>,
+    stdout_writer: SharedWriter,
+    client_requests: ClientRequests,
     config: TransportConfig,
     running: bool,
-    request_handler: Option<
-        Box<
-            dyn Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse> + Send + Sync,
-        >,
-    >,
+    request_handler: Option<RequestCallback>,
+    notification_handler: Option<NotificationCallback>,
+}
+
+/// Sends messages to the client of a STDIO server transport while it is serving
+struct StdioServerSender {
+    writer: SharedWriter,
+    requests: ClientRequests,
+    config: TransportConfig,
+}
+
+#[async_trait]
+impl ServerSender for StdioServerSender {
+    async fn send_notification(
+        &self,
+        _session_id: Option<&str>,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        let notification_line =
+            serde_json::to_string(&notification).map_err(McpError::serialization)?;
+        self.config.check_message_size(notification_line.len())?;
+
+        StdioServerTransport::write_line(&mut *self.writer.lock().await, &notification_line).await
+    }
+
+    async fn send_request(
+        &self,
+        _session_id: Option<&str>,
+        request: JsonRpcRequest,
+    ) -> McpResult<JsonRpcResponse> {
+        let request_line = serde_json::to_string(&request).map_err(McpError::serialization)?;
+        self.config.check_message_size(request_line.len())?;
+
+        let wait = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
+        request_client(&self.requests, request, wait, |_| async {
+            StdioServerTransport::write_line(&mut *self.writer.lock().await, &request_line).await
+        })
+        .await
+    }
 }
 
 impl StdioServerTransport {
@@ -319,15 +1024,34 @@ impl StdioServerTransport {
     /// # Returns
     /// New STDIO server transport instance
     pub fn with_config(config: TransportConfig) -> Self {
-        let stdin_reader = BufReader::new(tokio::io::stdin());
-        let stdout_writer = BufWriter::new(tokio::io::stdout());
+        Self::with_streams(tokio::io::stdin(), tokio::io::stdout(), config)
+    }
+
+    /// Create a STDIO server transport that talks over the given streams instead
+    ///
+    /// # Arguments
+    /// * `reader` - Stream the client's messages are read from
+    /// * `writer` - Stream the server's messages are written to
+    /// * `config` - Transport configuration
+    ///
+    /// # Returns
+    /// New STDIO server transport instance
+    pub fn with_streams<R, W>(reader: R, writer: W, config: TransportConfig) -> Self
+    where
+        R: AsyncRead + Send + Sync + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let reader: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(reader);
+        let writer: Box<dyn AsyncWrite + Send + Unpin> = Box::new(writer);
 
         Self {
-            stdin_reader: Some(stdin_reader),
-            stdout_writer: Some(stdout_writer),
+            stdin_reader: Some(BufReader::new(reader)),
+            stdout_writer: Arc::new(Mutex::new(BufWriter::new(writer))),
+            client_requests: ClientRequests::default(),
             config,
             running: false,
             request_handler: None,
+            notification_handler: None,
         }
     }
 
@@ -342,7 +1066,93 @@ impl StdioServerTransport {
             + Sync
             + 'static,
     {
-        self.request_handler = Some(Box::new(handler));
+        self.request_handler = Some(Arc::new(handler));
+    }
+
+    /// Set the notification handler function
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming notifications
+    pub fn set_notification_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(JsonRpcNotification) + Send + Sync + 'static,
+    {
+        self.notification_handler = Some(Arc::new(handler));
+    }
+
+    /// Pass a request to the request handler, answering it directly if there is none
+    fn dispatch(
+        handler: Option<&RequestCallback>,
+        request: JsonRpcRequest,
+    ) -> tokio::sync::oneshot::Receiver<JsonRpcResponse> {
+        match handler {
+            Some(handler) => handler(request),
+            None => {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::error(
+                    request.id,
+                    crate::protocol::types::METHOD_NOT_FOUND,
+                    format!("Method '{}' not found", request.method),
+                    None,
+                ));
+                receiver
+            }
+        }
+    }
+
+    /// Write a response, replacing it with an error if it exceeds the size limit
+    async fn write_response(
+        writer: &mut OutputWriter,
+        config: &TransportConfig,
+        response: JsonRpcResponse,
+    ) -> McpResult<()> {
+        Self::write_line(writer, &response_text(response, config)?).await
+    }
+
+    /// Write the response to a batch, if it has one
+    async fn write_batch_response(
+        writer: &mut OutputWriter,
+        config: &TransportConfig,
+        batch: Option<JsonRpcBatch<JsonRpcResponse>>,
+    ) -> McpResult<()> {
+        match batch {
+            Some(JsonRpcBatch::Single(response)) => {
+                Self::write_response(writer, config, response).await
+            }
+            Some(JsonRpcBatch::Batch(responses)) => {
+                let line = batch_response_text(responses, config.max_message_size)?;
+                Self::write_line(writer, &line).await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Build a sender sharing the transport's output
+    fn stdio_sender(&self) -> StdioServerSender {
+        StdioServerSender {
+            writer: self.stdout_writer.clone(),
+            requests: self.client_requests.clone(),
+            config: self.config.clone(),
+        }
+    }
+
+    async fn write_line(writer: &mut OutputWriter, line: &str) -> McpResult<()> {
+        tracing::trace!("Sending: {}", line);
+
+        writer
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| McpError::transport(format!("Failed to write response: {}", e)))?;
+        writer
+            .write_all(b"\n")
+            .await
+            .map_err(|e| McpError::transport(format!("Failed to write newline: {}", e)))?;
+        writer
+            .flush()
+            .await
+            .map_err(|e| McpError::transport(format!("Failed to flush: {}", e)))?;
+
+        Ok(())
     }
 }
 
@@ -355,23 +1165,45 @@ impl ServerTransport for StdioServerTransport {
             .stdin_reader
             .take()
             .ok_or_else(|| McpError::transport("STDIN reader already taken"))?;
-        let mut writer = self
-            .stdout_writer
-            .take()
-            .ok_or_else(|| McpError::transport("STDOUT writer already taken"))?;
+        // Responses are written in the background so that later messages, such as the
+        // cancellation of a request, are read while the request is handled
+        let writer = self.stdout_writer.clone();
 
         self.running = true;
 
-        let mut line = String::new();
-        while self.running {
-            line.clear();
-
-            match reader.read_line(&mut line).await {
-                Ok(0) => {
+        let mut buf = Vec::new();
+        loop {
+            match read_bounded_line(&mut reader, &mut buf, self.config.max_message_size).await {
+                Ok(LineRead::Eof) => {
                     tracing::debug!("STDIN closed, stopping server");
+                    // Requests to the client can no longer be answered
+                    self.client_requests
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .clear();
                     break;
                 }
-                Ok(_) => {
+                Ok(LineRead::TooLong) => {
+                    let max_size = self.config.max_message_size.unwrap_or_default();
+                    match recover_message_id(&buf) {
+                        Some(id) => {
+                            tracing::warn!(
+                                "Request {} exceeds the maximum size of {} bytes",
+                                id,
+                                max_size
+                            );
+                            let response = message_too_large_response(Some(id), max_size);
+                            Self::write_response(&mut *writer.lock().await, &self.config, response)
+                                .await?;
+                        }
+                        None => tracing::warn!(
+                            "Discarded message exceeding the maximum size of {} bytes",
+                            max_size
+                        ),
+                    }
+                }
+                Ok(LineRead::Line) => {
+                    let line = String::from_utf8_lossy(&buf);
                     let line = line.trim();
                     if line.is_empty() {
                         continue;
@@ -379,33 +1211,84 @@ impl ServerTransport for StdioServerTransport {
 
                     tracing::trace!("Received: {}", line);
 
+                    // Dispatch a batch of messages concurrently and answer them together
+                    if line.starts_with('[') {
+                        match serde_json::from_str::<Vec<Value>>(line) {
+                            Ok(entries) => {
+                                let handler = self.request_handler.clone();
+                                let notify = self.notification_handler.clone();
+                                let requests = self.client_requests.clone();
+                                let writer = writer.clone();
+                                let config = self.config.clone();
+                                tokio::spawn(async move {
+                                    let batch = dispatch_batch(
+                                        entries,
+                                        |request| Self::dispatch(handler.as_ref(), request),
+                                        |notification| {
+                                            deliver_notification(notify.as_ref(), notification)
+                                        },
+                                        |response| deliver_response(&requests, response),
+                                    )
+                                    .await;
+                                    let mut writer = writer.lock().await;
+                                    if let Err(e) =
+                                        Self::write_batch_response(&mut writer, &config, batch)
+                                            .await
+                                    {
+                                        tracing::error!("Failed to write batch response: {}", e);
+                                    }
+                                });
+                            }
+                            Err(e) => {
+                                tracing::warn!("Failed to parse batch: {} - Error: {}", line, e);
+                                let response =
+                                    JsonRpcResponse::error(None, PARSE_ERROR, e.to_string(), None);
+                                Self::write_response(
+                                    &mut *writer.lock().await,
+                                    &self.config,
+                                    response,
+                                )
+                                .await?;
+                            }
+                        }
+                        continue;
+                    }
+
                     // Parse the request
                     match serde_json::from_str::<JsonRpcRequest>(line) {
                         Ok(request) => {
-                            let response = self.handle_request(request).await?;
-
-                            let response_line = serde_json::to_string(&response)
-                                .map_err(McpError::serialization)?;
-
-                            tracing::trace!("Sending: {}", response_line);
-
-                            writer
-                                .write_all(response_line.as_bytes())
-                                .await
-                                .map_err(|e| {
-                                    McpError::transport(format!("Failed to write response: {}", e))
-                                })?;
-                            writer.write_all(b"\n").await.map_err(|e| {
-                                McpError::transport(format!("Failed to write newline: {}", e))
-                            })?;
-                            writer.flush().await.map_err(|e| {
-                                McpError::transport(format!("Failed to flush: {}", e))
-                            })?;
+                            let response = Self::dispatch(self.request_handler.as_ref(), request);
+                            let writer = writer.clone();
+                            let config = self.config.clone();
+                            tokio::spawn(async move {
+                                let Ok(response) = response.await else {
+                                    tracing::error!("Request handler channel closed");
+                                    return;
+                                };
+                                let mut writer = writer.lock().await;
+                                if let Err(e) =
+                                    Self::write_response(&mut writer, &config, response).await
+                                {
+                                    tracing::error!("Failed to write response: {}", e);
+                                }
+                            });
                         }
                         Err(e) => {
-                            tracing::warn!("Failed to parse request: {} - Error: {}", line, e);
-                            // Send parse error response if we can extract an ID
-                            // For now, just continue
+                            if let Ok(notification) =
+                                serde_json::from_str::<JsonRpcNotification>(line)
+                            {
+                                deliver_notification(
+                                    self.notification_handler.as_ref(),
+                                    notification,
+                                );
+                            } else if let Some(response) = serde_json::from_str::<Value>(line)
+                                .ok()
+                                .and_then(|message| parse_client_response(&message))
+                            {
+                                deliver_response(&self.client_requests, response);
+                            } else {
+                                tracing::warn!("Failed to parse request: {} - Error: {}", line, e);
+                            }
                         }
                     }
                 }
@@ -420,44 +1303,27 @@ impl ServerTransport for StdioServerTransport {
     }
 
     async fn handle_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
-        // Default implementation - return method not found
-        Ok(JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: request.id,
-            result: None,
-            error: Some(crate::protocol::types::JsonRpcError {
-                code: crate::protocol::types::METHOD_NOT_FOUND,
-                message: format!("Method '{}' not found", request.method),
-                data: None,
-            }),
-        })
+        Self::dispatch(self.request_handler.as_ref(), request)
+            .await
+            .map_err(|_| McpError::transport("Request handler channel closed"))
     }
 
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
-        let writer = self
-            .stdout_writer
-            .as_mut()
-            .ok_or_else(|| McpError::transport("STDOUT writer not available"))?;
-
-        let notification_line =
-            serde_json::to_string(&notification).map_err(McpError::serialization)?;
-
-        tracing::trace!("Sending notification: {}", notification_line);
-
-        writer
-            .write_all(notification_line.as_bytes())
+        self.stdio_sender()
+            .send_notification(None, notification)
             .await
-            .map_err(|e| McpError::transport(format!("Failed to write notification: {}", e)))?;
-        writer
-            .write_all(b"\n")
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to write newline: {}", e)))?;
-        writer
-            .flush()
-            .await
-            .map_err(|e| McpError::transport(format!("Failed to flush: {}", e)))?;
+    }
 
-        Ok(())
+    async fn set_handlers(&mut self, handlers: SessionHandlers) {
+        // The single client of the transport has no session ID
+        let request = handlers.request;
+        self.request_handler = Some(Arc::new(move |req| request(None, req)));
+        let notification = handlers.notification;
+        self.notification_handler = Some(Arc::new(move |n| notification(None, n)));
+    }
+
+    fn sender(&self) -> Option<Arc<dyn ServerSender>> {
+        Some(Arc::new(self.stdio_sender()))
     }
 
     async fn stop(&mut self) -> McpResult<()> {
@@ -500,7 +1366,7 @@ mod tests {
         let transport = StdioServerTransport::new();
         assert!(!transport.is_running());
         assert!(transport.stdin_reader.is_some());
-        assert!(transport.stdout_writer.is_some());
+        assert!(transport.sender().is_some());
     }
 
     #[test]
@@ -520,14 +1386,14 @@ mod tests {
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
-            id: json!(1),
+            id: RequestId::from(1),
             method: "unknown_method".to_string(),
             params: None,
         };
 
         let response = transport.handle_request(request).await.unwrap();
         assert_eq!(response.jsonrpc, "2.0");
-        assert_eq!(response.id, json!(1));
+        assert_eq!(response.id, Some(RequestId::from(1)));
         assert!(response.error.is_some());
         assert!(response.result.is_none());
 
@@ -535,5 +1401,276 @@ mod tests {
         assert_eq!(error.code, crate::protocol::types::METHOD_NOT_FOUND);
     }
 
+    #[test]
+    fn test_stdio_server_command_builder() {
+        let command = StdioServerCommand::new("server")
+            .arg("--verbose")
+            .args(["--port", "0"])
+            .env("API_KEY", "secret")
+            .env_remove("API_KEY")
+            .current_dir("/tmp");
+
+        assert_eq!(command.get_program(), "server");
+        assert_eq!(command.get_args(), ["--verbose", "--port", "0"]);
+        assert!(!command.env.contains_key("API_KEY"));
+        assert_eq!(command.env_remove, vec!["API_KEY".to_string()]);
+        assert_eq!(command.get_current_dir(), Some(Path::new("/tmp")));
+        assert!(!command.restart.enabled);
+    }
+
+    #[cfg(unix)]
+    fn request(id: i64) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: RequestId::from(id),
+            method: "test".to_string(),
+            params: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_env_and_cwd() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = dir.path().canonicalize().unwrap();
+        let command = StdioServerCommand::new("sh")
+            .arg("-c")
+            .arg(r#"read line; printf '{"jsonrpc":"2.0","id":1,"result":{"key":"%s","cwd":"%s"}}\n' "$API_KEY" "$(pwd -P)""#)
+            .env("API_KEY", "secret")
+            .current_dir(&cwd);
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        let response = transport.send_request(request(1)).await.unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["key"], "secret");
+        assert_eq!(result["cwd"], cwd.to_str().unwrap());
+
+        transport.close().await.unwrap();
+        assert!(!transport.is_connected());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_stderr_callback() {
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let captured = lines.clone();
+        let command = StdioServerCommand::new("sh")
+            .arg("-c")
+            .arg("echo 'starting up' >&2; read line")
+            .on_stderr(move |line| captured.lock().unwrap().push(line.to_string()));
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        for _ in 0..50 {
+            if !lines.lock().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(*lines.lock().unwrap(), vec!["starting up".to_string()]);
+
+        transport.close().await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_answers_server_requests() {
+        // The server asks for the client's roots and echoes the answer to stderr
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let captured = lines.clone();
+        let command = StdioServerCommand::new("sh")
+            .arg("-c")
+            .arg(r#"echo '{"jsonrpc":"2.0","id":"srv-1","method":"roots/list"}'; read line; echo "$line" >&2; read line"#)
+            .on_stderr(move |line| captured.lock().unwrap().push(line.to_string()));
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        transport.set_request_handler(Arc::new(|request: JsonRpcRequest| {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let roots = serde_json::json!({"roots": [{"uri": "file:///workspace"}]});
+            let _ = sender.send(JsonRpcResponse::success(request.id, roots).unwrap());
+            receiver
+        }));
+
+        for _ in 0..50 {
+            if !lines.lock().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        let answer: JsonRpcResponse = serde_json::from_str(&lines.lock().unwrap()[0]).unwrap();
+        assert_eq!(answer.id, Some(RequestId::from("srv-1")));
+        assert_eq!(
+            answer.result.unwrap()["roots"][0]["uri"],
+            "file:///workspace"
+        );
+
+        transport.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stdio_server_sends_requests() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+        let (client, server_end) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_end);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let mut transport = StdioServerTransport::with_streams(
+            server_read,
+            server_write,
+            TransportConfig::default(),
+        );
+        let sender = transport.sender().unwrap();
+        let serving = tokio::spawn(async move { transport.start().await });
+
+        let request = JsonRpcRequest::new(
+            RequestId::from("srv-1"),
+            "roots/list".to_string(),
+            None::<Value>,
+        )
+        .unwrap();
+        let pending = tokio::spawn(async move { sender.send_request(None, request).await });
+
+        // The client reads the request and writes back its response
+        let mut lines = BufReader::new(client_read).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        let request: JsonRpcRequest = serde_json::from_str(&line).unwrap();
+        assert_eq!(request.method, "roots/list");
+        let response = JsonRpcResponse::success(request.id, serde_json::json!({"roots": []}));
+        let line = format!("{}\n", serde_json::to_string(&response.unwrap()).unwrap());
+        client_write.write_all(line.as_bytes()).await.unwrap();
+
+        let response = pending.await.unwrap().unwrap();
+        assert_eq!(response.id, Some(RequestId::from("srv-1")));
+        assert_eq!(response.result, Some(serde_json::json!({"roots": []})));
+
+        client_write.shutdown().await.unwrap();
+        serving.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stdio_server_answers_invalid_batch() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+        let (client, server_end) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_end);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let mut transport = StdioServerTransport::with_streams(
+            server_read,
+            server_write,
+            TransportConfig::default(),
+        );
+        let serving = tokio::spawn(async move { transport.start().await });
+
+        client_write
+            .write_all(b"[{\"jsonrpc\": \"2.0\",\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(client_read).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        let response: JsonRpcResponse = serde_json::from_str(&line).unwrap();
+        assert_eq!(response.id, None);
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+
+        client_write.shutdown().await.unwrap();
+        serving.await.unwrap().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_reports_exit_status() {
+        let command = StdioServerCommand::new("sh").args(["-c", "exit 3"]);
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        sleep(Duration::from_millis(100)).await;
+
+        let error = transport.send_request(request(1)).await.unwrap_err();
+        assert!(matches!(error, McpError::Connection(_)));
+        assert!(error.to_string().contains("exit status: 3"));
+        assert!(!transport.is_connected());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_restarts_exited_server() {
+        let command = StdioServerCommand::new("sh")
+            .arg("-c")
+            .arg(r#"read line; echo '{"jsonrpc":"2.0","id":1,"result":{}}'"#)
+            .restart_on_exit(ReconnectConfig {
+                initial_delay_ms: 10,
+                ..Default::default()
+            });
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        assert!(transport.send_request(request(1)).await.is_ok());
+        sleep(Duration::from_millis(100)).await;
+
+        assert!(transport.send_request(request(1)).await.is_ok());
+        assert_eq!(transport.restart_count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_close_terminates_stubborn_server() {
+        let command = StdioServerCommand::new("sh")
+            .args(["-c", "while true; do sleep 1; done"])
+            .shutdown_timeout(Duration::from_millis(100));
+
+        let mut transport = StdioClientTransport::from_command(command).await.unwrap();
+        let started = std::time::Instant::now();
+        transport.close().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(transport.process_id().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_bounded_line() {
+        let mut reader: &[u8] = b"short\r\nthis line is far too long\nnext\n";
+        let mut buf = Vec::new();
+
+        let read = read_bounded_line(&mut reader, &mut buf, Some(10)).await;
+        assert_eq!(read.unwrap(), LineRead::Line);
+        assert_eq!(buf, b"short");
+
+        let read = read_bounded_line(&mut reader, &mut buf, Some(10)).await;
+        assert_eq!(read.unwrap(), LineRead::TooLong);
+        assert!(buf.starts_with(b"this line"));
+
+        let read = read_bounded_line(&mut reader, &mut buf, Some(10)).await;
+        assert_eq!(read.unwrap(), LineRead::Line);
+        assert_eq!(buf, b"next");
+
+        let read = read_bounded_line(&mut reader, &mut buf, Some(10)).await;
+        assert_eq!(read.unwrap(), LineRead::Eof);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_client_rejects_oversized_messages() {
+        let command = StdioServerCommand::new("sh").arg("-c").arg(
+            r#"read line; printf '{"jsonrpc":"2.0","id":1,"result":{"data":"%0200d"}}\n' 0; read line"#,
+        );
+        let config = TransportConfig {
+            max_message_size: Some(128),
+            ..Default::default()
+        };
+
+        let mut transport = StdioClientTransport::from_command_with_config(command, config)
+            .await
+            .unwrap();
+
+        let response = transport.send_request(request(1)).await.unwrap();
+        assert_eq!(response.id, Some(RequestId::from(1)));
+        let error = response.error.unwrap();
+        assert_eq!(error.code, crate::protocol::types::INVALID_REQUEST);
+        assert_eq!(error.data, Some(json!({ "maxSize": 128 })));
+
+        let mut oversized = request(2);
+        oversized.params = Some(json!({ "data": "x".repeat(256) }));
+        assert!(transport.send_request(oversized).await.is_err());
+
+        transport.close().await.unwrap();
+    }
+
     // Note: Integration tests with actual processes would go in tests/integration/
 }
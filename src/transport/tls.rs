@@ -0,0 +1,238 @@
+//! TLS support for the HTTP and WebSocket transports
+//!
+//! Server transports terminate TLS with a [`TlsServerConfig`] built from a
+//! PEM-encoded certificate chain and private key. The certificate can be
+//! replaced while the server is running: new connections use the reloaded
+//! certificate and established connections are left untouched.
+//!
+//! Clients connect to `https://` and `wss://` URLs and trust the public web
+//! roots plus any certificates in `TransportConfig::root_certificates`.
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::{net::TcpStream, time::timeout};
+use tokio_rustls::{
+    rustls::{Certificate, PrivateKey, ServerConfig},
+    server::TlsStream,
+    TlsAcceptor,
+};
+
+use crate::core::error::{McpError, McpResult};
+
+/// TLS settings for HTTP and WebSocket server transports
+///
+/// Clones share the same certificate, so a clone kept by the application can
+/// reload it after the original has been handed to a server transport.
+#[derive(Clone)]
+pub struct TlsServerConfig {
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsServerConfig {
+    /// Create a TLS configuration from an in-memory certificate and key
+    ///
+    /// # Arguments
+    /// * `cert_chain` - PEM-encoded certificate chain, leaf certificate first
+    /// * `private_key` - PEM-encoded PKCS#8, PKCS#1 or SEC1 private key
+    ///
+    /// # Returns
+    /// Result containing the configuration or an error if the PEM data is invalid
+    pub fn from_pem(cert_chain: &[u8], private_key: &[u8]) -> McpResult<Self> {
+        Ok(Self {
+            config: Arc::new(RwLock::new(server_config(cert_chain, private_key)?)),
+        })
+    }
+
+    /// Create a TLS configuration from certificate and key files
+    ///
+    /// # Arguments
+    /// * `cert_path` - Path to the PEM-encoded certificate chain
+    /// * `key_path` - Path to the PEM-encoded private key
+    ///
+    /// # Returns
+    /// Result containing the configuration or an error if a file cannot be read
+    pub fn from_pem_files<P: AsRef<Path>>(cert_path: P, key_path: P) -> McpResult<Self> {
+        let (cert_chain, private_key) = read_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+        Self::from_pem(&cert_chain, &private_key)
+    }
+
+    /// Replace the certificate and key with in-memory PEM data
+    ///
+    /// Connections accepted afterwards present the new certificate; established
+    /// connections are not affected. The current certificate is kept if the new
+    /// one is invalid.
+    ///
+    /// # Arguments
+    /// * `cert_chain` - PEM-encoded certificate chain, leaf certificate first
+    /// * `private_key` - PEM-encoded private key
+    pub fn reload_from_pem(&self, cert_chain: &[u8], private_key: &[u8]) -> McpResult<()> {
+        let config = server_config(cert_chain, private_key)?;
+        *self.config.write().unwrap_or_else(|e| e.into_inner()) = config;
+        tracing::info!("Reloaded TLS certificate");
+        Ok(())
+    }
+
+    /// Replace the certificate and key with the contents of PEM files
+    ///
+    /// # Arguments
+    /// * `cert_path` - Path to the PEM-encoded certificate chain
+    /// * `key_path` - Path to the PEM-encoded private key
+    pub fn reload_from_pem_files<P: AsRef<Path>>(
+        &self,
+        cert_path: P,
+        key_path: P,
+    ) -> McpResult<()> {
+        let (cert_chain, private_key) = read_pem_files(cert_path.as_ref(), key_path.as_ref())?;
+        self.reload_from_pem(&cert_chain, &private_key)
+    }
+
+    /// Perform the server side of a TLS handshake with the current certificate
+    pub(crate) async fn accept(
+        &self,
+        stream: TcpStream,
+        handshake_timeout: Duration,
+    ) -> McpResult<TlsStream<TcpStream>> {
+        let config = self
+            .config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        timeout(handshake_timeout, TlsAcceptor::from(config).accept(stream))
+            .await
+            .map_err(|_| McpError::Timeout("TLS handshake timed out".to_string()))?
+            .map_err(|e| McpError::transport(format!("TLS handshake failed: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for TlsServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsServerConfig").finish_non_exhaustive()
+    }
+}
+
+fn read_pem_files(cert_path: &Path, key_path: &Path) -> McpResult<(Vec<u8>, Vec<u8>)> {
+    let read = |path: &Path| {
+        fs::read(path)
+            .map_err(|e| McpError::transport(format!("Failed to read {}: {}", path.display(), e)))
+    };
+    Ok((read(cert_path)?, read(key_path)?))
+}
+
+fn server_config(cert_chain: &[u8], private_key: &[u8]) -> McpResult<Arc<ServerConfig>> {
+    let certs = read_certificates(cert_chain)?;
+    if certs.is_empty() {
+        return Err(McpError::transport(
+            "No certificate found in the PEM certificate chain",
+        ));
+    }
+
+    let key = rustls_pemfile::read_all(&mut &*private_key)
+        .map_err(|e| McpError::transport(format!("Invalid PEM private key: {}", e)))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| McpError::transport("No private key found in the PEM data"))?;
+
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| McpError::transport(format!("Invalid TLS certificate or key: {}", e)))?;
+    Ok(Arc::new(config))
+}
+
+fn read_certificates(pem: &[u8]) -> McpResult<Vec<Certificate>> {
+    let certs = rustls_pemfile::certs(&mut &*pem)
+        .map_err(|e| McpError::transport(format!("Invalid PEM certificate: {}", e)))?;
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// Build a WebSocket TLS connector trusting `root_certificates` besides the web roots
+///
+/// # Returns
+/// `None` if there are no extra roots, leaving the default connector in place
+#[cfg(feature = "websocket")]
+pub(crate) fn websocket_connector(
+    root_certificates: &[Vec<u8>],
+) -> McpResult<Option<tokio_tungstenite::Connector>> {
+    use tokio_rustls::rustls::{ClientConfig, OwnedTrustAnchor, RootCertStore};
+
+    if root_certificates.is_empty() {
+        return Ok(None);
+    }
+
+    let mut roots = RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+        OwnedTrustAnchor::from_subject_spki_name_constraints(
+            ta.subject,
+            ta.spki,
+            ta.name_constraints,
+        )
+    }));
+    for pem in root_certificates {
+        for cert in read_certificates(pem)? {
+            roots
+                .add(&cert)
+                .map_err(|e| McpError::transport(format!("Invalid root certificate: {}", e)))?;
+        }
+    }
+
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Some(tokio_tungstenite::Connector::Rustls(Arc::new(config))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn self_signed() -> (String, String) {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        (
+            cert.serialize_pem().unwrap(),
+            cert.serialize_private_key_pem(),
+        )
+    }
+
+    #[test]
+    fn test_tls_server_config() {
+        let (cert, key) = self_signed();
+        let tls = TlsServerConfig::from_pem(cert.as_bytes(), key.as_bytes()).unwrap();
+
+        assert!(TlsServerConfig::from_pem(b"", key.as_bytes()).is_err());
+        assert!(TlsServerConfig::from_pem(cert.as_bytes(), b"").is_err());
+
+        // Reloading through a clone swaps the certificate for every holder
+        let before = tls.config.read().unwrap().clone();
+        let (new_cert, new_key) = self_signed();
+        tls.clone()
+            .reload_from_pem(new_cert.as_bytes(), new_key.as_bytes())
+            .unwrap();
+        assert!(!Arc::ptr_eq(&before, &tls.config.read().unwrap()));
+
+        // A failed reload keeps the current certificate
+        let current = tls.config.read().unwrap().clone();
+        assert!(tls.reload_from_pem(b"", b"").is_err());
+        assert!(Arc::ptr_eq(&current, &tls.config.read().unwrap()));
+
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = (dir.path().join("cert.pem"), dir.path().join("key.pem"));
+        fs::write(&cert_path, &cert).unwrap();
+        fs::write(&key_path, &key).unwrap();
+        let from_files = TlsServerConfig::from_pem_files(&cert_path, &key_path).unwrap();
+        from_files
+            .reload_from_pem_files(&cert_path, &key_path)
+            .unwrap();
+        assert!(TlsServerConfig::from_pem_files(&key_path, &dir.path().join("missing")).is_err());
+    }
+}
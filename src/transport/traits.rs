@@ -3,9 +3,17 @@
 //! This module defines the core transport traits that enable MCP communication
 //! over different protocols like STDIO, HTTP, and WebSocket.
 
-use crate::core::error::McpResult;
+use crate::core::error::{McpError, McpResult};
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+use crate::protocol::types::{
+    JsonRpcBatch, RequestId, INTERNAL_ERROR, INVALID_REQUEST, METHOD_NOT_FOUND,
+};
 use crate::protocol::types::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
 use async_trait::async_trait;
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+use serde::Deserialize;
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+use serde_json::Value;
 
 /// Transport trait for MCP clients
 ///
@@ -22,6 +30,27 @@ pub trait Transport: Send + Sync {
     /// Result containing the JSON-RPC response or an error
     async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse>;
 
+    /// Send several JSON-RPC requests as one batch and wait for all responses
+    ///
+    /// Responses may be returned in any order; callers match them to requests by ID.
+    /// The default implementation sends the requests one at a time.
+    ///
+    /// # Arguments
+    /// * `requests` - The JSON-RPC requests to send
+    ///
+    /// # Returns
+    /// Result containing the JSON-RPC responses or an error
+    async fn send_batch(
+        &mut self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.send_request(request).await?);
+        }
+        Ok(responses)
+    }
+
     /// Send a JSON-RPC notification (no response expected)
     ///
     /// # Arguments
@@ -43,6 +72,18 @@ pub trait Transport: Send + Sync {
     /// Result indicating success or an error
     async fn close(&mut self) -> McpResult<()>;
 
+    /// Set the handler answering requests the server sends, such as `roots/list`
+    ///
+    /// Requests arriving while no handler is set are answered with
+    /// `METHOD_NOT_FOUND`. Transports that cannot receive requests from the
+    /// server keep the default implementation, which ignores the handler.
+    ///
+    /// # Arguments
+    /// * `handler` - Function that answers a request from the server
+    fn set_request_handler(&mut self, handler: RequestCallback) {
+        let _ = handler;
+    }
+
     /// Check if the transport is connected
     ///
     /// # Returns
@@ -60,6 +101,44 @@ pub trait Transport: Send + Sync {
     }
 }
 
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        (**self).send_request(request).await
+    }
+
+    async fn send_batch(
+        &mut self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        (**self).send_batch(requests).await
+    }
+
+    async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
+        (**self).send_notification(notification).await
+    }
+
+    async fn receive_notification(&mut self) -> McpResult<Option<JsonRpcNotification>> {
+        (**self).receive_notification().await
+    }
+
+    async fn close(&mut self) -> McpResult<()> {
+        (**self).close().await
+    }
+
+    fn set_request_handler(&mut self, handler: RequestCallback) {
+        (**self).set_request_handler(handler)
+    }
+
+    fn is_connected(&self) -> bool {
+        (**self).is_connected()
+    }
+
+    fn connection_info(&self) -> String {
+        (**self).connection_info()
+    }
+}
+
 /// Transport trait for MCP servers
 ///
 /// This trait defines the interface for handling incoming requests and
@@ -90,6 +169,70 @@ pub trait ServerTransport: Send + Sync {
     /// Result indicating success or an error
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()>;
 
+    /// Send a JSON-RPC notification to the client of a single session
+    ///
+    /// Transports that serve one client, or cannot address their clients
+    /// individually, keep the default implementation, which returns an error
+    /// rather than broadcasting the notification.
+    ///
+    /// # Arguments
+    /// * `session_id` - ID the transport assigned to the client's connection
+    /// * `notification` - The JSON-RPC notification to send
+    ///
+    /// # Returns
+    /// Result indicating success or an error
+    async fn send_session_notification(
+        &mut self,
+        session_id: &str,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        Err(McpError::transport(format!(
+            "Transport cannot send notifications to session {} ('{}')",
+            session_id, notification.method
+        )))
+    }
+
+    /// Send a JSON-RPC request to the client and wait for its response
+    ///
+    /// Transports that cannot carry server-to-client requests keep the default
+    /// implementation, which returns an error.
+    ///
+    /// # Arguments
+    /// * `request` - The JSON-RPC request to send
+    ///
+    /// # Returns
+    /// Result containing the client's JSON-RPC response or an error
+    async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        Err(McpError::transport(format!(
+            "Transport does not support server-to-client requests ('{}')",
+            request.method
+        )))
+    }
+
+    /// Install the handlers the transport passes incoming messages to
+    ///
+    /// `McpServer::start` calls this before starting the transport so that the
+    /// server answers the transport's requests. Transports that take their
+    /// handlers some other way keep the default implementation, which ignores them.
+    ///
+    /// # Arguments
+    /// * `handlers` - Callbacks for incoming requests, notifications and closed sessions
+    async fn set_handlers(&mut self, handlers: SessionHandlers) {
+        let _ = handlers;
+    }
+
+    /// Get a handle for sending messages to the clients while the transport is serving
+    ///
+    /// The `&mut self` methods of this trait cannot be called while `start` is
+    /// running, which for a transport such as STDIO lasts the whole session.
+    /// Transports that return `None` are only reachable between those calls.
+    ///
+    /// # Returns
+    /// The transport's sender, or `None` if it has none
+    fn sender(&self) -> Option<std::sync::Arc<dyn ServerSender>> {
+        None
+    }
+
     /// Stop the server transport
     ///
     /// # Returns
@@ -125,11 +268,43 @@ pub struct TransportConfig {
     /// Maximum message size in bytes
     pub max_message_size: Option<usize>,
     /// Keep-alive interval in milliseconds
+    ///
+    /// Server transports that can probe their peers ping them at this interval.
     pub keep_alive_ms: Option<u64>,
+    /// Consecutive unanswered keep-alive pings before a peer is considered gone
+    pub max_missed_pings: u32,
     /// Whether to enable compression
+    ///
+    /// HTTP transports negotiate gzip/br bodies; peers without support are
+    /// served uncompressed. WebSocket transports do not support compression yet,
+    /// as tokio-tungstenite 0.20 lacks `permessage-deflate`, and return an error
+    /// when they connect or start with it enabled.
     pub compression: bool,
     /// Custom headers for HTTP-based transports
     pub headers: std::collections::HashMap<String, String>,
+    /// Origins browsers may connect to a server transport from, such as `https://app.example.com`
+    ///
+    /// WebSocket servers refuse upgrades from other origins and HTTP servers do not
+    /// allow them cross-origin access; `None` allows every origin. Clients that send
+    /// no `Origin` header, which browsers always send, are not affected.
+    pub allowed_origins: Option<Vec<String>>,
+    /// Time in milliseconds after which an HTTP session its client stopped using ends
+    ///
+    /// A session is idle while its client sends nothing and has no event stream
+    /// open. An expired session ends as if the client had sent `DELETE`; `None`
+    /// keeps sessions until their clients end them.
+    pub session_idle_timeout_ms: Option<u64>,
+    /// Maximum number of HTTP sessions open at once
+    ///
+    /// `initialize` requests that would start another session are answered with
+    /// `503 Service Unavailable`; `None` allows any number.
+    pub max_sessions: Option<usize>,
+    /// PEM-encoded CA certificates that clients trust besides the built-in roots
+    ///
+    /// Used when connecting to `https://` and `wss://` URLs, for example to reach
+    /// servers whose certificates are issued by an internal CA. `wss://` URLs
+    /// require the `tls` feature.
+    pub root_certificates: Vec<Vec<u8>>,
 }
 
 impl Default for TransportConfig {
@@ -140,12 +315,599 @@ impl Default for TransportConfig {
             write_timeout_ms: Some(30_000),           // 30 seconds
             max_message_size: Some(16 * 1024 * 1024), // 16 MB
             keep_alive_ms: Some(30_000),              // 30 seconds
+            max_missed_pings: 3,
             compression: false,
             headers: std::collections::HashMap::new(),
+            allowed_origins: None,
+            session_idle_timeout_ms: Some(30 * 60_000), // 30 minutes
+            max_sessions: Some(1_000),
+            root_certificates: Vec::new(),
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Check whether a request from `origin` may be served
+    ///
+    /// # Arguments
+    /// * `origin` - Value of the request's `Origin` header, if it has one
+    ///
+    /// # Returns
+    /// True if the origin is allowed or the request has no origin
+    pub fn allows_origin(&self, origin: Option<&str>) -> bool {
+        match (&self.allowed_origins, origin) {
+            (Some(allowed), Some(origin)) => allowed
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(origin)),
+            _ => true,
+        }
+    }
+
+    /// Check a message of `size` bytes against `max_message_size`
+    ///
+    /// # Returns
+    /// Error if the message is larger than the configured limit
+    pub fn check_message_size(&self, size: usize) -> McpResult<()> {
+        match self.max_message_size {
+            Some(max) if size > max => Err(McpError::transport(format!(
+                "Message of {} bytes exceeds the maximum message size of {} bytes",
+                size, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Number of leading bytes kept from an oversized message to recover its ID
+pub(crate) const OVERSIZED_PREFIX_LEN: usize = 4096;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Build the error response returned in place of an oversized message
+pub(crate) fn message_too_large_response(
+    id: Option<RequestId>,
+    max_size: usize,
+) -> JsonRpcResponse {
+    JsonRpcResponse::error(
+        id,
+        INVALID_REQUEST,
+        format!("Message exceeds the maximum size of {} bytes", max_size),
+        Some(serde_json::json!({ "maxSize": max_size })),
+    )
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Recover the top-level `id` of a JSON-RPC message from its leading bytes
+///
+/// Used when a message is too large to parse in full.
+pub(crate) fn recover_message_id(prefix: &[u8]) -> Option<RequestId> {
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < prefix.len() {
+        match prefix[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'"' => {
+                let end = json_string_end(prefix, i)?;
+                if depth == 1 && &prefix[i..=end] == b"\"id\"" {
+                    let rest = skip_json_whitespace(&prefix[end + 1..]);
+                    if let Some(rest) = rest.strip_prefix(b":") {
+                        return parse_message_id(skip_json_whitespace(rest));
+                    }
+                }
+                i = end;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Index of the quote closing the JSON string that starts at `start`
+fn json_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+fn skip_json_whitespace(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+fn parse_message_id(bytes: &[u8]) -> Option<RequestId> {
+    let end = match bytes.first()? {
+        b'"' => json_string_end(bytes, 0)? + 1,
+        b'-' | b'0'..=b'9' => bytes
+            .iter()
+            .position(|b| !matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+            .unwrap_or(bytes.len()),
+        _ => return None,
+    };
+
+    serde_json::from_slice(&bytes[..end]).ok()
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Senders for the responses client transports are waiting on, keyed by request ID
+pub(crate) type PendingResponses =
+    std::collections::HashMap<RequestId, tokio::sync::oneshot::Sender<JsonRpcResponse>>;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Register requests as waiting for their responses
+///
+/// Nothing is registered if any of the IDs is already waiting or appears twice.
+///
+/// # Returns
+/// Receivers for the responses, in the order of `ids`, or an error naming the duplicate ID
+pub(crate) fn register_pending<'a>(
+    pending: &mut PendingResponses,
+    ids: impl IntoIterator<Item = &'a RequestId>,
+) -> McpResult<Vec<tokio::sync::oneshot::Receiver<JsonRpcResponse>>> {
+    let mut registered: Vec<RequestId> = Vec::new();
+    let mut receivers = Vec::new();
+    for id in ids {
+        if pending.contains_key(id) {
+            for id in &registered {
+                pending.remove(id);
+            }
+            return Err(McpError::protocol(format!(
+                "Request ID {} is already in flight",
+                id
+            )));
+        }
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        pending.insert(id.clone(), sender);
+        registered.push(id.clone());
+        receivers.push(receiver);
+    }
+    Ok(receivers)
+}
+
+/// Callback a transport passes incoming requests to
+pub type RequestCallback = std::sync::Arc<
+    dyn Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse> + Send + Sync,
+>;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Callback a server transport passes incoming notifications to
+pub(crate) type NotificationCallback = std::sync::Arc<dyn Fn(JsonRpcNotification) + Send + Sync>;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Request handler of a client transport, shared with the task reading its messages
+pub(crate) type ClientRequestHandler = std::sync::Arc<std::sync::RwLock<Option<RequestCallback>>>;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Answer a request the server sent to a client transport
+///
+/// # Returns
+/// The serialized response, a `METHOD_NOT_FOUND` error if the client has no request handler
+pub(crate) async fn answer_server_request(
+    handler: &ClientRequestHandler,
+    request: JsonRpcRequest,
+    config: &TransportConfig,
+) -> McpResult<String> {
+    let id = request.id.clone();
+    let handler = handler.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let response = match handler {
+        Some(handler) => handler(request).await.unwrap_or_else(|_| {
+            JsonRpcResponse::error(
+                Some(id),
+                INTERNAL_ERROR,
+                "Request handler channel closed".to_string(),
+                None,
+            )
+        }),
+        None => JsonRpcResponse::error(
+            Some(id),
+            METHOD_NOT_FOUND,
+            format!("Method '{}' not found", request.method),
+            None,
+        ),
+    };
+    response_text(response, config)
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Serialize a response, replacing it with an error if it exceeds the size limit
+pub(crate) fn response_text(
+    response: JsonRpcResponse,
+    config: &TransportConfig,
+) -> McpResult<String> {
+    let text = serde_json::to_string(&response).map_err(McpError::serialization)?;
+    if let Err(e) = config.check_message_size(text.len()) {
+        tracing::warn!("Response to request {:?}: {}", response.id, e);
+        let error_response = JsonRpcResponse::error(
+            response.id,
+            INTERNAL_ERROR,
+            "Response exceeds the maximum message size".to_string(),
+            None,
+        );
+        return serde_json::to_string(&error_response).map_err(McpError::serialization);
+    }
+    Ok(text)
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Requests a server transport sent to a client, waiting for their responses
+pub(crate) type ClientRequests = std::sync::Arc<std::sync::Mutex<PendingResponses>>;
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Send a request to a client and wait for the response delivered to `pending`
+///
+/// # Arguments
+/// * `pending` - Requests of the client's connection waiting for responses
+/// * `request` - The request to send
+/// * `wait` - How long to wait for the response
+/// * `send` - Writes the request to the client
+///
+/// # Returns
+/// Result containing the client's response, or an error if it could not be sent,
+/// the client went away, or the response did not arrive in time
+pub(crate) async fn request_client<F, Fut>(
+    pending: &ClientRequests,
+    request: JsonRpcRequest,
+    wait: std::time::Duration,
+    send: F,
+) -> McpResult<JsonRpcResponse>
+where
+    F: FnOnce(JsonRpcRequest) -> Fut,
+    Fut: std::future::Future<Output = McpResult<()>>,
+{
+    let id = request.id.clone();
+    let receiver = register_pending(
+        &mut pending.lock().unwrap_or_else(|e| e.into_inner()),
+        [&id],
+    )?
+    .remove(0);
+    let forget = || {
+        pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+    };
+
+    if let Err(e) = send(request).await {
+        forget();
+        return Err(e);
+    }
+    match tokio::time::timeout(wait, receiver).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(_)) => Err(McpError::connection(
+            "Client disconnected before answering the request",
+        )),
+        Err(_) => {
+            forget();
+            Err(McpError::timeout(format!(
+                "Client did not answer request {} in time",
+                id
+            )))
         }
     }
 }
 
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Deliver a client's response to the server request waiting for it
+pub(crate) fn deliver_response(pending: &ClientRequests, response: JsonRpcResponse) {
+    let sender = response
+        .id
+        .as_ref()
+        .and_then(|id| pending.lock().unwrap_or_else(|e| e.into_inner()).remove(id));
+    match sender {
+        Some(sender) => {
+            let _ = sender.send(response);
+        }
+        None => tracing::warn!(
+            "Received response for unknown request ID: {:?}",
+            response.id
+        ),
+    }
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Parse a response from a client, which unlike a request or notification has no method
+pub(crate) fn parse_client_response(message: &Value) -> Option<JsonRpcResponse> {
+    if message.get("method").is_some() {
+        return None;
+    }
+    serde_json::from_value::<JsonRpcResponse>(message.clone())
+        .ok()
+        .filter(|response| response.result.is_some() || response.error.is_some())
+}
+
+/// Callback a server transport serving several connections passes incoming requests to
+///
+/// Besides the request it receives the ID of the session the request arrived on,
+/// or `None` if the request does not belong to a session.
+pub type SessionRequestCallback = std::sync::Arc<
+    dyn Fn(Option<String>, JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>
+        + Send
+        + Sync,
+>;
+
+/// Callback a server transport serving several connections passes incoming notifications to
+pub type SessionNotificationCallback =
+    std::sync::Arc<dyn Fn(Option<String>, JsonRpcNotification) + Send + Sync>;
+
+/// Callback a server transport calls with the ID of a session that ended
+pub type SessionClosedCallback = std::sync::Arc<dyn Fn(String) + Send + Sync>;
+
+/// Bind a session request callback to the requests of one session
+#[cfg(any(feature = "http", feature = "websocket"))]
+pub(crate) fn session_request_handler(
+    handler: Option<SessionRequestCallback>,
+    session_id: Option<&str>,
+) -> Option<RequestCallback> {
+    let session_id = session_id.map(str::to_string);
+    handler.map(|handler| -> RequestCallback {
+        std::sync::Arc::new(move |request| handler(session_id.clone(), request))
+    })
+}
+
+/// Bind a session notification callback to the notifications of one session
+#[cfg(any(feature = "http", feature = "websocket"))]
+pub(crate) fn session_notification_handler(
+    handler: Option<SessionNotificationCallback>,
+    session_id: Option<&str>,
+) -> Option<NotificationCallback> {
+    let session_id = session_id.map(str::to_string);
+    handler.map(|handler| -> NotificationCallback {
+        std::sync::Arc::new(move |notification| handler(session_id.clone(), notification))
+    })
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Pass a notification to the notification handler of a server transport, if it has one
+pub(crate) fn deliver_notification(
+    handler: Option<&NotificationCallback>,
+    notification: JsonRpcNotification,
+) {
+    match handler {
+        Some(handler) => handler(notification),
+        None => tracing::debug!(
+            "No notification handler configured, dropping {}",
+            notification.method
+        ),
+    }
+}
+
+/// Requests a server transport is answering for a peer that may go away
+///
+/// Tracked requests that are still unanswered when this is dropped, because the
+/// client hung up without closing its session, are cancelled with
+/// `notifications/cancelled` so the handler releases the work done on their
+/// behalf. Requests of a session that closes are cancelled by the server instead.
+#[cfg(feature = "http")]
+pub(crate) struct CancelOnDrop {
+    ids: std::sync::Mutex<Vec<RequestId>>,
+    handler: Option<NotificationCallback>,
+}
+
+#[cfg(feature = "http")]
+impl CancelOnDrop {
+    pub(crate) fn new(handler: Option<NotificationCallback>) -> Self {
+        Self {
+            ids: std::sync::Mutex::new(Vec::new()),
+            handler,
+        }
+    }
+
+    /// Track a request until it is answered
+    pub(crate) fn track(&self, id: RequestId) {
+        self.ids.lock().unwrap_or_else(|e| e.into_inner()).push(id);
+    }
+
+    /// Stop tracking the requests, once their responses are available
+    pub(crate) fn disarm(&self) {
+        self.ids.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+#[cfg(feature = "http")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let ids = std::mem::take(self.ids.get_mut().unwrap_or_else(|e| e.into_inner()));
+        for id in ids {
+            tracing::debug!("Peer went away, cancelling request {}", id);
+            let params = crate::protocol::messages::CancelledParams {
+                request_id: id,
+                reason: Some("Client disconnected".to_string()),
+                meta: None,
+            };
+            match JsonRpcNotification::new(
+                crate::protocol::messages::methods::CANCELLED.to_string(),
+                Some(params),
+            ) {
+                Ok(notification) => deliver_notification(self.handler.as_ref(), notification),
+                Err(e) => tracing::error!("Failed to build cancellation: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Dispatch the entries of a batch received by a server transport
+///
+/// Every request is handed to `handler` before any response is awaited, so the
+/// requests are processed concurrently. Notifications are handed to `notify` and
+/// responses to requests of the server to `respond`; neither gets a response.
+/// Entries that are not valid messages get an `INVALID_REQUEST` error.
+///
+/// # Returns
+/// The response to send back, or `None` if the batch held nothing to answer
+pub(crate) async fn dispatch_batch<F, N, R>(
+    entries: Vec<Value>,
+    handler: F,
+    notify: N,
+    respond: R,
+) -> Option<JsonRpcBatch<JsonRpcResponse>>
+where
+    F: Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>,
+    N: Fn(JsonRpcNotification),
+    R: Fn(JsonRpcResponse),
+{
+    if entries.is_empty() {
+        return Some(JsonRpcBatch::Single(JsonRpcResponse::error(
+            None,
+            INVALID_REQUEST,
+            "Batch cannot be empty".to_string(),
+            None,
+        )));
+    }
+
+    let pending: Vec<_> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            if let Ok(request) = serde_json::from_value::<JsonRpcRequest>(entry.clone()) {
+                let id = request.id.clone();
+                Some(Ok((id, handler(request))))
+            } else if let Ok(notification) =
+                serde_json::from_value::<JsonRpcNotification>(entry.clone())
+            {
+                notify(notification);
+                None
+            } else if let Some(response) = parse_client_response(&entry) {
+                respond(response);
+                None
+            } else {
+                let id = entry
+                    .get("id")
+                    .and_then(|id| RequestId::deserialize(id).ok());
+                Some(Err(JsonRpcResponse::error(
+                    id,
+                    INVALID_REQUEST,
+                    "Invalid batch entry".to_string(),
+                    None,
+                )))
+            }
+        })
+        .collect();
+
+    let mut responses = Vec::with_capacity(pending.len());
+    for entry in pending {
+        responses.push(match entry {
+            Ok((id, receiver)) => receiver.await.unwrap_or_else(|_| {
+                JsonRpcResponse::error(
+                    id,
+                    INTERNAL_ERROR,
+                    "Request handler channel closed".to_string(),
+                    None,
+                )
+            }),
+            Err(response) => response,
+        });
+    }
+
+    (!responses.is_empty()).then_some(JsonRpcBatch::Batch(responses))
+}
+
+#[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+/// Serialize a batch response, replacing its entries with errors if it exceeds the size limit
+pub(crate) fn batch_response_text(
+    responses: Vec<JsonRpcResponse>,
+    max_size: Option<usize>,
+) -> McpResult<String> {
+    let text = serde_json::to_string(&responses).map_err(McpError::serialization)?;
+    if max_size.map_or(true, |max| text.len() <= max) {
+        return Ok(text);
+    }
+
+    tracing::warn!("Batch response exceeds the maximum message size");
+    let errors: Vec<_> = responses
+        .into_iter()
+        .map(|response| {
+            JsonRpcResponse::error(
+                response.id,
+                INTERNAL_ERROR,
+                "Response exceeds the maximum message size".to_string(),
+                None,
+            )
+        })
+        .collect();
+    serde_json::to_string(&errors).map_err(McpError::serialization)
+}
+
+/// Handle for sending messages to the clients of a server transport
+///
+/// Obtained from [`ServerTransport::sender`]. It does not borrow the transport,
+/// so handlers can send through it while the transport serves their requests.
+#[async_trait]
+pub trait ServerSender: Send + Sync {
+    /// Send a JSON-RPC notification
+    ///
+    /// # Arguments
+    /// * `session_id` - Session of the client to notify, or `None` to notify every
+    ///   client of the transport
+    /// * `notification` - The JSON-RPC notification to send
+    ///
+    /// # Returns
+    /// Result indicating success or an error
+    async fn send_notification(
+        &self,
+        session_id: Option<&str>,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()>;
+
+    /// Send a JSON-RPC request to a client and wait for its response
+    ///
+    /// Senders of transports that cannot carry server-to-client requests keep the
+    /// default implementation, which returns an error.
+    ///
+    /// # Arguments
+    /// * `session_id` - Session of the client to ask, or `None` for the client of a
+    ///   transport serving a single connection
+    /// * `request` - The JSON-RPC request to send
+    ///
+    /// # Returns
+    /// Result containing the client's JSON-RPC response or an error
+    async fn send_request(
+        &self,
+        session_id: Option<&str>,
+        request: JsonRpcRequest,
+    ) -> McpResult<JsonRpcResponse> {
+        let _ = session_id;
+        Err(McpError::transport(format!(
+            "Transport does not support server-to-client requests ('{}')",
+            request.method
+        )))
+    }
+}
+
+/// Callbacks a server transport passes the messages it receives to
+///
+/// Each callback is told the ID of the session the message arrived on, or `None`
+/// for the client of a transport serving a single connection.
+#[derive(Clone)]
+pub struct SessionHandlers {
+    /// Answers incoming requests
+    pub request: SessionRequestCallback,
+    /// Handles incoming notifications
+    pub notification: SessionNotificationCallback,
+    /// Tears down a session whose connection closed
+    pub closed: SessionClosedCallback,
+}
+
+impl std::fmt::Debug for SessionHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionHandlers").finish_non_exhaustive()
+    }
+}
+
 /// Connection state for transports
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -309,6 +1071,176 @@ mod tests {
         assert!(!config.compression);
     }
 
+    #[test]
+    fn test_check_message_size() {
+        let config = TransportConfig {
+            max_message_size: Some(10),
+            ..Default::default()
+        };
+        assert!(config.check_message_size(10).is_ok());
+        assert!(config.check_message_size(11).is_err());
+
+        let unlimited = TransportConfig {
+            max_message_size: None,
+            ..Default::default()
+        };
+        assert!(unlimited.check_message_size(usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_allows_origin() {
+        let config = TransportConfig {
+            allowed_origins: Some(vec!["https://app.example.com".to_string()]),
+            ..Default::default()
+        };
+        assert!(config.allows_origin(Some("https://APP.example.com")));
+        assert!(!config.allows_origin(Some("https://evil.example.com")));
+        assert!(config.allows_origin(None));
+        assert!(TransportConfig::default().allows_origin(Some("https://evil.example.com")));
+    }
+
+    #[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+    #[test]
+    fn test_recover_message_id() {
+        let prefix =
+            br#"{"jsonrpc":"2.0","params":{"id":"nested"},"id":42,"method":"x","params":"aaaa"#;
+        assert_eq!(recover_message_id(prefix), Some(RequestId::Number(42)));
+
+        let prefix = br#"{"method":"id","id" : "req-\"1\"","params":{"#;
+        assert_eq!(
+            recover_message_id(prefix),
+            Some(RequestId::from("req-\"1\""))
+        );
+
+        assert_eq!(
+            recover_message_id(br#"{"jsonrpc":"2.0","params":{"id":1"#),
+            None
+        );
+        assert_eq!(recover_message_id(br#"{"id":null}"#), None);
+        assert_eq!(recover_message_id(br#"{"id":"unterminated"#), None);
+    }
+
+    #[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+    #[test]
+    fn test_register_pending() {
+        let mut pending = PendingResponses::new();
+        let first = RequestId::Number(1);
+        assert_eq!(register_pending(&mut pending, [&first]).unwrap().len(), 1);
+        assert!(register_pending(&mut pending, [&first]).is_err());
+
+        let second = RequestId::Number(2);
+        assert!(register_pending(&mut pending, [&second, &second]).is_err());
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+    #[tokio::test]
+    async fn test_dispatch_batch() {
+        let handler = |request: JsonRpcRequest| {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let _ = sender.send(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: Some(request.id),
+                result: Some(Value::from(request.method)),
+                error: None,
+            });
+            receiver
+        };
+        let notified = std::sync::Mutex::new(Vec::new());
+        let notify = |notification: JsonRpcNotification| {
+            notified.lock().unwrap().push(notification.method);
+        };
+        let answered = std::sync::Mutex::new(Vec::new());
+        let respond = |response: JsonRpcResponse| {
+            answered.lock().unwrap().push(response.id.unwrap());
+        };
+
+        let entries = vec![
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            serde_json::json!({"id": 3, "bogus": true}),
+            serde_json::json!({"jsonrpc": "2.0", "id": "b", "method": "prompts/list"}),
+            serde_json::json!({"jsonrpc": "2.0", "id": "srv-1", "result": {"roots": []}}),
+        ];
+        let responses = match dispatch_batch(entries, handler, notify, respond).await {
+            Some(JsonRpcBatch::Batch(responses)) => responses,
+            other => panic!("unexpected batch response: {:?}", other),
+        };
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].result, Some(Value::from("tools/list")));
+        assert_eq!(responses[1].id, Some(RequestId::Number(3)));
+        assert_eq!(responses[1].error.as_ref().unwrap().code, INVALID_REQUEST);
+        assert_eq!(responses[2].id, Some(RequestId::from("b")));
+        assert_eq!(*notified.lock().unwrap(), ["notifications/initialized"]);
+        assert_eq!(*answered.lock().unwrap(), [RequestId::from("srv-1")]);
+
+        let notifications_only = vec![serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/initialized"
+        })];
+        assert!(dispatch_batch(notifications_only, handler, notify, respond)
+            .await
+            .is_none());
+
+        match dispatch_batch(Vec::new(), handler, notify, respond).await {
+            Some(JsonRpcBatch::Single(response)) => {
+                assert_eq!(response.error.unwrap().code, INVALID_REQUEST)
+            }
+            other => panic!("unexpected batch response: {:?}", other),
+        }
+    }
+
+    #[cfg(any(feature = "stdio", feature = "http", feature = "websocket"))]
+    #[tokio::test]
+    async fn test_request_client() {
+        let pending = ClientRequests::default();
+        let wait = std::time::Duration::from_secs(5);
+
+        let request = JsonRpcRequest::new(
+            RequestId::from("srv-1"),
+            "roots/list".to_string(),
+            None::<()>,
+        )
+        .unwrap();
+        let answer = {
+            let pending = pending.clone();
+            move |request: JsonRpcRequest| async move {
+                let response =
+                    serde_json::json!({"jsonrpc": "2.0", "id": request.id, "result": {}});
+                deliver_response(&pending, parse_client_response(&response).unwrap());
+                Ok(())
+            }
+        };
+        let response = request_client(&pending, request, wait, answer)
+            .await
+            .unwrap();
+        assert_eq!(response.id, Some(RequestId::from("srv-1")));
+        assert!(pending.lock().unwrap().is_empty());
+
+        // A request that cannot be sent or is not answered in time is forgotten
+        let request =
+            JsonRpcRequest::new(RequestId::Number(2), "ping".to_string(), None::<()>).unwrap();
+        let failed = request_client(&pending, request.clone(), wait, |_| async {
+            Err(McpError::connection("gone"))
+        })
+        .await;
+        assert!(failed.is_err());
+        let silent = request_client(
+            &pending,
+            request,
+            std::time::Duration::from_millis(10),
+            |_| async { Ok(()) },
+        )
+        .await;
+        assert!(matches!(silent, Err(McpError::Timeout(_))));
+        assert!(pending.lock().unwrap().is_empty());
+
+        // Requests and notifications are not responses
+        let request = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        assert!(parse_client_response(&request).is_none());
+        assert!(parse_client_response(&serde_json::json!({"jsonrpc": "2.0", "id": 1})).is_none());
+    }
+
     #[test]
     fn test_reconnect_config_default() {
         let config = ReconnectConfig::default();
@@ -341,4 +1273,34 @@ mod tests {
         assert_eq!(stats.bytes_sent, 0);
         assert_eq!(stats.bytes_received, 0);
     }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_cancel_on_drop() {
+        let cancelled = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handler: NotificationCallback = {
+            let cancelled = cancelled.clone();
+            std::sync::Arc::new(move |notification: JsonRpcNotification| {
+                let params: crate::protocol::messages::CancelledParams =
+                    serde_json::from_value(notification.params.unwrap()).unwrap();
+                cancelled.lock().unwrap().push(params.request_id);
+            })
+        };
+
+        // Answered requests are not cancelled
+        let pending = CancelOnDrop::new(Some(handler.clone()));
+        pending.track(RequestId::from(1));
+        pending.disarm();
+        drop(pending);
+        assert!(cancelled.lock().unwrap().is_empty());
+
+        let pending = CancelOnDrop::new(Some(handler));
+        pending.track(RequestId::from(2));
+        pending.track(RequestId::from("three"));
+        drop(pending);
+        assert_eq!(
+            *cancelled.lock().unwrap(),
+            vec![RequestId::from(2), RequestId::from("three")]
+        );
+    }
 }
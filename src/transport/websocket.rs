@@ -2,6 +2,11 @@
 //!
 //! This module provides WebSocket-based transport for MCP communication,
 //! offering bidirectional, real-time communication between clients and servers.
+//!
+//! Messages are never compressed: tokio-tungstenite 0.20 does not implement the
+//! RFC 7692 `permessage-deflate` extension, so no extension is negotiated and
+//! transports configured with `TransportConfig::compression` fail to connect or
+//! start rather than silently sending uncompressed messages.
 
 use async_trait::async_trait;
 use futures_util::{
@@ -11,30 +16,173 @@ use futures_util::{
 use serde_json::Value;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::{
+    io::{AsyncRead, AsyncWrite},
     net::{TcpListener, TcpStream},
     sync::{broadcast, mpsc, Mutex, RwLock},
-    time::timeout,
+    task::JoinSet,
+    time::{interval_at, timeout, Instant, Interval},
 };
 use tokio_tungstenite::{
-    accept_async, connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+    accept_hdr_async_with_config,
+    tungstenite::{
+        client::IntoClientRequest,
+        handshake::server::{Callback, ErrorResponse, Request, Response},
+        http::{
+            header::{ORIGIN, SEC_WEBSOCKET_PROTOCOL},
+            HeaderMap, HeaderValue, StatusCode,
+        },
+        protocol::{frame::coding::CloseCode, CloseFrame, WebSocketConfig},
+        Message,
+    },
+    MaybeTlsStream, WebSocketStream,
 };
 use url::Url;
 
 use crate::core::error::{McpError, McpResult};
-use crate::protocol::types::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
-use crate::transport::traits::{ConnectionState, ServerTransport, Transport, TransportConfig};
+use crate::protocol::types::{
+    JsonRpcBatch, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId, PARSE_ERROR,
+};
+use crate::transport::compression::header_lists;
+#[cfg(feature = "tls")]
+use crate::transport::tls::{websocket_connector, TlsServerConfig};
+use crate::transport::traits::{
+    answer_server_request, batch_response_text, deliver_notification, deliver_response,
+    dispatch_batch, parse_client_response, register_pending, request_client, response_text,
+    session_notification_handler, session_request_handler, ClientRequestHandler, ClientRequests,
+    ConnectionState, PendingResponses, RequestCallback, ServerSender, ServerTransport,
+    SessionClosedCallback, SessionHandlers, SessionNotificationCallback, SessionRequestCallback,
+    Transport, TransportConfig,
+};
+#[cfg(feature = "tls")]
+use tokio_tungstenite::connect_async_tls_with_config;
+#[cfg(not(feature = "tls"))]
+use tokio_tungstenite::connect_async_with_config;
+
+/// WebSocket subprotocol negotiated for MCP connections
+pub const MCP_SUBPROTOCOL: &str = "mcp";
+
+/// Build the WebSocket protocol configuration for a transport
+///
+/// Frames and messages above `max_message_size` are rejected by the
+/// WebSocket layer before they are buffered.
+fn websocket_config(config: &TransportConfig) -> WebSocketConfig {
+    WebSocketConfig {
+        max_message_size: config.max_message_size,
+        max_frame_size: config.max_message_size,
+        ..Default::default()
+    }
+}
+
+/// Refuse a configuration that enables compression
+///
+/// tokio-tungstenite 0.20 cannot negotiate `permessage-deflate`, so messages
+/// would go out uncompressed while the configuration says otherwise.
+fn check_no_compression(config: &TransportConfig) -> McpResult<()> {
+    if config.compression {
+        return Err(McpError::WebSocket(
+            "Compression is not supported by WebSocket transports".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check whether handshake headers offer the MCP subprotocol
+///
+/// # Returns
+/// `Some(true)` if it is offered, `Some(false)` if only other subprotocols are,
+/// or `None` if no subprotocol is offered
+fn offers_subprotocol(headers: &HeaderMap) -> Option<bool> {
+    let mut offered = headers
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .peekable();
+    offered.peek()?;
+    Some(offered.any(|value| header_lists(value, MCP_SUBPROTOCOL)))
+}
+
+/// Build the HTTP response refusing a WebSocket upgrade
+fn handshake_error(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Checks a client's upgrade request before a server accepts it
+///
+/// Refuses origins the configuration does not allow and negotiates the MCP
+/// subprotocol.
+struct HandshakeCheck<'a> {
+    config: &'a TransportConfig,
+}
+
+impl Callback for HandshakeCheck<'_> {
+    fn on_request(
+        self,
+        request: &Request,
+        mut response: Response,
+    ) -> Result<Response, ErrorResponse> {
+        let origin = request
+            .headers()
+            .get(ORIGIN)
+            .map(|origin| origin.to_str().unwrap_or_default());
+        if !self.config.allows_origin(origin) {
+            tracing::warn!("Refusing WebSocket upgrade from origin {:?}", origin);
+            return Err(handshake_error(
+                StatusCode::FORBIDDEN,
+                "Origin is not allowed",
+            ));
+        }
+
+        match offers_subprotocol(request.headers()) {
+            Some(true) => {
+                response.headers_mut().insert(
+                    SEC_WEBSOCKET_PROTOCOL,
+                    HeaderValue::from_static(MCP_SUBPROTOCOL),
+                );
+            }
+            Some(false) => {
+                return Err(handshake_error(
+                    StatusCode::BAD_REQUEST,
+                    "Unsupported WebSocket subprotocol",
+                ));
+            }
+            // Clients that predate subprotocol negotiation are still served
+            None => {}
+        }
+        Ok(response)
+    }
+}
+
+/// Close code and reason telling a client why a connection error ended its connection
+fn close_code(error: &tokio_tungstenite::tungstenite::Error) -> Option<(CloseCode, &'static str)> {
+    use tokio_tungstenite::tungstenite::Error;
+
+    match error {
+        Error::Capacity(_) => Some((CloseCode::Size, "Message exceeds the maximum size")),
+        Error::Protocol(_) | Error::AttackAttempt => {
+            Some((CloseCode::Protocol, "WebSocket protocol violation"))
+        }
+        Error::Utf8 => Some((CloseCode::Invalid, "Text message is not valid UTF-8")),
+        _ => None,
+    }
+}
 
 // ============================================================================
 // WebSocket Client Transport
 // ============================================================================
 
+/// Write half of a client connection, shared with the task answering the server's requests
+type ClientSink = Arc<Mutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>>;
+
 /// WebSocket transport for MCP clients
 ///
 /// This transport communicates with an MCP server via WebSocket connections,
 /// providing bidirectional real-time communication for both requests and notifications.
 pub struct WebSocketClientTransport {
-    ws_sender: Option<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
-    pending_requests: Arc<Mutex<HashMap<Value, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
+    ws_sender: Option<ClientSink>,
+    pending_requests: Arc<Mutex<PendingResponses>>,
+    request_handler: ClientRequestHandler,
     notification_receiver: Option<mpsc::UnboundedReceiver<JsonRpcNotification>>,
     config: TransportConfig,
     state: Arc<RwLock<ConnectionState>>,
@@ -61,8 +209,10 @@ impl WebSocketClientTransport {
     /// * `config` - Transport configuration
     ///
     /// # Returns
-    /// Result containing the transport or an error
+    /// Result containing the transport or an error, also returned if `config`
+    /// enables compression, which WebSocket transports do not support
     pub async fn with_config<S: AsRef<str>>(url: S, config: TransportConfig) -> McpResult<Self> {
+        check_no_compression(&config)?;
         let url_str = url.as_ref();
         let url_parsed = Url::parse(url_str)
             .map_err(|e| McpError::WebSocket(format!("Invalid WebSocket URL: {}", e)))?;
@@ -72,20 +222,46 @@ impl WebSocketClientTransport {
         // Connect to WebSocket with timeout
         let connect_timeout = Duration::from_millis(config.connect_timeout_ms.unwrap_or(30_000));
 
-        let (ws_stream, _) = timeout(connect_timeout, connect_async(&url_parsed))
+        let mut request = url_parsed
+            .as_str()
+            .into_client_request()
+            .map_err(|e| McpError::WebSocket(format!("Invalid WebSocket request: {}", e)))?;
+        request.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(MCP_SUBPROTOCOL),
+        );
+
+        #[cfg(feature = "tls")]
+        let connect = connect_async_tls_with_config(
+            request,
+            Some(websocket_config(&config)),
+            false,
+            websocket_connector(&config.root_certificates)?,
+        );
+        #[cfg(not(feature = "tls"))]
+        let connect = connect_async_with_config(request, Some(websocket_config(&config)), false);
+
+        let (ws_stream, _) = timeout(connect_timeout, connect)
             .await
             .map_err(|_| McpError::WebSocket("Connection timeout".to_string()))?
             .map_err(|e| McpError::WebSocket(format!("Failed to connect: {}", e)))?;
 
         let (ws_sender, ws_receiver) = ws_stream.split();
+        let ws_sender = Arc::new(Mutex::new(ws_sender));
 
         let pending_requests = Arc::new(Mutex::new(HashMap::new()));
+        let request_handler = ClientRequestHandler::default();
         let (notification_sender, notification_receiver) = mpsc::unbounded_channel();
         let state = Arc::new(RwLock::new(ConnectionState::Connected));
 
         // Start message handling task
         let message_handler = tokio::spawn(Self::handle_messages(
             ws_receiver,
+            ClientResponder {
+                sink: ws_sender.clone(),
+                handler: request_handler.clone(),
+                config: config.clone(),
+            },
             pending_requests.clone(),
             notification_sender,
             state.clone(),
@@ -94,6 +270,7 @@ impl WebSocketClientTransport {
         Ok(Self {
             ws_sender: Some(ws_sender),
             pending_requests,
+            request_handler,
             notification_receiver: Some(notification_receiver),
             config,
             state,
@@ -104,7 +281,8 @@ impl WebSocketClientTransport {
 
     async fn handle_messages(
         mut ws_receiver: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-        pending_requests: Arc<Mutex<HashMap<Value, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
+        responder: ClientResponder,
+        pending_requests: Arc<Mutex<PendingResponses>>,
         notification_sender: mpsc::UnboundedSender<JsonRpcNotification>,
         state: Arc<RwLock<ConnectionState>>,
     ) {
@@ -113,18 +291,28 @@ impl WebSocketClientTransport {
                 Ok(Message::Text(text)) => {
                     tracing::trace!("Received WebSocket message: {}", text);
 
-                    // Try to parse as response first
-                    if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(&text) {
+                    // Requests from the server parse as responses too, so check for them first
+                    if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(&text) {
+                        tokio::spawn(responder.clone().answer(request));
+                    }
+                    // Try to parse as a response or a batch of responses
+                    else if let Ok(responses) =
+                        serde_json::from_str::<JsonRpcBatch<JsonRpcResponse>>(&text)
+                    {
                         let mut pending = pending_requests.lock().await;
-                        if let Some(sender) = pending.remove(&response.id) {
-                            if let Err(_) = sender.send(response) {
-                                tracing::warn!("Failed to send response to waiting request");
+                        for response in responses.into_vec() {
+                            if let Some(sender) =
+                                response.id.as_ref().and_then(|id| pending.remove(id))
+                            {
+                                if let Err(_) = sender.send(response) {
+                                    tracing::warn!("Failed to send response to waiting request");
+                                }
+                            } else {
+                                tracing::warn!(
+                                    "Received response for unknown request ID: {:?}",
+                                    response.id
+                                );
                             }
-                        } else {
-                            tracing::warn!(
-                                "Received response for unknown request ID: {:?}",
-                                response.id
-                            );
                         }
                     }
                     // Try to parse as notification
@@ -166,12 +354,17 @@ impl WebSocketClientTransport {
             }
         }
 
+        // Nothing else will arrive, so fail outstanding requests right away
+        pending_requests.lock().await.clear();
+
         tracing::debug!("WebSocket message handler exiting");
     }
 
     async fn send_message(&mut self, message: Message) -> McpResult<()> {
-        if let Some(ref mut sender) = self.ws_sender {
+        if let Some(ref sender) = self.ws_sender {
             sender
+                .lock()
+                .await
                 .send(message)
                 .await
                 .map_err(|e| McpError::WebSocket(format!("Failed to send message: {}", e)))?;
@@ -185,18 +378,15 @@ impl WebSocketClientTransport {
 #[async_trait]
 impl Transport for WebSocketClientTransport {
     async fn send_request(&mut self, request: JsonRpcRequest) -> McpResult<JsonRpcResponse> {
-        let (sender, receiver) = tokio::sync::oneshot::channel();
+        let request_text =
+            serde_json::to_string(&request).map_err(|e| McpError::Serialization(e))?;
+        self.config.check_message_size(request_text.len())?;
 
         // Store the pending request
-        {
-            let mut pending = self.pending_requests.lock().await;
-            pending.insert(request.id.clone(), sender);
-        }
+        let receiver =
+            register_pending(&mut *self.pending_requests.lock().await, [&request.id])?.remove(0);
 
         // Send the request
-        let request_text =
-            serde_json::to_string(&request).map_err(|e| McpError::Serialization(e))?;
-
         tracing::trace!("Sending WebSocket request: {}", request_text);
 
         self.send_message(Message::Text(request_text)).await?;
@@ -212,9 +402,40 @@ impl Transport for WebSocketClientTransport {
         Ok(response)
     }
 
+    async fn send_batch(
+        &mut self,
+        requests: Vec<JsonRpcRequest>,
+    ) -> McpResult<Vec<JsonRpcResponse>> {
+        let batch_text = serde_json::to_string(&requests).map_err(McpError::serialization)?;
+        self.config.check_message_size(batch_text.len())?;
+
+        let ids: Vec<RequestId> = requests.iter().map(|request| request.id.clone()).collect();
+        let receivers = register_pending(&mut *self.pending_requests.lock().await, &ids)?;
+
+        tracing::trace!("Sending WebSocket batch: {}", batch_text);
+
+        self.send_message(Message::Text(batch_text)).await?;
+
+        // Wait for every response within a single timeout
+        let timeout_duration = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
+        let collect = async {
+            let mut responses = Vec::with_capacity(receivers.len());
+            for receiver in receivers {
+                responses.push(receiver.await?);
+            }
+            Ok::<_, tokio::sync::oneshot::error::RecvError>(responses)
+        };
+
+        timeout(timeout_duration, collect)
+            .await
+            .map_err(|_| McpError::WebSocket("Request timeout".to_string()))?
+            .map_err(|_| McpError::WebSocket("Response channel closed".to_string()))
+    }
+
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
         let notification_text =
             serde_json::to_string(&notification).map_err(|e| McpError::Serialization(e))?;
+        self.config.check_message_size(notification_text.len())?;
 
         tracing::trace!("Sending WebSocket notification: {}", notification_text);
 
@@ -241,8 +462,8 @@ impl Transport for WebSocketClientTransport {
         *self.state.write().await = ConnectionState::Closing;
 
         // Send close message
-        if let Some(ref mut sender) = self.ws_sender {
-            let _ = sender.send(Message::Close(None)).await;
+        if let Some(ref sender) = self.ws_sender {
+            let _ = sender.lock().await.send(Message::Close(None)).await;
         }
 
         // Abort message handler
@@ -258,6 +479,13 @@ impl Transport for WebSocketClientTransport {
         Ok(())
     }
 
+    fn set_request_handler(&mut self, handler: RequestCallback) {
+        *self
+            .request_handler
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(handler);
+    }
+
     fn is_connected(&self) -> bool {
         // We'd need to check the actual state here
         self.ws_sender.is_some()
@@ -268,16 +496,200 @@ impl Transport for WebSocketClientTransport {
     }
 }
 
+/// Answers the requests a server sends to a WebSocket client
+#[derive(Clone)]
+struct ClientResponder {
+    sink: ClientSink,
+    handler: ClientRequestHandler,
+    config: TransportConfig,
+}
+
+impl ClientResponder {
+    /// Answer a request from the server through the client's request handler
+    async fn answer(self, request: JsonRpcRequest) {
+        let message = answer_server_request(&self.handler, request, &self.config).await;
+        let result = match message {
+            Ok(text) => self
+                .sink
+                .lock()
+                .await
+                .send(Message::Text(text))
+                .await
+                .map_err(|e| McpError::WebSocket(format!("Failed to send message: {}", e))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to answer server request: {}", e);
+        }
+    }
+}
+
 // ============================================================================
 // WebSocket Server Transport
 // ============================================================================
 
+/// Wait for the next keep-alive tick, or forever if keep-alive is disabled
+async fn keep_alive_tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Byte stream a server-side WebSocket connection runs over, plain TCP or TLS
+trait ServerStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> ServerStream for T {}
+
+/// Number of messages queued for a client before it counts as not reading them
+const CLIENT_QUEUE_SIZE: usize = 64;
+
 /// Connection state for a WebSocket client
+///
+/// Messages for the client are queued to a task of its own that writes them to
+/// the socket, so a client that stops reading never holds up the others.
+#[derive(Clone)]
 struct WebSocketConnection {
-    sender: SplitSink<WebSocketStream<TcpStream>, Message>,
+    queue: mpsc::Sender<Message>,
+    requests: ClientRequests,
     id: String,
 }
 
+impl WebSocketConnection {
+    /// Queue a message for the client without waiting
+    ///
+    /// # Returns
+    /// Result indicating success, or an error if the client's queue is full or closed
+    fn queue(&self, message: Message) -> McpResult<()> {
+        self.queue.try_send(message).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => {
+                McpError::WebSocket(format!("Client {} is not reading its messages", self.id))
+            }
+            mpsc::error::TrySendError::Closed(_) => {
+                McpError::WebSocket(format!("Client {} is disconnected", self.id))
+            }
+        })
+    }
+
+    /// Queue a message for the client, waiting up to `write_timeout` for room
+    async fn send(&self, message: Message, write_timeout: Duration) -> McpResult<()> {
+        match timeout(write_timeout, self.queue.send(message)).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err(McpError::WebSocket(format!(
+                "Client {} is disconnected",
+                self.id
+            ))),
+            Err(_) => Err(McpError::WebSocket(format!(
+                "Client {} is not reading its messages",
+                self.id
+            ))),
+        }
+    }
+}
+
+/// Write a client's queued messages to its socket
+///
+/// Stops once the queue is closed, a close frame has been written, or a write
+/// does not complete within `write_timeout` because the client stopped reading.
+async fn write_messages(
+    mut sink: SplitSink<WebSocketStream<Box<dyn ServerStream>>, Message>,
+    mut queue: mpsc::Receiver<Message>,
+    write_timeout: Duration,
+    client_id: String,
+) {
+    while let Some(message) = queue.recv().await {
+        let closing = matches!(message, Message::Close(_));
+        match timeout(write_timeout, sink.send(message)).await {
+            Ok(Ok(())) if !closing => {}
+            Ok(Ok(())) => break,
+            Ok(Err(e)) => {
+                tracing::debug!("Failed to write to client {}: {}", client_id, e);
+                break;
+            }
+            Err(_) => {
+                tracing::warn!("Client {} stopped reading, closing connection", client_id);
+                break;
+            }
+        }
+    }
+}
+
+/// Connected clients of a WebSocket server transport, keyed by client ID
+type Clients = Arc<RwLock<HashMap<String, WebSocketConnection>>>;
+
+/// Sends messages to the clients of a WebSocket server transport
+struct WebSocketServerSender {
+    clients: Clients,
+    config: TransportConfig,
+}
+
+impl WebSocketServerSender {
+    /// Look up a connected client
+    async fn client(&self, client_id: &str) -> McpResult<WebSocketConnection> {
+        let client = self.clients.read().await.get(client_id).cloned();
+        client.ok_or_else(|| {
+            McpError::connection(format!("WebSocket client {} is not connected", client_id))
+        })
+    }
+
+    /// Time to wait for room in a client's queue before giving up on it
+    fn write_timeout(&self) -> Duration {
+        Duration::from_millis(self.config.write_timeout_ms.unwrap_or(30_000))
+    }
+}
+
+#[async_trait]
+impl ServerSender for WebSocketServerSender {
+    async fn send_notification(
+        &self,
+        session_id: Option<&str>,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        let notification_text =
+            serde_json::to_string(&notification).map_err(McpError::serialization)?;
+        self.config.check_message_size(notification_text.len())?;
+
+        if let Some(client_id) = session_id {
+            let client = self.client(client_id).await?;
+            let message = Message::Text(notification_text);
+            return client.send(message, self.write_timeout()).await;
+        }
+
+        // Clients whose queue is full miss the notification rather than holding up the rest
+        let clients: Vec<_> = self.clients.read().await.values().cloned().collect();
+        for client in clients {
+            if let Err(e) = client.queue(Message::Text(notification_text.clone())) {
+                tracing::warn!("Failed to send notification: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_request(
+        &self,
+        session_id: Option<&str>,
+        request: JsonRpcRequest,
+    ) -> McpResult<JsonRpcResponse> {
+        let client_id = session_id.ok_or_else(|| {
+            McpError::protocol("Requests to WebSocket clients need the session of the client")
+        })?;
+        let client = self.client(client_id).await?;
+
+        let request_text = serde_json::to_string(&request).map_err(McpError::serialization)?;
+        self.config.check_message_size(request_text.len())?;
+        let message = Message::Text(request_text);
+
+        let wait = Duration::from_millis(self.config.read_timeout_ms.unwrap_or(60_000));
+        request_client(&client.requests, request, wait, |_| {
+            client.send(message, self.write_timeout())
+        })
+        .await
+    }
+}
+
 /// WebSocket transport for MCP servers
 ///
 /// This transport serves MCP requests over WebSocket connections,
@@ -285,21 +697,15 @@ struct WebSocketConnection {
 pub struct WebSocketServerTransport {
     bind_addr: String,
     config: TransportConfig,
-    clients: Arc<RwLock<HashMap<String, WebSocketConnection>>>,
-    request_handler: Arc<
-        RwLock<
-            Option<
-                Arc<
-                    dyn Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>
-                        + Send
-                        + Sync,
-                >,
-            >,
-        >,
-    >,
+    clients: Clients,
+    request_handler: Arc<RwLock<Option<SessionRequestCallback>>>,
+    notification_handler: Arc<RwLock<Option<SessionNotificationCallback>>>,
+    closed_handler: Arc<RwLock<Option<SessionClosedCallback>>>,
     server_handle: Option<tokio::task::JoinHandle<()>>,
     running: Arc<RwLock<bool>>,
     shutdown_sender: Option<broadcast::Sender<()>>,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsServerConfig>,
 }
 
 impl WebSocketServerTransport {
@@ -330,12 +736,29 @@ impl WebSocketServerTransport {
             config,
             clients: Arc::new(RwLock::new(HashMap::new())),
             request_handler: Arc::new(RwLock::new(None)),
+            notification_handler: Arc::new(RwLock::new(None)),
+            closed_handler: Arc::new(RwLock::new(None)),
             server_handle: None,
             running: Arc::new(RwLock::new(false)),
             shutdown_sender: Some(shutdown_sender),
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
+    /// Serve `wss://` connections with the given certificate instead of plain `ws://`
+    ///
+    /// # Arguments
+    /// * `tls` - Certificate configuration; keep a clone to reload the certificate later
+    ///
+    /// # Returns
+    /// The transport, accepting TLS connections once started
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsServerConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
     /// Set the request handler function
     ///
     /// # Arguments
@@ -346,30 +769,83 @@ impl WebSocketServerTransport {
             + Send
             + Sync
             + 'static,
+    {
+        self.set_session_request_handler(move |_, request| handler(request))
+            .await;
+    }
+
+    /// Set a request handler function that is told which connection a request came from
+    ///
+    /// Every client connection is a separate session, identified by an ID that the
+    /// session notification and closed handlers receive as well.
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming requests, given their session ID
+    pub async fn set_session_request_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(Option<String>, JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>
+            + Send
+            + Sync
+            + 'static,
     {
         let mut request_handler = self.request_handler.write().await;
         *request_handler = Some(Arc::new(handler));
     }
 
+    /// Set the notification handler function
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming notifications
+    pub async fn set_notification_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(JsonRpcNotification) + Send + Sync + 'static,
+    {
+        self.set_session_notification_handler(move |_, notification| handler(notification))
+            .await;
+    }
+
+    /// Set a notification handler function that is told which connection a notification came from
+    ///
+    /// # Arguments
+    /// * `handler` - Function that processes incoming notifications, given their session ID
+    pub async fn set_session_notification_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(Option<String>, JsonRpcNotification) + Send + Sync + 'static,
+    {
+        let mut notification_handler = self.notification_handler.write().await;
+        *notification_handler = Some(Arc::new(handler));
+    }
+
+    /// Set the function called with the session ID of every connection that closes
+    ///
+    /// # Arguments
+    /// * `handler` - Function that tears down the session of a closed connection
+    pub async fn set_session_closed_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        let mut closed_handler = self.closed_handler.write().await;
+        *closed_handler = Some(Arc::new(handler));
+    }
+
     async fn handle_client_connection(
-        stream: TcpStream,
-        clients: Arc<RwLock<HashMap<String, WebSocketConnection>>>,
-        request_handler: Arc<
-            RwLock<
-                Option<
-                    Arc<
-                        dyn Fn(JsonRpcRequest) -> tokio::sync::oneshot::Receiver<JsonRpcResponse>
-                            + Send
-                            + Sync,
-                    >,
-                >,
-            >,
-        >,
+        stream: Box<dyn ServerStream>,
+        clients: Clients,
+        request_handler: Arc<RwLock<Option<SessionRequestCallback>>>,
+        notification_handler: Arc<RwLock<Option<SessionNotificationCallback>>>,
+        closed_handler: Arc<RwLock<Option<SessionClosedCallback>>>,
         mut shutdown_receiver: broadcast::Receiver<()>,
+        config: TransportConfig,
     ) {
         let client_id = uuid::Uuid::new_v4().to_string();
 
-        let ws_stream = match accept_async(stream).await {
+        let ws_stream = match accept_hdr_async_with_config(
+            stream,
+            HandshakeCheck { config: &config },
+            Some(websocket_config(&config)),
+        )
+        .await
+        {
             Ok(ws) => ws,
             Err(e) => {
                 tracing::error!("Failed to accept WebSocket connection: {}", e);
@@ -379,66 +855,149 @@ impl WebSocketServerTransport {
 
         tracing::info!("New WebSocket client connected: {}", client_id);
 
-        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+        let (ws_sender, mut ws_receiver) = ws_stream.split();
+        let write_timeout = Duration::from_millis(config.write_timeout_ms.unwrap_or(30_000));
+        let (queue, queued) = mpsc::channel(CLIENT_QUEUE_SIZE);
+        let mut writer = tokio::spawn(write_messages(
+            ws_sender,
+            queued,
+            write_timeout,
+            client_id.clone(),
+        ));
+        let connection = WebSocketConnection {
+            queue,
+            requests: ClientRequests::default(),
+            id: client_id.clone(),
+        };
 
         // Add client to the connections map
-        {
-            let mut clients_guard = clients.write().await;
-            clients_guard.insert(
-                client_id.clone(),
-                WebSocketConnection {
-                    sender: ws_sender,
-                    id: client_id.clone(),
-                },
-            );
-        }
+        clients
+            .write()
+            .await
+            .insert(client_id.clone(), connection.clone());
+
+        // Requests being answered for this client, aborted when it goes away
+        let mut tasks = JoinSet::new();
+        // The client is pinged periodically and reaped once too many pings go unanswered
+        let mut keep_alive = config.keep_alive_ms.filter(|ms| *ms > 0).map(|ms| {
+            let period = Duration::from_millis(ms);
+            interval_at(Instant::now() + period, period)
+        });
+        let mut missed_pings = 0;
 
         // Handle messages from this client
         loop {
             tokio::select! {
                 message = ws_receiver.next() => {
+                    // Any message shows that the client is still there
+                    if let Some(Ok(_)) = message {
+                        missed_pings = 0;
+                    }
                     match message {
                         Some(Ok(Message::Text(text))) => {
                             tracing::trace!("Received message from {}: {}", client_id, text);
 
-                            // Try to parse as request
-                            if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(&text) {
-                                let handler_guard = request_handler.read().await;
-                                if let Some(ref handler) = *handler_guard {
-                                    let response_rx = handler(request.clone());
-                                    drop(handler_guard);
-
-                                    match response_rx.await {
-                                        Ok(response) => {
-                                            let response_text = match serde_json::to_string(&response) {
-                                                Ok(text) => text,
-                                                Err(e) => {
-                                                    tracing::error!("Failed to serialize response: {}", e);
-                                                    continue;
-                                                }
-                                            };
-
-                                            // Send response back to client
-                                            let mut clients_guard = clients.write().await;
-                                            if let Some(client) = clients_guard.get_mut(&client_id) {
-                                                if let Err(e) = client.sender.send(Message::Text(response_text)).await {
-                                                    tracing::error!("Failed to send response to client {}: {}", client_id, e);
-                                                    break;
-                                                }
+                            // Requests are answered in the background so that later messages,
+                            // such as the cancellation of a request, are read in the meantime
+                            let connection = connection.clone();
+                            let config = config.clone();
+
+                            // Dispatch a batch of messages concurrently and answer them together
+                            if text.trim_start().starts_with('[') {
+                                let entries = match serde_json::from_str::<Vec<Value>>(&text) {
+                                    Ok(entries) => entries,
+                                    Err(e) => {
+                                        tracing::warn!("Failed to parse batch from client {}: {}", client_id, text);
+                                        let response = JsonRpcResponse::error(None, PARSE_ERROR, e.to_string(), None);
+                                        tasks.spawn(async move {
+                                            match response_text(response, &config) {
+                                                Ok(text) => Self::send_response(&connection, Message::Text(text), write_timeout).await,
+                                                Err(e) => tracing::error!("Failed to serialize parse error: {}", e),
                                             }
+                                        });
+                                        continue;
+                                    }
+                                };
+                                let handler = session_request_handler(
+                                    request_handler.read().await.clone(),
+                                    Some(&client_id),
+                                );
+                                let notify = session_notification_handler(
+                                    notification_handler.read().await.clone(),
+                                    Some(&client_id),
+                                );
+
+                                tasks.spawn(async move {
+                                    let dispatch = |request: JsonRpcRequest| match &handler {
+                                        Some(handler) => handler(request),
+                                        None => {
+                                            let (sender, receiver) = tokio::sync::oneshot::channel();
+                                            let _ = sender.send(JsonRpcResponse::error(
+                                                request.id,
+                                                crate::protocol::types::METHOD_NOT_FOUND,
+                                                "No request handler configured".to_string(),
+                                                None,
+                                            ));
+                                            receiver
                                         }
-                                        Err(_) => {
-                                            tracing::error!("Request handler channel closed for client {}", client_id);
+                                    };
+                                    let notify = |notification| deliver_notification(notify.as_ref(), notification);
+                                    let respond = |response| deliver_response(&connection.requests, response);
+
+                                    let batch = dispatch_batch(entries, dispatch, notify, respond).await;
+                                    let response_text = match batch {
+                                        Some(JsonRpcBatch::Single(response)) => response_text(response, &config),
+                                        Some(JsonRpcBatch::Batch(responses)) => {
+                                            batch_response_text(responses, config.max_message_size)
                                         }
+                                        None => return,
+                                    };
+                                    match response_text {
+                                        Ok(text) => Self::send_response(&connection, Message::Text(text), write_timeout).await,
+                                        Err(e) => tracing::error!("Failed to serialize batch response: {}", e),
                                     }
-                                } else {
+                                });
+                                continue;
+                            }
+
+                            // Try to parse as request
+                            if let Ok(request) = serde_json::from_str::<JsonRpcRequest>(&text) {
+                                let handler = session_request_handler(
+                                    request_handler.read().await.clone(),
+                                    Some(&client_id),
+                                );
+                                let Some(handler) = handler else {
                                     tracing::warn!("No request handler configured for client {}", client_id);
-                                }
+                                    continue;
+                                };
+                                let response_rx = handler(request);
+
+                                tasks.spawn(async move {
+                                    let Ok(response) = response_rx.await else {
+                                        tracing::error!("Request handler channel closed for client {}", connection.id);
+                                        return;
+                                    };
+                                    match response_text(response, &config) {
+                                        Ok(text) => Self::send_response(&connection, Message::Text(text), write_timeout).await,
+                                        Err(e) => tracing::error!("Failed to serialize response: {}", e),
+                                    }
+                                });
                             }
                             // Handle notifications (no response needed)
-                            else if let Ok(_notification) = serde_json::from_str::<JsonRpcNotification>(&text) {
+                            else if let Ok(notification) = serde_json::from_str::<JsonRpcNotification>(&text) {
                                 tracing::trace!("Received notification from client {}", client_id);
-                                // Notifications don't require responses
+                                let handler = session_notification_handler(
+                                    notification_handler.read().await.clone(),
+                                    Some(&client_id),
+                                );
+                                deliver_notification(handler.as_ref(), notification);
+                            }
+                            // Responses to requests the server sent to the client
+                            else if let Some(response) = serde_json::from_str::<Value>(&text)
+                                .ok()
+                                .and_then(|message| parse_client_response(&message))
+                            {
+                                deliver_response(&connection.requests, response);
                             } else {
                                 tracing::warn!("Failed to parse message from client {}: {}", client_id, text);
                             }
@@ -449,19 +1008,19 @@ impl WebSocketServerTransport {
                         }
                         Some(Ok(Message::Ping(data))) => {
                             tracing::trace!("Received ping from client {}", client_id);
-                            let mut clients_guard = clients.write().await;
-                            if let Some(client) = clients_guard.get_mut(&client_id) {
-                                if let Err(e) = client.sender.send(Message::Pong(data)).await {
-                                    tracing::error!("Failed to send pong to client {}: {}", client_id, e);
-                                    break;
-                                }
+                            if let Err(e) = connection.queue(Message::Pong(data)) {
+                                tracing::debug!("Failed to send pong: {}", e);
                             }
                         }
                         Some(Ok(Message::Pong(_))) => {
                             tracing::trace!("Received pong from client {}", client_id);
                         }
                         Some(Ok(Message::Binary(_))) => {
-                            tracing::warn!("Received unexpected binary message from client {}", client_id);
+                            // JSON-RPC is carried in text messages
+                            let (code, reason) = (CloseCode::Unsupported, "Binary messages are not supported");
+                            tracing::warn!("Closing connection of client {}: {}", client_id, reason);
+                            Self::close_client(&connection, code, reason);
+                            break;
                         }
                         Some(Ok(Message::Frame(_))) => {
                             tracing::trace!("Received WebSocket frame from client {} (internal)", client_id);
@@ -469,6 +1028,9 @@ impl WebSocketServerTransport {
                         }
                         Some(Err(e)) => {
                             tracing::error!("WebSocket error for client {}: {}", client_id, e);
+                            if let Some((code, reason)) = close_code(&e) {
+                                Self::close_client(&connection, code, reason);
+                            }
                             break;
                         }
                         None => {
@@ -477,6 +1039,27 @@ impl WebSocketServerTransport {
                         }
                     }
                 }
+                _ = keep_alive_tick(&mut keep_alive) => {
+                    if missed_pings >= config.max_missed_pings {
+                        tracing::warn!(
+                            "Client {} missed {} keep-alive pings, closing connection",
+                            client_id,
+                            missed_pings
+                        );
+                        let _ = connection.queue(Message::Close(None));
+                        break;
+                    }
+                    missed_pings += 1;
+                    // A ping that does not fit in the queue counts as missed as well
+                    if let Err(e) = connection.queue(Message::Ping(Vec::new())) {
+                        tracing::debug!("Failed to send ping: {}", e);
+                    }
+                }
+                _ = &mut writer => {
+                    tracing::info!("Connection of client {} can no longer be written to", client_id);
+                    break;
+                }
+                Some(_) = tasks.join_next() => {}
                 _ = shutdown_receiver.recv() => {
                     tracing::info!("Shutting down connection for client {}", client_id);
                     break;
@@ -485,18 +1068,90 @@ impl WebSocketServerTransport {
         }
 
         // Remove client from connections
-        {
-            let mut clients_guard = clients.write().await;
-            clients_guard.remove(&client_id);
+        clients.write().await.remove(&client_id);
+
+        // Stop answering the client; closing its session below cancels the requests
+        // still being handled, without a `notifications/cancelled` for each
+        tasks.shutdown().await;
+
+        // Fail the requests still waiting for the client, then let the writer flush
+        // a pending close frame and drop the socket
+        connection
+            .requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        drop(connection);
+        if !writer.is_finished() && timeout(write_timeout, &mut writer).await.is_err() {
+            writer.abort();
+        }
+        if let Some(handler) = closed_handler.read().await.clone() {
+            handler(client_id.clone());
         }
 
         tracing::info!("Client {} connection handler exiting", client_id);
     }
+
+    /// Get the IDs of the connected clients
+    ///
+    /// A client's ID is also the ID of its session, as passed to the session handlers.
+    pub async fn client_ids(&self) -> Vec<String> {
+        self.clients.read().await.keys().cloned().collect()
+    }
+
+    /// Send a notification to a single connected client
+    ///
+    /// # Arguments
+    /// * `client_id` - ID of the client's connection
+    /// * `notification` - The notification to send
+    ///
+    /// # Returns
+    /// Result indicating success, or an error if the client is not connected
+    pub async fn send_notification_to(
+        &self,
+        client_id: &str,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        self.websocket_sender()
+            .send_notification(Some(client_id), notification)
+            .await
+    }
+
+    /// Build a sender sharing the transport's connections
+    fn websocket_sender(&self) -> WebSocketServerSender {
+        WebSocketServerSender {
+            clients: self.clients.clone(),
+            config: self.config.clone(),
+        }
+    }
+
+    /// Close a client's connection, telling it why with a close code
+    fn close_client(client: &WebSocketConnection, code: CloseCode, reason: &str) {
+        let frame = CloseFrame {
+            code,
+            reason: reason.to_string().into(),
+        };
+        if let Err(e) = client.queue(Message::Close(Some(frame))) {
+            tracing::debug!("Failed to close connection: {}", e);
+        }
+    }
+
+    /// Send a response to a connected client
+    async fn send_response(
+        client: &WebSocketConnection,
+        message: Message,
+        write_timeout: Duration,
+    ) {
+        if let Err(e) = client.send(message, write_timeout).await {
+            tracing::error!("Failed to send response: {}", e);
+        }
+    }
 }
 
 #[async_trait]
 impl ServerTransport for WebSocketServerTransport {
     async fn start(&mut self) -> McpResult<()> {
+        check_no_compression(&self.config)?;
         tracing::info!("Starting WebSocket server on {}", self.bind_addr);
 
         let listener = TcpListener::bind(&self.bind_addr).await.map_err(|e| {
@@ -505,8 +1160,13 @@ impl ServerTransport for WebSocketServerTransport {
 
         let clients = self.clients.clone();
         let request_handler = self.request_handler.clone();
+        let notification_handler = self.notification_handler.clone();
+        let closed_handler = self.closed_handler.clone();
         let running = self.running.clone();
+        let config = self.config.clone();
         let shutdown_sender = self.shutdown_sender.as_ref().unwrap().clone();
+        #[cfg(feature = "tls")]
+        let tls = self.tls.clone();
 
         *running.write().await = true;
 
@@ -520,12 +1180,51 @@ impl ServerTransport for WebSocketServerTransport {
                             Ok((stream, addr)) => {
                                 tracing::debug!("New connection from: {}", addr);
 
-                                tokio::spawn(Self::handle_client_connection(
-                                    stream,
-                                    clients.clone(),
-                                    request_handler.clone(),
-                                    shutdown_sender.subscribe(),
-                                ));
+                                #[cfg(feature = "tls")]
+                                let tls = tls.clone();
+                                let clients = clients.clone();
+                                let request_handler = request_handler.clone();
+                                let notification_handler = notification_handler.clone();
+                                let closed_handler = closed_handler.clone();
+                                let shutdown_receiver = shutdown_sender.subscribe();
+                                let config = config.clone();
+                                tokio::spawn(async move {
+                                    // The TLS handshake runs on the connection's own task so
+                                    // slow clients cannot hold up the accept loop
+                                    #[cfg(feature = "tls")]
+                                    let stream: Box<dyn ServerStream> = match tls {
+                                        Some(tls) => {
+                                            let handshake_timeout = Duration::from_millis(
+                                                config.connect_timeout_ms.unwrap_or(30_000),
+                                            );
+                                            match tls.accept(stream, handshake_timeout).await {
+                                                Ok(stream) => Box::new(stream),
+                                                Err(e) => {
+                                                    tracing::debug!(
+                                                        "Dropping connection from {}: {}",
+                                                        addr,
+                                                        e
+                                                    );
+                                                    return;
+                                                }
+                                            }
+                                        }
+                                        None => Box::new(stream),
+                                    };
+                                    #[cfg(not(feature = "tls"))]
+                                    let stream: Box<dyn ServerStream> = Box::new(stream);
+
+                                    Self::handle_client_connection(
+                                        stream,
+                                        clients,
+                                        request_handler,
+                                        notification_handler,
+                                        closed_handler,
+                                        shutdown_receiver,
+                                        config,
+                                    )
+                                    .await
+                                });
                             }
                             Err(e) => {
                                 tracing::error!("Failed to accept connection: {}", e);
@@ -553,7 +1252,7 @@ impl ServerTransport for WebSocketServerTransport {
         let handler_guard = self.request_handler.read().await;
 
         if let Some(ref handler) = *handler_guard {
-            let response_rx = handler(request);
+            let response_rx = handler(None, request);
             drop(handler_guard);
 
             match response_rx.await {
@@ -565,7 +1264,7 @@ impl ServerTransport for WebSocketServerTransport {
         } else {
             Ok(JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
-                id: request.id,
+                id: Some(request.id),
                 result: None,
                 error: Some(crate::protocol::types::JsonRpcError {
                     code: crate::protocol::types::METHOD_NOT_FOUND,
@@ -577,29 +1276,27 @@ impl ServerTransport for WebSocketServerTransport {
     }
 
     async fn send_notification(&mut self, notification: JsonRpcNotification) -> McpResult<()> {
-        let notification_text =
-            serde_json::to_string(&notification).map_err(|e| McpError::Serialization(e))?;
-
-        let mut clients_guard = self.clients.write().await;
-        let mut disconnected_clients = Vec::new();
+        self.websocket_sender()
+            .send_notification(None, notification)
+            .await
+    }
 
-        for (client_id, client) in clients_guard.iter_mut() {
-            if let Err(e) = client
-                .sender
-                .send(Message::Text(notification_text.clone()))
-                .await
-            {
-                tracing::error!("Failed to send notification to client {}: {}", client_id, e);
-                disconnected_clients.push(client_id.clone());
-            }
-        }
+    async fn send_session_notification(
+        &mut self,
+        session_id: &str,
+        notification: JsonRpcNotification,
+    ) -> McpResult<()> {
+        self.send_notification_to(session_id, notification).await
+    }
 
-        // Remove disconnected clients
-        for client_id in disconnected_clients {
-            clients_guard.remove(&client_id);
-        }
+    async fn set_handlers(&mut self, handlers: SessionHandlers) {
+        *self.request_handler.write().await = Some(handlers.request);
+        *self.notification_handler.write().await = Some(handlers.notification);
+        *self.closed_handler.write().await = Some(handlers.closed);
+    }
 
-        Ok(())
+    fn sender(&self) -> Option<Arc<dyn ServerSender>> {
+        Some(Arc::new(self.websocket_sender()))
     }
 
     async fn stop(&mut self) -> McpResult<()> {
@@ -619,11 +1316,10 @@ impl ServerTransport for WebSocketServerTransport {
 
         // Close all client connections
         let mut clients_guard = self.clients.write().await;
-        for (client_id, client) in clients_guard.iter_mut() {
+        for (client_id, client) in clients_guard.drain() {
             tracing::debug!("Closing connection for client {}", client_id);
-            let _ = client.sender.send(Message::Close(None)).await;
+            let _ = client.queue(Message::Close(None));
         }
-        clients_guard.clear();
 
         Ok(())
     }
@@ -641,6 +1337,7 @@ impl ServerTransport for WebSocketServerTransport {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::types::INTERNAL_ERROR;
     use serde_json::json;
 
     #[test]
@@ -652,14 +1349,425 @@ mod tests {
 
     #[test]
     fn test_websocket_server_with_config() {
-        let mut config = TransportConfig::default();
-        config.max_message_size = Some(64 * 1024);
+        let config = TransportConfig {
+            max_message_size: Some(64 * 1024),
+            ..Default::default()
+        };
 
         let transport = WebSocketServerTransport::with_config("0.0.0.0:9090", config);
         assert_eq!(transport.bind_addr, "0.0.0.0:9090");
         assert_eq!(transport.config.max_message_size, Some(64 * 1024));
     }
 
+    #[test]
+    fn test_websocket_config_applies_message_size() {
+        let config = TransportConfig {
+            max_message_size: Some(1024),
+            ..Default::default()
+        };
+
+        let ws_config = websocket_config(&config);
+        assert_eq!(ws_config.max_message_size, Some(1024));
+        assert_eq!(ws_config.max_frame_size, Some(1024));
+    }
+
+    #[test]
+    fn test_websocket_server_replaces_oversized_response() {
+        let config = TransportConfig {
+            max_message_size: Some(64),
+            ..Default::default()
+        };
+
+        let response = JsonRpcResponse::success(RequestId::from(3), "x".repeat(128)).unwrap();
+        let text = response_text(response, &config).unwrap();
+        let response: JsonRpcResponse = serde_json::from_str(&text).unwrap();
+        assert_eq!(response.id, Some(RequestId::from(3)));
+        assert_eq!(response.error.unwrap().code, INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_compression_not_negotiated() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            compression: true,
+            ..Default::default()
+        };
+        let url = format!("ws://127.0.0.1:{}", port);
+
+        // Transports configured for compression refuse to run without it
+        let mut server =
+            WebSocketServerTransport::with_config(format!("127.0.0.1:{}", port), config.clone());
+        assert!(matches!(server.start().await, Err(McpError::WebSocket(_))));
+        let client = WebSocketClientTransport::with_config(&url, config).await;
+        assert!(matches!(client, Err(McpError::WebSocket(_))));
+
+        // Offered extensions are declined
+        let mut server = WebSocketServerTransport::new(format!("127.0.0.1:{}", port));
+        server.start().await.unwrap();
+        let mut request = url.as_str().into_client_request().unwrap();
+        request.headers_mut().insert(
+            tokio_tungstenite::tungstenite::http::header::SEC_WEBSOCKET_EXTENSIONS,
+            HeaderValue::from_static("permessage-deflate"),
+        );
+        let (_raw, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+        assert!(response.headers().get("sec-websocket-extensions").is_none());
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_reaps_unresponsive_client() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            keep_alive_ms: Some(20),
+            max_missed_pings: 2,
+            ..Default::default()
+        };
+
+        let mut server =
+            WebSocketServerTransport::with_config(format!("127.0.0.1:{}", port), config);
+        // Requests are never answered, so they are still in flight when the client is reaped
+        let unanswered = Arc::new(std::sync::Mutex::new(Vec::new()));
+        server
+            .set_request_handler(move |_| {
+                let (sender, receiver) = tokio::sync::oneshot::channel::<JsonRpcResponse>();
+                unanswered.lock().unwrap().push(sender);
+                receiver
+            })
+            .await;
+        let (notification_tx, mut notification_rx) = mpsc::unbounded_channel();
+        server
+            .set_session_notification_handler(move |session_id, notification| {
+                let _ = notification_tx.send((session_id, notification));
+            })
+            .await;
+        let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+        server
+            .set_session_closed_handler(move |session_id| {
+                let _ = closed_tx.send(session_id);
+            })
+            .await;
+        server.start().await.unwrap();
+
+        // A client that sends a request and then stops reading never answers pings
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        let request = JsonRpcRequest::new(RequestId::from(7), "slow".to_string(), None::<Value>);
+        let text = serde_json::to_string(&request.unwrap()).unwrap();
+        client.send(Message::Text(text)).await.unwrap();
+
+        let session_id = timeout(Duration::from_secs(5), closed_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(server.clients.read().await.is_empty());
+        assert!(!session_id.is_empty());
+
+        // Closing the session cancels its requests, so none is cancelled on its own
+        assert!(notification_rx.try_recv().is_err());
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_answers_invalid_batch() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = WebSocketServerTransport::new(format!("127.0.0.1:{}", port));
+        server.start().await.unwrap();
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        client
+            .send(Message::Text("[{\"jsonrpc\": \"2.0\",".to_string()))
+            .await
+            .unwrap();
+
+        let Some(Ok(Message::Text(text))) = timeout(Duration::from_secs(2), client.next())
+            .await
+            .unwrap()
+        else {
+            panic!("Expected a parse error");
+        };
+        let response: JsonRpcResponse = serde_json::from_str(&text).unwrap();
+        assert_eq!(response.id, None);
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_client_that_stops_reading() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            write_timeout_ms: Some(200),
+            keep_alive_ms: None,
+            ..Default::default()
+        };
+        let mut server =
+            WebSocketServerTransport::with_config(format!("127.0.0.1:{}", port), config);
+        server.start().await.unwrap();
+
+        // Flood a client that never reads until its socket and queue are full
+        let url = format!("ws://127.0.0.1:{}", port);
+        let (_stalled, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+        let stalled_id = loop {
+            if let Some(id) = server.client_ids().await.pop() {
+                break id;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        let payload = "x".repeat(100_000);
+        for _ in 0..200 {
+            let notification = JsonRpcNotification::new(
+                "notifications/message".to_string(),
+                Some(json!({"level": "info", "data": payload})),
+            );
+            timeout(
+                Duration::from_secs(1),
+                server.send_notification(notification.unwrap()),
+            )
+            .await
+            .expect("a stalled client must not block broadcasts")
+            .unwrap();
+        }
+
+        // Other clients still connect and are written to
+        let (mut client, _) = timeout(
+            Duration::from_secs(2),
+            tokio_tungstenite::connect_async(&url),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        // The stalled client is dropped once a write to it times out
+        let id = loop {
+            if let [id] = server.client_ids().await.as_slice() {
+                if *id != stalled_id {
+                    break id.clone();
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let notification =
+            JsonRpcNotification::new("notifications/progress".to_string(), None::<Value>);
+        server
+            .send_notification_to(&id, notification.unwrap())
+            .await
+            .unwrap();
+        let Some(Ok(Message::Text(text))) = timeout(Duration::from_secs(2), client.next())
+            .await
+            .unwrap()
+        else {
+            panic!("expected a notification");
+        };
+        assert!(text.contains("notifications/progress"));
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_targeted_notification() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut server = WebSocketServerTransport::new(format!("127.0.0.1:{}", port));
+        server.start().await.unwrap();
+
+        // Connect the clients one at a time to learn which ID belongs to which
+        let url = format!("ws://127.0.0.1:{}", port);
+        let mut clients = Vec::new();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let (client, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+            clients.push(client);
+            let id = loop {
+                let known = server.client_ids().await;
+                if let Some(id) = known.into_iter().find(|id| !ids.contains(id)) {
+                    break id;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            };
+            ids.push(id);
+        }
+
+        for (index, id) in ids.iter().enumerate() {
+            let notification = JsonRpcNotification::new(
+                "notifications/progress".to_string(),
+                Some(json!({"progressToken": index, "progress": 1})),
+            );
+            server
+                .send_notification_to(id, notification.unwrap())
+                .await
+                .unwrap();
+        }
+
+        // Each client receives only the notification addressed to it
+        for (index, client) in clients.iter_mut().enumerate() {
+            let Some(Ok(Message::Text(text))) = client.next().await else {
+                panic!("expected a notification");
+            };
+            let notification: JsonRpcNotification = serde_json::from_str(&text).unwrap();
+            assert_eq!(notification.params.unwrap()["progressToken"], json!(index));
+        }
+
+        let notification =
+            JsonRpcNotification::new("notifications/progress".to_string(), None::<Value>);
+        assert!(server
+            .send_notification_to("unknown", notification.unwrap())
+            .await
+            .is_err());
+
+        server.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn test_websocket_tls_certificate_reload() {
+        let self_signed = || {
+            let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+            (
+                cert.serialize_pem().unwrap(),
+                cert.serialize_private_key_pem(),
+            )
+        };
+        let trusting = |cert: &str| TransportConfig {
+            root_certificates: vec![cert.as_bytes().to_vec()],
+            ..Default::default()
+        };
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let (old_cert, old_key) = self_signed();
+        let tls = TlsServerConfig::from_pem(old_cert.as_bytes(), old_key.as_bytes()).unwrap();
+        let mut server =
+            WebSocketServerTransport::new(format!("127.0.0.1:{}", port)).with_tls(tls.clone());
+        server
+            .set_request_handler(|request| {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                let _ = sender.send(JsonRpcResponse::success(request.id, request.method).unwrap());
+                receiver
+            })
+            .await;
+        server.start().await.unwrap();
+
+        let url = format!("wss://localhost:{}", port);
+        assert!(
+            WebSocketClientTransport::new(format!("ws://localhost:{}", port))
+                .await
+                .is_err()
+        );
+        let mut client = WebSocketClientTransport::with_config(&url, trusting(&old_cert))
+            .await
+            .unwrap();
+
+        let (new_cert, new_key) = self_signed();
+        tls.reload_from_pem(new_cert.as_bytes(), new_key.as_bytes())
+            .unwrap();
+
+        // The established connection survives the reload
+        let request = JsonRpcRequest::new(RequestId::from(1), "ping".to_string(), None::<Value>);
+        let response = client.send_request(request.unwrap()).await.unwrap();
+        assert_eq!(response.result, Some(json!("ping")));
+
+        // New connections are served the new certificate
+        assert!(
+            WebSocketClientTransport::with_config(&url, trusting(&old_cert))
+                .await
+                .is_err()
+        );
+        let mut client = WebSocketClientTransport::with_config(&url, trusting(&new_cert))
+            .await
+            .unwrap();
+        let request = JsonRpcRequest::new(RequestId::from(2), "ping".to_string(), None::<Value>);
+        assert!(client.send_request(request.unwrap()).await.is_ok());
+
+        server.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_websocket_handshake_and_close_codes() {
+        use tokio_tungstenite::tungstenite::Error;
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = TransportConfig {
+            allowed_origins: Some(vec!["https://app.example.com".to_string()]),
+            max_message_size: Some(1024),
+            ..Default::default()
+        };
+        let mut server =
+            WebSocketServerTransport::with_config(format!("127.0.0.1:{}", port), config);
+        server.start().await.unwrap();
+
+        let url = format!("ws://127.0.0.1:{}", port);
+        let connect = |origin: &'static str, protocol: &'static str| {
+            let mut request = url.as_str().into_client_request().unwrap();
+            let headers = request.headers_mut();
+            headers.insert(ORIGIN, HeaderValue::from_static(origin));
+            headers.insert(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static(protocol));
+            tokio_tungstenite::connect_async(request)
+        };
+
+        let Err(Error::Http(response)) = connect("https://evil.example.com", "mcp").await else {
+            panic!("expected the origin to be refused");
+        };
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let Err(Error::Http(response)) = connect("https://app.example.com", "chat").await else {
+            panic!("expected the subprotocol to be refused");
+        };
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Protocol violations close the connection with a matching close code
+        let cases = [
+            (Message::Binary(vec![1, 2, 3]), CloseCode::Unsupported),
+            (Message::Text("x".repeat(2048)), CloseCode::Size),
+        ];
+        for (message, code) in cases {
+            let (mut client, response) = connect("https://app.example.com", "chat, mcp")
+                .await
+                .unwrap();
+            assert_eq!(
+                response.headers().get(SEC_WEBSOCKET_PROTOCOL).unwrap(),
+                MCP_SUBPROTOCOL
+            );
+
+            client.send(message).await.unwrap();
+            let Some(Ok(Message::Close(Some(frame)))) = client.next().await else {
+                panic!("expected a close frame");
+            };
+            assert_eq!(frame.code, code);
+        }
+
+        server.stop().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_websocket_client_invalid_url() {
         let result = WebSocketClientTransport::new("invalid-url").await;